@@ -0,0 +1,48 @@
+use syn::parse::{Parse, ParseStream, Result};
+use syn::punctuated::Punctuated;
+use syn::{parenthesized, Path, Token};
+
+pub struct Args {
+    // None means the default of Send, Sync, and Send + Sync.
+    pub auto_traits: Option<Vec<Vec<Path>>>,
+}
+
+mod kw {
+    syn::custom_keyword!(auto_traits);
+}
+
+impl Parse for Args {
+    fn parse(input: ParseStream) -> Result<Self> {
+        if input.is_empty() {
+            return Ok(Args { auto_traits: None });
+        }
+
+        if !input.peek(kw::auto_traits) {
+            return Err(input.error("unrecognized argument, expected `auto_traits(...)`"));
+        }
+        input.parse::<kw::auto_traits>()?;
+
+        let content;
+        parenthesized!(content in input);
+        let combinations = Punctuated::<Combination, Token![,]>::parse_terminated(&content)?;
+        let auto_traits = combinations.into_iter().map(|c| c.0).collect();
+
+        input.parse::<Option<Token![,]>>()?;
+        if !input.is_empty() {
+            return Err(input.error("unexpected token after `auto_traits(...)`"));
+        }
+
+        Ok(Args {
+            auto_traits: Some(auto_traits),
+        })
+    }
+}
+
+struct Combination(Vec<Path>);
+
+impl Parse for Combination {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let paths = Punctuated::<Path, Token![+]>::parse_separated_nonempty(input)?;
+        Ok(Combination(paths.into_iter().collect()))
+    }
+}
@@ -0,0 +1,155 @@
+use crate::args::Args;
+use proc_macro2::{Span, TokenStream};
+use quote::{format_ident, quote, ToTokens};
+use std::iter;
+use syn::{
+    parse_quote, Error, GenericParam, Item, ItemTrait, Lifetime, LifetimeParam, Path, Result,
+    TraitItem, TraitItemType, Type, TypeParamBound, WherePredicate,
+};
+
+pub fn serializable(args: Args, input: Item) -> Result<TokenStream> {
+    let Item::Trait(mut input) = input else {
+        return Err(Error::new(
+            Span::call_site(),
+            "#[erased_serde::serializable] can only be applied to a trait",
+        ));
+    };
+
+    if !has_erased_serialize_supertrait(&input) {
+        input
+            .supertraits
+            .push(parse_quote!(::erased_serde::Serialize));
+    }
+
+    let ident = &input.ident;
+    let mut generics = input.generics.clone();
+    generics.params.insert(
+        0,
+        GenericParam::Lifetime(LifetimeParam::new(Lifetime::new(
+            "'erased",
+            Span::call_site(),
+        ))),
+    );
+
+    // Predicates on Self are about the concrete type, and have no place on
+    // the impl for the trait object.
+    let where_clause = generics.make_where_clause();
+    where_clause.predicates = where_clause
+        .predicates
+        .iter()
+        .filter(|predicate| !is_predicate_on_self(predicate))
+        .cloned()
+        .collect();
+
+    let mut type_args = Vec::new();
+    for param in &input.generics.params {
+        type_args.push(match param {
+            GenericParam::Lifetime(param) => param.lifetime.to_token_stream(),
+            GenericParam::Type(param) => param.ident.to_token_stream(),
+            GenericParam::Const(param) => param.ident.to_token_stream(),
+        });
+    }
+
+    // Every associated type needs a binding in the trait object type, so the
+    // impls are generic over all of them.
+    for item in &input.items {
+        if let TraitItem::Type(item) = item {
+            if !is_bindable(item) {
+                continue;
+            }
+            let assoc = &item.ident;
+            let param = format_ident!("__{}", assoc);
+            generics.params.push(parse_quote!(#param));
+            if !item.bounds.is_empty() {
+                let bounds = &item.bounds;
+                generics
+                    .make_where_clause()
+                    .predicates
+                    .push(parse_quote!(#param: #bounds));
+            }
+            type_args.push(quote!(#assoc = #param));
+        }
+    }
+
+    let type_args = if type_args.is_empty() {
+        None
+    } else {
+        Some(quote!(<#(#type_args),*>))
+    };
+
+    let combinations = args.auto_traits.unwrap_or_else(|| {
+        vec![
+            vec![parse_quote!(::erased_serde::__private::Send)],
+            vec![parse_quote!(::erased_serde::__private::Sync)],
+            vec![
+                parse_quote!(::erased_serde::__private::Send),
+                parse_quote!(::erased_serde::__private::Sync),
+            ],
+        ]
+    });
+
+    let (impl_generics, _, where_clause) = generics.split_for_impl();
+    let impls = iter::once(Vec::new())
+        .chain(combinations)
+        .map(|auto_traits: Vec<Path>| {
+            quote! {
+                impl #impl_generics ::erased_serde::__private::serde::Serialize
+                    for dyn #ident #type_args #(+ #auto_traits)* + 'erased
+                #where_clause
+                {
+                    fn serialize<__S>(
+                        &self,
+                        serializer: __S,
+                    ) -> ::erased_serde::__private::Result<__S::Ok, __S::Error>
+                    where
+                        __S: ::erased_serde::__private::serde::Serializer,
+                    {
+                        ::erased_serde::serialize(self, serializer)
+                    }
+                }
+            }
+        });
+
+    Ok(quote! {
+        #input
+        #(#impls)*
+    })
+}
+
+// A bare `Serialize` supertrait can only be erased_serde's, as imported with
+// `use erased_serde::Serialize`, because serde::Serialize would make the trait
+// not dyn compatible.
+fn has_erased_serialize_supertrait(input: &ItemTrait) -> bool {
+    input.supertraits.iter().any(|bound| match bound {
+        TypeParamBound::Trait(bound) => {
+            let segments: Vec<_> = bound.path.segments.iter().map(|s| &s.ident).collect();
+            match segments.as_slice() {
+                [ident] => bound.path.leading_colon.is_none() && *ident == "Serialize",
+                [.., krate, ident] => *krate == "erased_serde" && *ident == "Serialize",
+                [] => false,
+            }
+        }
+        _ => false,
+    })
+}
+
+fn is_predicate_on_self(predicate: &WherePredicate) -> bool {
+    match predicate {
+        WherePredicate::Type(predicate) => match &predicate.bounded_ty {
+            Type::Path(ty) => ty.qself.is_none() && ty.path.is_ident("Self"),
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+// Associated types with their own generic parameters, or that only exist when
+// Self: Sized, are not named in the trait object type.
+fn is_bindable(item: &TraitItemType) -> bool {
+    item.generics.params.is_empty()
+        && !item
+            .generics
+            .where_clause
+            .as_ref()
+            .is_some_and(|where_clause| where_clause.predicates.iter().any(is_predicate_on_self))
+}
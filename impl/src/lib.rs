@@ -0,0 +1,17 @@
+extern crate proc_macro;
+
+mod args;
+mod expand;
+
+use crate::args::Args;
+use proc_macro::TokenStream;
+use syn::parse_macro_input;
+
+#[proc_macro_attribute]
+pub fn serializable(args: TokenStream, input: TokenStream) -> TokenStream {
+    let args = parse_macro_input!(args as Args);
+    let input = parse_macro_input!(input as syn::Item);
+    expand::serializable(args, input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
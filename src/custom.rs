@@ -0,0 +1,3185 @@
+//! Implementing an erased serializer or deserializer directly.
+//!
+//! Ordinarily a `&mut dyn erased_serde::Serializer` is made by erasing some
+//! implementation of Serde's generic `Serializer` trait. Data sinks that are
+//! dynamic by nature, such as the object model of a scripting runtime, can
+//! instead implement the object-safe [`Serializer`] trait of this module
+//! together with its compound-state traits, and then be wrapped in [`Erased`]
+//! to be used anywhere an `erased_serde::Serializer` or a `serde::Serializer`
+//! is expected. Data sources do the same with [`Deserializer`], described
+//! [below](#deserializing).
+//!
+//! # Serializing
+//!
+//! The serializer is its own compound state. A call such as
+//! `erased_serialize_seq` opens a sequence, after which the elements arrive
+//! through [`SerializeSeq::erased_serialize_element`] on the same object until
+//! [`SerializeSeq::erased_end`] closes it.
+//!
+//! ```rust
+//! use erased_serde::custom::{self, Erased};
+//! use erased_serde::{Error, Serialize};
+//! use serde::ser::Error as _;
+//!
+//! // Counts the scalars in a value, refusing to go beyond a fixed depth.
+//! struct Counter {
+//!     scalars: usize,
+//!     depth: usize,
+//! }
+//!
+//! impl Counter {
+//!     fn scalar(&mut self) -> Result<(), Error> {
+//!         self.scalars += 1;
+//!         Ok(())
+//!     }
+//!
+//!     fn open(&mut self) -> Result<(), Error> {
+//!         self.depth += 1;
+//!         if self.depth > 2 {
+//!             return Err(Error::custom("too deeply nested"));
+//!         }
+//!         Ok(())
+//!     }
+//!
+//!     fn close(&mut self) -> Result<(), Error> {
+//!         self.depth -= 1;
+//!         Ok(())
+//!     }
+//!
+//!     fn nested(&mut self, value: &dyn Serialize) -> Result<(), Error> {
+//!         let mut erased = Erased::new(&mut *self);
+//!         value.erased_serialize(&mut erased)?;
+//!         erased.into_result().map(drop)
+//!     }
+//! }
+//!
+//! impl custom::Serializer for Counter {
+//!     fn erased_serialize_bool(&mut self, _v: bool) -> Result<(), Error> { self.scalar() }
+//!     fn erased_serialize_i8(&mut self, _v: i8) -> Result<(), Error> { self.scalar() }
+//!     fn erased_serialize_i16(&mut self, _v: i16) -> Result<(), Error> { self.scalar() }
+//!     fn erased_serialize_i32(&mut self, _v: i32) -> Result<(), Error> { self.scalar() }
+//!     fn erased_serialize_i64(&mut self, _v: i64) -> Result<(), Error> { self.scalar() }
+//!     fn erased_serialize_i128(&mut self, _v: i128) -> Result<(), Error> { self.scalar() }
+//!     fn erased_serialize_u8(&mut self, _v: u8) -> Result<(), Error> { self.scalar() }
+//!     fn erased_serialize_u16(&mut self, _v: u16) -> Result<(), Error> { self.scalar() }
+//!     fn erased_serialize_u32(&mut self, _v: u32) -> Result<(), Error> { self.scalar() }
+//!     fn erased_serialize_u64(&mut self, _v: u64) -> Result<(), Error> { self.scalar() }
+//!     fn erased_serialize_u128(&mut self, _v: u128) -> Result<(), Error> { self.scalar() }
+//!     fn erased_serialize_f32(&mut self, _v: f32) -> Result<(), Error> { self.scalar() }
+//!     fn erased_serialize_f64(&mut self, _v: f64) -> Result<(), Error> { self.scalar() }
+//!     fn erased_serialize_char(&mut self, _v: char) -> Result<(), Error> { self.scalar() }
+//!     fn erased_serialize_str(&mut self, _v: &str) -> Result<(), Error> { self.scalar() }
+//!     fn erased_serialize_bytes(&mut self, _v: &[u8]) -> Result<(), Error> { self.scalar() }
+//!     fn erased_serialize_none(&mut self) -> Result<(), Error> { self.scalar() }
+//!     fn erased_serialize_some(&mut self, value: &dyn Serialize) -> Result<(), Error> { self.nested(value) }
+//!     fn erased_serialize_unit(&mut self) -> Result<(), Error> { self.scalar() }
+//!     fn erased_serialize_unit_struct(&mut self, _name: &'static str) -> Result<(), Error> { self.scalar() }
+//!     fn erased_serialize_unit_variant(&mut self, _name: &'static str, _variant_index: u32, _variant: &'static str) -> Result<(), Error> { self.scalar() }
+//!     fn erased_serialize_newtype_struct(&mut self, _name: &'static str, value: &dyn Serialize) -> Result<(), Error> { self.nested(value) }
+//!     fn erased_serialize_newtype_variant(&mut self, _name: &'static str, _variant_index: u32, _variant: &'static str, value: &dyn Serialize) -> Result<(), Error> { self.nested(value) }
+//!     fn erased_serialize_seq(&mut self, _len: Option<usize>) -> Result<(), Error> { self.open() }
+//!     fn erased_serialize_tuple(&mut self, _len: usize) -> Result<(), Error> { self.open() }
+//!     fn erased_serialize_tuple_struct(&mut self, _name: &'static str, _len: usize) -> Result<(), Error> { self.open() }
+//!     fn erased_serialize_tuple_variant(&mut self, _name: &'static str, _variant_index: u32, _variant: &'static str, _len: usize) -> Result<(), Error> { self.open() }
+//!     fn erased_serialize_map(&mut self, _len: Option<usize>) -> Result<(), Error> { self.open() }
+//!     fn erased_serialize_struct(&mut self, _name: &'static str, _len: usize) -> Result<(), Error> { self.open() }
+//!     fn erased_serialize_struct_variant(&mut self, _name: &'static str, _variant_index: u32, _variant: &'static str, _len: usize) -> Result<(), Error> { self.open() }
+//! }
+//!
+//! impl custom::SerializeSeq for Counter {
+//!     fn erased_serialize_element(&mut self, value: &dyn Serialize) -> Result<(), Error> { self.nested(value) }
+//!     fn erased_end(&mut self) -> Result<(), Error> { self.close() }
+//! }
+//!
+//! impl custom::SerializeTuple for Counter {
+//!     fn erased_serialize_element(&mut self, value: &dyn Serialize) -> Result<(), Error> { self.nested(value) }
+//!     fn erased_end(&mut self) -> Result<(), Error> { self.close() }
+//! }
+//!
+//! impl custom::SerializeTupleStruct for Counter {
+//!     fn erased_serialize_field(&mut self, value: &dyn Serialize) -> Result<(), Error> { self.nested(value) }
+//!     fn erased_end(&mut self) -> Result<(), Error> { self.close() }
+//! }
+//!
+//! impl custom::SerializeTupleVariant for Counter {
+//!     fn erased_serialize_field(&mut self, value: &dyn Serialize) -> Result<(), Error> { self.nested(value) }
+//!     fn erased_end(&mut self) -> Result<(), Error> { self.close() }
+//! }
+//!
+//! impl custom::SerializeMap for Counter {
+//!     fn erased_serialize_key(&mut self, key: &dyn Serialize) -> Result<(), Error> { self.nested(key) }
+//!     fn erased_serialize_value(&mut self, value: &dyn Serialize) -> Result<(), Error> { self.nested(value) }
+//!     fn erased_end(&mut self) -> Result<(), Error> { self.close() }
+//! }
+//!
+//! impl custom::SerializeStruct for Counter {
+//!     fn erased_serialize_field(&mut self, _key: &'static str, value: &dyn Serialize) -> Result<(), Error> { self.nested(value) }
+//!     fn erased_end(&mut self) -> Result<(), Error> { self.close() }
+//! }
+//!
+//! impl custom::SerializeStructVariant for Counter {
+//!     fn erased_serialize_field(&mut self, _key: &'static str, value: &dyn Serialize) -> Result<(), Error> { self.nested(value) }
+//!     fn erased_end(&mut self) -> Result<(), Error> { self.close() }
+//! }
+//!
+//! let value: Box<dyn Serialize> = Box::new(vec![(1, "a"), (2, "b")]);
+//! let mut erased = Erased::new(Counter { scalars: 0, depth: 0 });
+//! value.erased_serialize(&mut erased).unwrap();
+//! assert_eq!(erased.into_result().unwrap().scalars, 4);
+//!
+//! let value: Box<dyn Serialize> = Box::new(vec![vec![vec![0]]]);
+//! let mut erased = Erased::new(Counter { scalars: 0, depth: 0 });
+//! let error = erased_serde::serialize(&value, &mut erased).unwrap_err();
+//! assert_eq!(error.to_string(), "too deeply nested");
+//! ```
+//!
+//! # Deserializing
+//!
+//! A [`Deserializer`] receives the visitor of the `Deserialize` impl being
+//! driven as a [`Visit`]. Calling one of its methods hands the data over and
+//! yields the [`Out`] to return. Sequences, maps and enums are handed over as
+//! [`SeqAccess`], [`MapAccess`] and [`EnumAccess`] implementations, whose
+//! elements are produced from a [`Seed`] in the same way.
+//!
+//! ```rust
+//! use erased_serde::custom::{self, Erased, Out, Seed, Visit};
+//! use erased_serde::Error;
+//! use std::collections::BTreeMap as Map;
+//!
+//! // A value owned by some host runtime.
+//! enum Object {
+//!     Int(i64),
+//!     List(Vec<Object>),
+//! }
+//!
+//! impl<'de> custom::Deserializer<'de> for &Object {
+//!     fn erased_deserialize_any<'a>(&mut self, visitor: Visit<'a, 'de>) -> Result<Out<'a>, Error> {
+//!         match self {
+//!             Object::Int(n) => visitor.visit_i64(*n),
+//!             Object::List(list) => visitor.visit_seq(&mut Elements(list.iter())),
+//!         }
+//!     }
+//! }
+//!
+//! struct Elements<'o>(std::slice::Iter<'o, Object>);
+//!
+//! impl<'de> custom::SeqAccess<'de> for Elements<'_> {
+//!     fn erased_next_element<'a>(&mut self, seed: Seed<'a, 'de>) -> Result<Option<Out<'a>>, Error> {
+//!         match self.0.next() {
+//!             Some(element) => seed.deserialize(&mut Erased::new(element)).map(Some),
+//!             None => Ok(None),
+//!         }
+//!     }
+//! }
+//!
+//! let object = Object::List(vec![Object::Int(1), Object::Int(2)]);
+//! let mut erased = Erased::new(&object);
+//! let deserializer: &mut dyn erased_serde::Deserializer = &mut erased;
+//! let data: Vec<u8> = erased_serde::deserialize(deserializer).unwrap();
+//! assert_eq!(data, [1, 2]);
+//!
+//! let error = erased_serde::deserialize::<Map<u8, u8>>(deserializer).unwrap_err();
+//! assert_eq!(error.to_string(), "invalid type: sequence, expected a map");
+//! ```
+
+use crate::any::Any;
+use crate::error::Error;
+use crate::ser::{ErrorImpl, Serialize};
+use crate::{de, sealed, ser};
+use alloc::boxed::Box;
+#[cfg(feature = "alloc")]
+use alloc::string::String;
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+use core::fmt::{self, Display};
+use core::marker::PhantomData;
+use serde::de::Expected;
+
+// TRAITS //////////////////////////////////////////////////////////////////////
+
+/// An object-safe serializer which can be implemented outside of this crate.
+///
+/// Every method either serializes a complete value or opens a compound value,
+/// whose contents then arrive through the corresponding compound-state trait
+/// implemented by the same object. Errors are reported as
+/// `erased_serde::Error`, which can be created with
+/// `serde::ser::Error::custom`.
+///
+/// Wrap an implementation in [`Erased`] to use it as an
+/// `erased_serde::Serializer`.
+pub trait Serializer:
+    SerializeSeq
+    + SerializeTuple
+    + SerializeTupleStruct
+    + SerializeTupleVariant
+    + SerializeMap
+    + SerializeStruct
+    + SerializeStructVariant
+{
+    fn erased_serialize_bool(&mut self, v: bool) -> Result<(), Error>;
+    fn erased_serialize_i8(&mut self, v: i8) -> Result<(), Error>;
+    fn erased_serialize_i16(&mut self, v: i16) -> Result<(), Error>;
+    fn erased_serialize_i32(&mut self, v: i32) -> Result<(), Error>;
+    fn erased_serialize_i64(&mut self, v: i64) -> Result<(), Error>;
+    fn erased_serialize_i128(&mut self, v: i128) -> Result<(), Error>;
+    fn erased_serialize_u8(&mut self, v: u8) -> Result<(), Error>;
+    fn erased_serialize_u16(&mut self, v: u16) -> Result<(), Error>;
+    fn erased_serialize_u32(&mut self, v: u32) -> Result<(), Error>;
+    fn erased_serialize_u64(&mut self, v: u64) -> Result<(), Error>;
+    fn erased_serialize_u128(&mut self, v: u128) -> Result<(), Error>;
+    fn erased_serialize_f32(&mut self, v: f32) -> Result<(), Error>;
+    fn erased_serialize_f64(&mut self, v: f64) -> Result<(), Error>;
+    fn erased_serialize_char(&mut self, v: char) -> Result<(), Error>;
+    fn erased_serialize_str(&mut self, v: &str) -> Result<(), Error>;
+    fn erased_serialize_bytes(&mut self, v: &[u8]) -> Result<(), Error>;
+    fn erased_serialize_none(&mut self) -> Result<(), Error>;
+    fn erased_serialize_some(&mut self, value: &dyn Serialize) -> Result<(), Error>;
+    fn erased_serialize_unit(&mut self) -> Result<(), Error>;
+    fn erased_serialize_unit_struct(&mut self, name: &'static str) -> Result<(), Error>;
+    fn erased_serialize_unit_variant(
+        &mut self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+    ) -> Result<(), Error>;
+    fn erased_serialize_newtype_struct(
+        &mut self,
+        name: &'static str,
+        value: &dyn Serialize,
+    ) -> Result<(), Error>;
+    fn erased_serialize_newtype_variant(
+        &mut self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        value: &dyn Serialize,
+    ) -> Result<(), Error>;
+    fn erased_serialize_seq(&mut self, len: Option<usize>) -> Result<(), Error>;
+    fn erased_serialize_tuple(&mut self, len: usize) -> Result<(), Error>;
+    fn erased_serialize_tuple_struct(
+        &mut self,
+        name: &'static str,
+        len: usize,
+    ) -> Result<(), Error>;
+    fn erased_serialize_tuple_variant(
+        &mut self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<(), Error>;
+    fn erased_serialize_map(&mut self, len: Option<usize>) -> Result<(), Error>;
+    fn erased_serialize_struct(&mut self, name: &'static str, len: usize) -> Result<(), Error>;
+    fn erased_serialize_struct_variant(
+        &mut self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<(), Error>;
+
+    /// Defaults to true, like Serde's `Serializer::is_human_readable`.
+    fn erased_is_human_readable(&self) -> bool {
+        true
+    }
+}
+
+/// Receives the elements of a sequence opened by
+/// [`Serializer::erased_serialize_seq`].
+pub trait SerializeSeq {
+    fn erased_serialize_element(&mut self, value: &dyn Serialize) -> Result<(), Error>;
+    fn erased_end(&mut self) -> Result<(), Error>;
+}
+
+/// Receives the elements of a tuple opened by
+/// [`Serializer::erased_serialize_tuple`].
+pub trait SerializeTuple {
+    fn erased_serialize_element(&mut self, value: &dyn Serialize) -> Result<(), Error>;
+    fn erased_end(&mut self) -> Result<(), Error>;
+}
+
+/// Receives the fields of a tuple struct opened by
+/// [`Serializer::erased_serialize_tuple_struct`].
+pub trait SerializeTupleStruct {
+    fn erased_serialize_field(&mut self, value: &dyn Serialize) -> Result<(), Error>;
+    fn erased_end(&mut self) -> Result<(), Error>;
+}
+
+/// Receives the fields of a tuple variant opened by
+/// [`Serializer::erased_serialize_tuple_variant`].
+pub trait SerializeTupleVariant {
+    fn erased_serialize_field(&mut self, value: &dyn Serialize) -> Result<(), Error>;
+    fn erased_end(&mut self) -> Result<(), Error>;
+}
+
+/// Receives the entries of a map opened by
+/// [`Serializer::erased_serialize_map`].
+pub trait SerializeMap {
+    fn erased_serialize_key(&mut self, key: &dyn Serialize) -> Result<(), Error>;
+    fn erased_serialize_value(&mut self, value: &dyn Serialize) -> Result<(), Error>;
+    fn erased_serialize_entry(
+        &mut self,
+        key: &dyn Serialize,
+        value: &dyn Serialize,
+    ) -> Result<(), Error> {
+        self.erased_serialize_key(key)?;
+        self.erased_serialize_value(value)
+    }
+    fn erased_end(&mut self) -> Result<(), Error>;
+}
+
+/// Receives the fields of a struct opened by
+/// [`Serializer::erased_serialize_struct`].
+pub trait SerializeStruct {
+    fn erased_serialize_field(
+        &mut self,
+        key: &'static str,
+        value: &dyn Serialize,
+    ) -> Result<(), Error>;
+    fn erased_skip_field(&mut self, key: &'static str) -> Result<(), Error> {
+        let _ = key;
+        Ok(())
+    }
+    fn erased_end(&mut self) -> Result<(), Error>;
+}
+
+/// Receives the fields of a struct variant opened by
+/// [`Serializer::erased_serialize_struct_variant`].
+pub trait SerializeStructVariant {
+    fn erased_serialize_field(
+        &mut self,
+        key: &'static str,
+        value: &dyn Serialize,
+    ) -> Result<(), Error>;
+    fn erased_skip_field(&mut self, key: &'static str) -> Result<(), Error> {
+        let _ = key;
+        Ok(())
+    }
+    fn erased_end(&mut self) -> Result<(), Error>;
+}
+
+// ADAPTER /////////////////////////////////////////////////////////////////////
+
+/// An implementation of [`custom::Serializer`][Serializer] or
+/// [`custom::Deserializer`][Deserializer] usable as an
+/// `erased_serde::Serializer` or `erased_serde::Deserializer`.
+///
+/// A wrapped serializer can also be used as a `serde::Serializer`, by way of
+/// `&mut Erased<S>`. When it is instead driven through `&mut dyn
+/// erased_serde::Serializer`, an error returned by a method that produces no
+/// compound state, such as `erased_end`, is held here instead of being
+/// returned to the caller, in the same way that
+/// [`ErasedSerializer`][crate::ErasedSerializer] holds the output of the
+/// serializer it wraps. Use [`into_result`][Erased::into_result] to retrieve
+/// it.
+pub struct Erased<T> {
+    inner: T,
+    error: Option<Error>,
+}
+
+impl<T> Erased<T> {
+    pub fn new(inner: T) -> Self {
+        Erased { inner, error: None }
+    }
+
+    /// Access the wrapped serializer or deserializer.
+    pub fn get_mut(&mut self) -> &mut T {
+        &mut self.inner
+    }
+}
+
+impl<S> Erased<S>
+where
+    S: Serializer,
+{
+    /// Consume the adapter, returning the wrapped serializer if no error has
+    /// occurred.
+    pub fn into_result(self) -> Result<S, Error> {
+        match self.error {
+            Some(error) => Err(error),
+            None => Ok(self.inner),
+        }
+    }
+
+    fn keep(&mut self, result: Result<(), Error>) {
+        if let Err(error) = result {
+            self.error = Some(error);
+        }
+    }
+
+    fn short_circuit(&mut self, result: Result<(), Error>) -> Result<(), ErrorImpl> {
+        result.map_err(|error| {
+            self.error = Some(error);
+            ErrorImpl::ShortCircuit
+        })
+    }
+}
+
+impl<S> ser::Serializer for Erased<S>
+where
+    S: Serializer,
+{
+    fn erased_serialize_bool(&mut self, v: bool) {
+        let result = self.inner.erased_serialize_bool(v);
+        self.keep(result);
+    }
+
+    fn erased_serialize_i8(&mut self, v: i8) {
+        let result = self.inner.erased_serialize_i8(v);
+        self.keep(result);
+    }
+
+    fn erased_serialize_i16(&mut self, v: i16) {
+        let result = self.inner.erased_serialize_i16(v);
+        self.keep(result);
+    }
+
+    fn erased_serialize_i32(&mut self, v: i32) {
+        let result = self.inner.erased_serialize_i32(v);
+        self.keep(result);
+    }
+
+    fn erased_serialize_i64(&mut self, v: i64) {
+        let result = self.inner.erased_serialize_i64(v);
+        self.keep(result);
+    }
+
+    fn erased_serialize_i128(&mut self, v: i128) {
+        let result = self.inner.erased_serialize_i128(v);
+        self.keep(result);
+    }
+
+    fn erased_serialize_u8(&mut self, v: u8) {
+        let result = self.inner.erased_serialize_u8(v);
+        self.keep(result);
+    }
+
+    fn erased_serialize_u16(&mut self, v: u16) {
+        let result = self.inner.erased_serialize_u16(v);
+        self.keep(result);
+    }
+
+    fn erased_serialize_u32(&mut self, v: u32) {
+        let result = self.inner.erased_serialize_u32(v);
+        self.keep(result);
+    }
+
+    fn erased_serialize_u64(&mut self, v: u64) {
+        let result = self.inner.erased_serialize_u64(v);
+        self.keep(result);
+    }
+
+    fn erased_serialize_u128(&mut self, v: u128) {
+        let result = self.inner.erased_serialize_u128(v);
+        self.keep(result);
+    }
+
+    fn erased_serialize_f32(&mut self, v: f32) {
+        let result = self.inner.erased_serialize_f32(v);
+        self.keep(result);
+    }
+
+    fn erased_serialize_f64(&mut self, v: f64) {
+        let result = self.inner.erased_serialize_f64(v);
+        self.keep(result);
+    }
+
+    fn erased_serialize_char(&mut self, v: char) {
+        let result = self.inner.erased_serialize_char(v);
+        self.keep(result);
+    }
+
+    fn erased_serialize_str(&mut self, v: &str) {
+        let result = self.inner.erased_serialize_str(v);
+        self.keep(result);
+    }
+
+    fn erased_serialize_bytes(&mut self, v: &[u8]) {
+        let result = self.inner.erased_serialize_bytes(v);
+        self.keep(result);
+    }
+
+    fn erased_serialize_none(&mut self) {
+        let result = self.inner.erased_serialize_none();
+        self.keep(result);
+    }
+
+    fn erased_serialize_some(&mut self, value: &dyn Serialize) {
+        let result = self.inner.erased_serialize_some(value);
+        self.keep(result);
+    }
+
+    fn erased_serialize_unit(&mut self) {
+        let result = self.inner.erased_serialize_unit();
+        self.keep(result);
+    }
+
+    fn erased_serialize_unit_struct(&mut self, name: &'static str) {
+        let result = self.inner.erased_serialize_unit_struct(name);
+        self.keep(result);
+    }
+
+    fn erased_serialize_unit_variant(
+        &mut self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+    ) {
+        let result = self
+            .inner
+            .erased_serialize_unit_variant(name, variant_index, variant);
+        self.keep(result);
+    }
+
+    fn erased_serialize_newtype_struct(&mut self, name: &'static str, value: &dyn Serialize) {
+        let result = self.inner.erased_serialize_newtype_struct(name, value);
+        self.keep(result);
+    }
+
+    fn erased_serialize_newtype_variant(
+        &mut self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        value: &dyn Serialize,
+    ) {
+        let result =
+            self.inner
+                .erased_serialize_newtype_variant(name, variant_index, variant, value);
+        self.keep(result);
+    }
+
+    fn erased_serialize_seq(
+        &mut self,
+        len: Option<usize>,
+    ) -> Result<&mut dyn ser::SerializeSeq, ErrorImpl> {
+        let result = self.inner.erased_serialize_seq(len);
+        self.short_circuit(result)?;
+        Ok(self)
+    }
+
+    fn erased_serialize_tuple(
+        &mut self,
+        len: usize,
+    ) -> Result<&mut dyn ser::SerializeTuple, ErrorImpl> {
+        let result = self.inner.erased_serialize_tuple(len);
+        self.short_circuit(result)?;
+        Ok(self)
+    }
+
+    fn erased_serialize_tuple_struct(
+        &mut self,
+        name: &'static str,
+        len: usize,
+    ) -> Result<&mut dyn ser::SerializeTupleStruct, ErrorImpl> {
+        let result = self.inner.erased_serialize_tuple_struct(name, len);
+        self.short_circuit(result)?;
+        Ok(self)
+    }
+
+    fn erased_serialize_tuple_variant(
+        &mut self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<&mut dyn ser::SerializeTupleVariant, ErrorImpl> {
+        let result = self
+            .inner
+            .erased_serialize_tuple_variant(name, variant_index, variant, len);
+        self.short_circuit(result)?;
+        Ok(self)
+    }
+
+    fn erased_serialize_map(
+        &mut self,
+        len: Option<usize>,
+    ) -> Result<&mut dyn ser::SerializeMap, ErrorImpl> {
+        let result = self.inner.erased_serialize_map(len);
+        self.short_circuit(result)?;
+        Ok(self)
+    }
+
+    fn erased_serialize_struct(
+        &mut self,
+        name: &'static str,
+        len: usize,
+    ) -> Result<&mut dyn ser::SerializeStruct, ErrorImpl> {
+        let result = self.inner.erased_serialize_struct(name, len);
+        self.short_circuit(result)?;
+        Ok(self)
+    }
+
+    fn erased_serialize_struct_variant(
+        &mut self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<&mut dyn ser::SerializeStructVariant, ErrorImpl> {
+        let result = self
+            .inner
+            .erased_serialize_struct_variant(name, variant_index, variant, len);
+        self.short_circuit(result)?;
+        Ok(self)
+    }
+
+    fn erased_is_human_readable(&self) -> bool {
+        self.inner.erased_is_human_readable()
+    }
+
+    fn erased_display_error(&self) -> &dyn Display {
+        match &self.error {
+            Some(error) => error,
+            None => unreachable!(),
+        }
+    }
+
+    fn erased_preserved_error(&mut self) -> Option<Error> {
+        self.error.take()
+    }
+
+    fn erased_failed(&self) -> bool {
+        self.error.is_some()
+    }
+
+    fn erased_open_seq(&mut self, len: Option<usize>) -> Result<&mut dyn ser::SerializeSeq, Error> {
+        let error = ser::Serializer::erased_serialize_seq(self, len).err();
+        ser::opened(self, error)?;
+        Ok(self)
+    }
+
+    fn erased_open_tuple(&mut self, len: usize) -> Result<&mut dyn ser::SerializeTuple, Error> {
+        let error = ser::Serializer::erased_serialize_tuple(self, len).err();
+        ser::opened(self, error)?;
+        Ok(self)
+    }
+
+    fn erased_open_tuple_struct(
+        &mut self,
+        name: &'static str,
+        len: usize,
+    ) -> Result<&mut dyn ser::SerializeTupleStruct, Error> {
+        let error = ser::Serializer::erased_serialize_tuple_struct(self, name, len).err();
+        ser::opened(self, error)?;
+        Ok(self)
+    }
+
+    fn erased_open_tuple_variant(
+        &mut self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<&mut dyn ser::SerializeTupleVariant, Error> {
+        let error = ser::Serializer::erased_serialize_tuple_variant(
+            self,
+            name,
+            variant_index,
+            variant,
+            len,
+        )
+        .err();
+        ser::opened(self, error)?;
+        Ok(self)
+    }
+
+    fn erased_open_map(&mut self, len: Option<usize>) -> Result<&mut dyn ser::SerializeMap, Error> {
+        let error = ser::Serializer::erased_serialize_map(self, len).err();
+        ser::opened(self, error)?;
+        Ok(self)
+    }
+
+    fn erased_open_struct(
+        &mut self,
+        name: &'static str,
+        len: usize,
+    ) -> Result<&mut dyn ser::SerializeStruct, Error> {
+        let error = ser::Serializer::erased_serialize_struct(self, name, len).err();
+        ser::opened(self, error)?;
+        Ok(self)
+    }
+
+    fn erased_open_struct_variant(
+        &mut self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<&mut dyn ser::SerializeStructVariant, Error> {
+        let error = ser::Serializer::erased_serialize_struct_variant(
+            self,
+            name,
+            variant_index,
+            variant,
+            len,
+        )
+        .err();
+        ser::opened(self, error)?;
+        Ok(self)
+    }
+
+    fn erased_as_serializer(&mut self) -> &mut dyn ser::Serializer {
+        self
+    }
+}
+
+impl<S> sealed::serializer::Sealed for Erased<S> where S: Serializer {}
+
+impl<S> ser::SerializeSeq for Erased<S>
+where
+    S: Serializer,
+{
+    fn erased_serialize_element(&mut self, value: &dyn Serialize) -> Result<(), ErrorImpl> {
+        let result = SerializeSeq::erased_serialize_element(&mut self.inner, value);
+        self.short_circuit(result)
+    }
+
+    fn erased_end(&mut self) {
+        let result = SerializeSeq::erased_end(&mut self.inner);
+        self.keep(result);
+    }
+}
+
+impl<S> ser::SerializeTuple for Erased<S>
+where
+    S: Serializer,
+{
+    fn erased_serialize_element(&mut self, value: &dyn Serialize) -> Result<(), ErrorImpl> {
+        let result = SerializeTuple::erased_serialize_element(&mut self.inner, value);
+        self.short_circuit(result)
+    }
+
+    fn erased_end(&mut self) {
+        let result = SerializeTuple::erased_end(&mut self.inner);
+        self.keep(result);
+    }
+}
+
+impl<S> ser::SerializeTupleStruct for Erased<S>
+where
+    S: Serializer,
+{
+    fn erased_serialize_field(&mut self, value: &dyn Serialize) -> Result<(), ErrorImpl> {
+        let result = SerializeTupleStruct::erased_serialize_field(&mut self.inner, value);
+        self.short_circuit(result)
+    }
+
+    fn erased_end(&mut self) {
+        let result = SerializeTupleStruct::erased_end(&mut self.inner);
+        self.keep(result);
+    }
+}
+
+impl<S> ser::SerializeTupleVariant for Erased<S>
+where
+    S: Serializer,
+{
+    fn erased_serialize_field(&mut self, value: &dyn Serialize) -> Result<(), ErrorImpl> {
+        let result = SerializeTupleVariant::erased_serialize_field(&mut self.inner, value);
+        self.short_circuit(result)
+    }
+
+    fn erased_end(&mut self) {
+        let result = SerializeTupleVariant::erased_end(&mut self.inner);
+        self.keep(result);
+    }
+}
+
+impl<S> ser::SerializeMap for Erased<S>
+where
+    S: Serializer,
+{
+    fn erased_serialize_key(&mut self, key: &dyn Serialize) -> Result<(), ErrorImpl> {
+        let result = SerializeMap::erased_serialize_key(&mut self.inner, key);
+        self.short_circuit(result)
+    }
+
+    fn erased_serialize_value(&mut self, value: &dyn Serialize) -> Result<(), ErrorImpl> {
+        let result = SerializeMap::erased_serialize_value(&mut self.inner, value);
+        self.short_circuit(result)
+    }
+
+    fn erased_serialize_entry(
+        &mut self,
+        key: &dyn Serialize,
+        value: &dyn Serialize,
+    ) -> Result<(), ErrorImpl> {
+        let result = SerializeMap::erased_serialize_entry(&mut self.inner, key, value);
+        self.short_circuit(result)
+    }
+
+    fn erased_end(&mut self) {
+        let result = SerializeMap::erased_end(&mut self.inner);
+        self.keep(result);
+    }
+}
+
+impl<S> ser::SerializeStruct for Erased<S>
+where
+    S: Serializer,
+{
+    fn erased_serialize_field(
+        &mut self,
+        key: &'static str,
+        value: &dyn Serialize,
+    ) -> Result<(), ErrorImpl> {
+        let result = SerializeStruct::erased_serialize_field(&mut self.inner, key, value);
+        self.short_circuit(result)
+    }
+
+    fn erased_skip_field(&mut self, key: &'static str) -> Result<(), ErrorImpl> {
+        let result = SerializeStruct::erased_skip_field(&mut self.inner, key);
+        self.short_circuit(result)
+    }
+
+    fn erased_end(&mut self) {
+        let result = SerializeStruct::erased_end(&mut self.inner);
+        self.keep(result);
+    }
+}
+
+impl<S> ser::SerializeStructVariant for Erased<S>
+where
+    S: Serializer,
+{
+    fn erased_serialize_field(
+        &mut self,
+        key: &'static str,
+        value: &dyn Serialize,
+    ) -> Result<(), ErrorImpl> {
+        let result = SerializeStructVariant::erased_serialize_field(&mut self.inner, key, value);
+        self.short_circuit(result)
+    }
+
+    fn erased_skip_field(&mut self, key: &'static str) -> Result<(), ErrorImpl> {
+        let result = SerializeStructVariant::erased_skip_field(&mut self.inner, key);
+        self.short_circuit(result)
+    }
+
+    fn erased_end(&mut self) {
+        let result = SerializeStructVariant::erased_end(&mut self.inner);
+        self.keep(result);
+    }
+}
+
+// IMPL SERDE FOR CUSTOM ///////////////////////////////////////////////////////
+
+impl<S> serde::Serializer for &mut Erased<S>
+where
+    S: Serializer,
+{
+    type Ok = ();
+    type Error = Error;
+    type SerializeSeq = Self;
+    type SerializeTuple = Self;
+    type SerializeTupleStruct = Self;
+    type SerializeTupleVariant = Self;
+    type SerializeMap = Self;
+    type SerializeStruct = Self;
+    type SerializeStructVariant = Self;
+
+    fn serialize_bool(self, v: bool) -> Result<(), Error> {
+        self.inner.erased_serialize_bool(v)
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<(), Error> {
+        self.inner.erased_serialize_i8(v)
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<(), Error> {
+        self.inner.erased_serialize_i16(v)
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<(), Error> {
+        self.inner.erased_serialize_i32(v)
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<(), Error> {
+        self.inner.erased_serialize_i64(v)
+    }
+
+    fn serialize_i128(self, v: i128) -> Result<(), Error> {
+        self.inner.erased_serialize_i128(v)
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<(), Error> {
+        self.inner.erased_serialize_u8(v)
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<(), Error> {
+        self.inner.erased_serialize_u16(v)
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<(), Error> {
+        self.inner.erased_serialize_u32(v)
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<(), Error> {
+        self.inner.erased_serialize_u64(v)
+    }
+
+    fn serialize_u128(self, v: u128) -> Result<(), Error> {
+        self.inner.erased_serialize_u128(v)
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<(), Error> {
+        self.inner.erased_serialize_f32(v)
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<(), Error> {
+        self.inner.erased_serialize_f64(v)
+    }
+
+    fn serialize_char(self, v: char) -> Result<(), Error> {
+        self.inner.erased_serialize_char(v)
+    }
+
+    fn serialize_str(self, v: &str) -> Result<(), Error> {
+        self.inner.erased_serialize_str(v)
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<(), Error> {
+        self.inner.erased_serialize_bytes(v)
+    }
+
+    fn serialize_none(self) -> Result<(), Error> {
+        self.inner.erased_serialize_none()
+    }
+
+    fn serialize_some<T>(self, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + serde::Serialize,
+    {
+        self.inner.erased_serialize_some(&value)
+    }
+
+    fn serialize_unit(self) -> Result<(), Error> {
+        self.inner.erased_serialize_unit()
+    }
+
+    fn serialize_unit_struct(self, name: &'static str) -> Result<(), Error> {
+        self.inner.erased_serialize_unit_struct(name)
+    }
+
+    fn serialize_unit_variant(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+    ) -> Result<(), Error> {
+        self.inner
+            .erased_serialize_unit_variant(name, variant_index, variant)
+    }
+
+    fn serialize_newtype_struct<T>(self, name: &'static str, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + serde::Serialize,
+    {
+        self.inner.erased_serialize_newtype_struct(name, &value)
+    }
+
+    fn serialize_newtype_variant<T>(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<(), Error>
+    where
+        T: ?Sized + serde::Serialize,
+    {
+        self.inner
+            .erased_serialize_newtype_variant(name, variant_index, variant, &value)
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<Self, Error> {
+        self.inner.erased_serialize_seq(len)?;
+        Ok(self)
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<Self, Error> {
+        self.inner.erased_serialize_tuple(len)?;
+        Ok(self)
+    }
+
+    fn serialize_tuple_struct(self, name: &'static str, len: usize) -> Result<Self, Error> {
+        self.inner.erased_serialize_tuple_struct(name, len)?;
+        Ok(self)
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self, Error> {
+        self.inner
+            .erased_serialize_tuple_variant(name, variant_index, variant, len)?;
+        Ok(self)
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<Self, Error> {
+        self.inner.erased_serialize_map(len)?;
+        Ok(self)
+    }
+
+    fn serialize_struct(self, name: &'static str, len: usize) -> Result<Self, Error> {
+        self.inner.erased_serialize_struct(name, len)?;
+        Ok(self)
+    }
+
+    fn serialize_struct_variant(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self, Error> {
+        self.inner
+            .erased_serialize_struct_variant(name, variant_index, variant, len)?;
+        Ok(self)
+    }
+
+    fn is_human_readable(&self) -> bool {
+        self.inner.erased_is_human_readable()
+    }
+}
+
+impl<S> serde::ser::SerializeSeq for &mut Erased<S>
+where
+    S: Serializer,
+{
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + serde::Serialize,
+    {
+        SerializeSeq::erased_serialize_element(&mut self.inner, &value)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        SerializeSeq::erased_end(&mut self.inner)
+    }
+}
+
+impl<S> serde::ser::SerializeTuple for &mut Erased<S>
+where
+    S: Serializer,
+{
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + serde::Serialize,
+    {
+        SerializeTuple::erased_serialize_element(&mut self.inner, &value)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        SerializeTuple::erased_end(&mut self.inner)
+    }
+}
+
+impl<S> serde::ser::SerializeTupleStruct for &mut Erased<S>
+where
+    S: Serializer,
+{
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + serde::Serialize,
+    {
+        SerializeTupleStruct::erased_serialize_field(&mut self.inner, &value)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        SerializeTupleStruct::erased_end(&mut self.inner)
+    }
+}
+
+impl<S> serde::ser::SerializeTupleVariant for &mut Erased<S>
+where
+    S: Serializer,
+{
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + serde::Serialize,
+    {
+        SerializeTupleVariant::erased_serialize_field(&mut self.inner, &value)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        SerializeTupleVariant::erased_end(&mut self.inner)
+    }
+}
+
+impl<S> serde::ser::SerializeMap for &mut Erased<S>
+where
+    S: Serializer,
+{
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_key<T>(&mut self, key: &T) -> Result<(), Error>
+    where
+        T: ?Sized + serde::Serialize,
+    {
+        SerializeMap::erased_serialize_key(&mut self.inner, &key)
+    }
+
+    fn serialize_value<T>(&mut self, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + serde::Serialize,
+    {
+        SerializeMap::erased_serialize_value(&mut self.inner, &value)
+    }
+
+    fn serialize_entry<K, V>(&mut self, key: &K, value: &V) -> Result<(), Error>
+    where
+        K: ?Sized + serde::Serialize,
+        V: ?Sized + serde::Serialize,
+    {
+        SerializeMap::erased_serialize_entry(&mut self.inner, &key, &value)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        SerializeMap::erased_end(&mut self.inner)
+    }
+}
+
+impl<S> serde::ser::SerializeStruct for &mut Erased<S>
+where
+    S: Serializer,
+{
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + serde::Serialize,
+    {
+        SerializeStruct::erased_serialize_field(&mut self.inner, key, &value)
+    }
+
+    fn skip_field(&mut self, key: &'static str) -> Result<(), Error> {
+        SerializeStruct::erased_skip_field(&mut self.inner, key)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        SerializeStruct::erased_end(&mut self.inner)
+    }
+}
+
+impl<S> serde::ser::SerializeStructVariant for &mut Erased<S>
+where
+    S: Serializer,
+{
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + serde::Serialize,
+    {
+        SerializeStructVariant::erased_serialize_field(&mut self.inner, key, &value)
+    }
+
+    fn skip_field(&mut self, key: &'static str) -> Result<(), Error> {
+        SerializeStructVariant::erased_skip_field(&mut self.inner, key)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        SerializeStructVariant::erased_end(&mut self.inner)
+    }
+}
+
+// DESERIALIZER TRAITS /////////////////////////////////////////////////////////
+
+/// An object-safe deserializer which can be implemented outside of this crate.
+///
+/// Each method is handed the visitor of the `Deserialize` impl being driven,
+/// in the form of a [`Visit`], and must return the [`Out`] which that visitor
+/// produces. Every method other than `erased_deserialize_any` forwards to it
+/// by default, which suits self-describing data.
+///
+/// Wrap an implementation in [`Erased`] to use it as an
+/// `erased_serde::Deserializer`.
+pub trait Deserializer<'de> {
+    fn erased_deserialize_any<'a>(&mut self, visitor: Visit<'a, 'de>) -> Result<Out<'a>, Error>;
+
+    fn erased_deserialize_bool<'a>(&mut self, visitor: Visit<'a, 'de>) -> Result<Out<'a>, Error> {
+        self.erased_deserialize_any(visitor)
+    }
+
+    fn erased_deserialize_i8<'a>(&mut self, visitor: Visit<'a, 'de>) -> Result<Out<'a>, Error> {
+        self.erased_deserialize_any(visitor)
+    }
+
+    fn erased_deserialize_i16<'a>(&mut self, visitor: Visit<'a, 'de>) -> Result<Out<'a>, Error> {
+        self.erased_deserialize_any(visitor)
+    }
+
+    fn erased_deserialize_i32<'a>(&mut self, visitor: Visit<'a, 'de>) -> Result<Out<'a>, Error> {
+        self.erased_deserialize_any(visitor)
+    }
+
+    fn erased_deserialize_i64<'a>(&mut self, visitor: Visit<'a, 'de>) -> Result<Out<'a>, Error> {
+        self.erased_deserialize_any(visitor)
+    }
+
+    fn erased_deserialize_i128<'a>(&mut self, visitor: Visit<'a, 'de>) -> Result<Out<'a>, Error> {
+        self.erased_deserialize_any(visitor)
+    }
+
+    fn erased_deserialize_u8<'a>(&mut self, visitor: Visit<'a, 'de>) -> Result<Out<'a>, Error> {
+        self.erased_deserialize_any(visitor)
+    }
+
+    fn erased_deserialize_u16<'a>(&mut self, visitor: Visit<'a, 'de>) -> Result<Out<'a>, Error> {
+        self.erased_deserialize_any(visitor)
+    }
+
+    fn erased_deserialize_u32<'a>(&mut self, visitor: Visit<'a, 'de>) -> Result<Out<'a>, Error> {
+        self.erased_deserialize_any(visitor)
+    }
+
+    fn erased_deserialize_u64<'a>(&mut self, visitor: Visit<'a, 'de>) -> Result<Out<'a>, Error> {
+        self.erased_deserialize_any(visitor)
+    }
+
+    fn erased_deserialize_u128<'a>(&mut self, visitor: Visit<'a, 'de>) -> Result<Out<'a>, Error> {
+        self.erased_deserialize_any(visitor)
+    }
+
+    fn erased_deserialize_f32<'a>(&mut self, visitor: Visit<'a, 'de>) -> Result<Out<'a>, Error> {
+        self.erased_deserialize_any(visitor)
+    }
+
+    fn erased_deserialize_f64<'a>(&mut self, visitor: Visit<'a, 'de>) -> Result<Out<'a>, Error> {
+        self.erased_deserialize_any(visitor)
+    }
+
+    fn erased_deserialize_char<'a>(&mut self, visitor: Visit<'a, 'de>) -> Result<Out<'a>, Error> {
+        self.erased_deserialize_any(visitor)
+    }
+
+    fn erased_deserialize_str<'a>(&mut self, visitor: Visit<'a, 'de>) -> Result<Out<'a>, Error> {
+        self.erased_deserialize_any(visitor)
+    }
+
+    fn erased_deserialize_string<'a>(&mut self, visitor: Visit<'a, 'de>) -> Result<Out<'a>, Error> {
+        self.erased_deserialize_any(visitor)
+    }
+
+    fn erased_deserialize_bytes<'a>(&mut self, visitor: Visit<'a, 'de>) -> Result<Out<'a>, Error> {
+        self.erased_deserialize_any(visitor)
+    }
+
+    fn erased_deserialize_byte_buf<'a>(
+        &mut self,
+        visitor: Visit<'a, 'de>,
+    ) -> Result<Out<'a>, Error> {
+        self.erased_deserialize_any(visitor)
+    }
+
+    fn erased_deserialize_option<'a>(&mut self, visitor: Visit<'a, 'de>) -> Result<Out<'a>, Error> {
+        self.erased_deserialize_any(visitor)
+    }
+
+    fn erased_deserialize_unit<'a>(&mut self, visitor: Visit<'a, 'de>) -> Result<Out<'a>, Error> {
+        self.erased_deserialize_any(visitor)
+    }
+
+    fn erased_deserialize_unit_struct<'a>(
+        &mut self,
+        name: &'static str,
+        visitor: Visit<'a, 'de>,
+    ) -> Result<Out<'a>, Error> {
+        let _ = name;
+        self.erased_deserialize_any(visitor)
+    }
+
+    fn erased_deserialize_newtype_struct<'a>(
+        &mut self,
+        name: &'static str,
+        visitor: Visit<'a, 'de>,
+    ) -> Result<Out<'a>, Error> {
+        let _ = name;
+        self.erased_deserialize_any(visitor)
+    }
+
+    fn erased_deserialize_seq<'a>(&mut self, visitor: Visit<'a, 'de>) -> Result<Out<'a>, Error> {
+        self.erased_deserialize_any(visitor)
+    }
+
+    fn erased_deserialize_tuple<'a>(
+        &mut self,
+        len: usize,
+        visitor: Visit<'a, 'de>,
+    ) -> Result<Out<'a>, Error> {
+        let _ = len;
+        self.erased_deserialize_any(visitor)
+    }
+
+    fn erased_deserialize_tuple_struct<'a>(
+        &mut self,
+        name: &'static str,
+        len: usize,
+        visitor: Visit<'a, 'de>,
+    ) -> Result<Out<'a>, Error> {
+        let _ = name;
+        let _ = len;
+        self.erased_deserialize_any(visitor)
+    }
+
+    fn erased_deserialize_map<'a>(&mut self, visitor: Visit<'a, 'de>) -> Result<Out<'a>, Error> {
+        self.erased_deserialize_any(visitor)
+    }
+
+    fn erased_deserialize_struct<'a>(
+        &mut self,
+        name: &'static str,
+        fields: &'static [&'static str],
+        visitor: Visit<'a, 'de>,
+    ) -> Result<Out<'a>, Error> {
+        let _ = name;
+        let _ = fields;
+        self.erased_deserialize_any(visitor)
+    }
+
+    fn erased_deserialize_identifier<'a>(
+        &mut self,
+        visitor: Visit<'a, 'de>,
+    ) -> Result<Out<'a>, Error> {
+        self.erased_deserialize_any(visitor)
+    }
+
+    fn erased_deserialize_enum<'a>(
+        &mut self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: Visit<'a, 'de>,
+    ) -> Result<Out<'a>, Error> {
+        let _ = name;
+        let _ = variants;
+        self.erased_deserialize_any(visitor)
+    }
+
+    fn erased_deserialize_ignored_any<'a>(
+        &mut self,
+        visitor: Visit<'a, 'de>,
+    ) -> Result<Out<'a>, Error> {
+        self.erased_deserialize_any(visitor)
+    }
+
+    /// Defaults to true, like Serde's `Deserializer::is_human_readable`.
+    fn erased_is_human_readable(&self) -> bool {
+        true
+    }
+}
+
+/// Provides the elements of a sequence to [`Visit::visit_seq`].
+pub trait SeqAccess<'de> {
+    fn erased_next_element<'a>(&mut self, seed: Seed<'a, 'de>) -> Result<Option<Out<'a>>, Error>;
+    fn erased_size_hint(&self) -> Option<usize> {
+        None
+    }
+}
+
+/// Provides the entries of a map to [`Visit::visit_map`].
+pub trait MapAccess<'de> {
+    fn erased_next_key<'a>(&mut self, seed: Seed<'a, 'de>) -> Result<Option<Out<'a>>, Error>;
+    fn erased_next_value<'a>(&mut self, seed: Seed<'a, 'de>) -> Result<Out<'a>, Error>;
+    fn erased_size_hint(&self) -> Option<usize> {
+        None
+    }
+}
+
+/// Provides an enum variant to [`Visit::visit_enum`].
+///
+/// The variant's identifier is requested first through
+/// `erased_variant_seed`, followed by exactly one of the remaining methods
+/// for its content.
+pub trait EnumAccess<'de> {
+    fn erased_variant_seed<'a>(&mut self, seed: Seed<'a, 'de>) -> Result<Out<'a>, Error>;
+    fn erased_unit_variant(&mut self) -> Result<(), Error>;
+    fn erased_newtype_variant_seed<'a>(&mut self, seed: Seed<'a, 'de>) -> Result<Out<'a>, Error>;
+    fn erased_tuple_variant<'a>(
+        &mut self,
+        len: usize,
+        visitor: Visit<'a, 'de>,
+    ) -> Result<Out<'a>, Error>;
+    fn erased_struct_variant<'a>(
+        &mut self,
+        fields: &'static [&'static str],
+        visitor: Visit<'a, 'de>,
+    ) -> Result<Out<'a>, Error>;
+}
+
+// HANDLES /////////////////////////////////////////////////////////////////////
+
+// Invariant in 'a. Each trait method above is generic over 'a, so inside it the
+// only Out<'a> in existence is whichever the Visit<'a> or Seed<'a> it was given
+// produces. An Out from some other visitor cannot be returned in its place.
+type Brand<'a> = PhantomData<fn(&'a ()) -> &'a ()>;
+
+/// The visitor passed to a [`custom::Deserializer`][Deserializer].
+///
+/// Calling one of its methods consumes it, drives the underlying visitor, and
+/// produces the [`Out`] to be returned. The `Out<'a>` carries the visitor's
+/// value and can only be returned from the method that received this `Visit`,
+/// so every method below is a way of finishing that call. It also implements
+/// `serde::de::Expected` for use in error messages.
+pub struct Visit<'a, 'de> {
+    visitor: &'a mut dyn de::Visitor<'de>,
+    brand: Brand<'a>,
+}
+
+/// The seed passed to a [`SeqAccess`], [`MapAccess`] or [`EnumAccess`].
+pub struct Seed<'a, 'de> {
+    seed: &'a mut dyn de::DeserializeSeed<'de>,
+    brand: Brand<'a>,
+}
+
+/// The value produced by a [`Visit`] or [`Seed`].
+///
+/// It can only be returned from the method which received that `Visit` or
+/// `Seed`.
+pub struct Out<'a> {
+    out: de::Out,
+    brand: Brand<'a>,
+}
+
+impl Out<'_> {
+    fn new(out: de::Out) -> Self {
+        Out {
+            out,
+            brand: PhantomData,
+        }
+    }
+
+    fn into_inner(self) -> de::Out {
+        self.out
+    }
+}
+
+impl<'a, 'de> Visit<'a, 'de> {
+    fn new(visitor: &'a mut dyn de::Visitor<'de>) -> Self {
+        Visit {
+            visitor,
+            brand: PhantomData,
+        }
+    }
+
+    /// Hand the visitor a `bool`.
+    pub fn visit_bool(self, v: bool) -> Result<Out<'a>, Error> {
+        self.visitor.erased_visit_bool(v).map(Out::new)
+    }
+
+    /// Hand the visitor an `i8`.
+    pub fn visit_i8(self, v: i8) -> Result<Out<'a>, Error> {
+        self.visitor.erased_visit_i8(v).map(Out::new)
+    }
+
+    /// Hand the visitor an `i16`.
+    pub fn visit_i16(self, v: i16) -> Result<Out<'a>, Error> {
+        self.visitor.erased_visit_i16(v).map(Out::new)
+    }
+
+    /// Hand the visitor an `i32`.
+    pub fn visit_i32(self, v: i32) -> Result<Out<'a>, Error> {
+        self.visitor.erased_visit_i32(v).map(Out::new)
+    }
+
+    /// Hand the visitor an `i64`.
+    pub fn visit_i64(self, v: i64) -> Result<Out<'a>, Error> {
+        self.visitor.erased_visit_i64(v).map(Out::new)
+    }
+
+    /// Hand the visitor an `i128`.
+    pub fn visit_i128(self, v: i128) -> Result<Out<'a>, Error> {
+        self.visitor.erased_visit_i128(v).map(Out::new)
+    }
+
+    /// Hand the visitor a `u8`.
+    pub fn visit_u8(self, v: u8) -> Result<Out<'a>, Error> {
+        self.visitor.erased_visit_u8(v).map(Out::new)
+    }
+
+    /// Hand the visitor a `u16`.
+    pub fn visit_u16(self, v: u16) -> Result<Out<'a>, Error> {
+        self.visitor.erased_visit_u16(v).map(Out::new)
+    }
+
+    /// Hand the visitor a `u32`.
+    pub fn visit_u32(self, v: u32) -> Result<Out<'a>, Error> {
+        self.visitor.erased_visit_u32(v).map(Out::new)
+    }
+
+    /// Hand the visitor a `u64`.
+    pub fn visit_u64(self, v: u64) -> Result<Out<'a>, Error> {
+        self.visitor.erased_visit_u64(v).map(Out::new)
+    }
+
+    /// Hand the visitor a `u128`.
+    pub fn visit_u128(self, v: u128) -> Result<Out<'a>, Error> {
+        self.visitor.erased_visit_u128(v).map(Out::new)
+    }
+
+    /// Hand the visitor an `f32`.
+    pub fn visit_f32(self, v: f32) -> Result<Out<'a>, Error> {
+        self.visitor.erased_visit_f32(v).map(Out::new)
+    }
+
+    /// Hand the visitor an `f64`.
+    pub fn visit_f64(self, v: f64) -> Result<Out<'a>, Error> {
+        self.visitor.erased_visit_f64(v).map(Out::new)
+    }
+
+    /// Hand the visitor a `char`.
+    pub fn visit_char(self, v: char) -> Result<Out<'a>, Error> {
+        self.visitor.erased_visit_char(v).map(Out::new)
+    }
+
+    /// Hand the visitor a string that only lives for the duration of the call.
+    pub fn visit_str(self, v: &str) -> Result<Out<'a>, Error> {
+        self.visitor.erased_visit_str(v).map(Out::new)
+    }
+
+    /// Hand the visitor a string borrowed from the input for `'de`.
+    pub fn visit_borrowed_str(self, v: &'de str) -> Result<Out<'a>, Error> {
+        self.visitor.erased_visit_borrowed_str(v).map(Out::new)
+    }
+
+    /// Hand the visitor an owned `String`.
+    #[cfg(feature = "alloc")]
+    pub fn visit_string(self, v: String) -> Result<Out<'a>, Error> {
+        self.visitor.erased_visit_string(v).map(Out::new)
+    }
+
+    /// Hand the visitor bytes that only live for the duration of the call.
+    pub fn visit_bytes(self, v: &[u8]) -> Result<Out<'a>, Error> {
+        self.visitor.erased_visit_bytes(v).map(Out::new)
+    }
+
+    /// Hand the visitor bytes borrowed from the input for `'de`.
+    pub fn visit_borrowed_bytes(self, v: &'de [u8]) -> Result<Out<'a>, Error> {
+        self.visitor.erased_visit_borrowed_bytes(v).map(Out::new)
+    }
+
+    /// Hand the visitor an owned byte buffer.
+    #[cfg(feature = "alloc")]
+    pub fn visit_byte_buf(self, v: Vec<u8>) -> Result<Out<'a>, Error> {
+        self.visitor.erased_visit_byte_buf(v).map(Out::new)
+    }
+
+    /// Tell the visitor that an optional value is absent.
+    pub fn visit_none(self) -> Result<Out<'a>, Error> {
+        self.visitor.erased_visit_none().map(Out::new)
+    }
+
+    /// Tell the visitor that an optional value is present, to be read from
+    /// `deserializer`.
+    pub fn visit_some(
+        self,
+        deserializer: &mut dyn crate::Deserializer<'de>,
+    ) -> Result<Out<'a>, Error> {
+        self.visitor.erased_visit_some(deserializer).map(Out::new)
+    }
+
+    /// Hand the visitor a `()`.
+    pub fn visit_unit(self) -> Result<Out<'a>, Error> {
+        self.visitor.erased_visit_unit().map(Out::new)
+    }
+
+    /// Hand the visitor a newtype struct whose content is read from
+    /// `deserializer`.
+    pub fn visit_newtype_struct(
+        self,
+        deserializer: &mut dyn crate::Deserializer<'de>,
+    ) -> Result<Out<'a>, Error> {
+        self.visitor
+            .erased_visit_newtype_struct(deserializer)
+            .map(Out::new)
+    }
+
+    /// Hand the visitor a sequence whose elements are read from `seq`.
+    pub fn visit_seq(self, seq: &mut dyn SeqAccess<'de>) -> Result<Out<'a>, Error> {
+        let mut seq = SeqAdapter { seq };
+        self.visitor.erased_visit_seq(&mut seq).map(Out::new)
+    }
+
+    /// Hand the visitor a map whose entries are read from `map`.
+    pub fn visit_map(self, map: &mut dyn MapAccess<'de>) -> Result<Out<'a>, Error> {
+        let mut map = MapAdapter { map };
+        self.visitor.erased_visit_map(&mut map).map(Out::new)
+    }
+
+    /// Hand the visitor an enum whose variant is read from `data`.
+    pub fn visit_enum(self, data: &mut dyn EnumAccess<'de>) -> Result<Out<'a>, Error> {
+        let mut data = EnumAdapter { data: Some(data) };
+        self.visitor.erased_visit_enum(&mut data).map(Out::new)
+    }
+}
+
+impl Expected for Visit<'_, '_> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        self.visitor.erased_expecting(formatter)
+    }
+}
+
+impl<'a, 'de> Seed<'a, 'de> {
+    fn new(seed: &'a mut dyn de::DeserializeSeed<'de>) -> Self {
+        Seed {
+            seed,
+            brand: PhantomData,
+        }
+    }
+
+    /// Run the seed against `deserializer`.
+    pub fn deserialize(
+        self,
+        deserializer: &mut dyn crate::Deserializer<'de>,
+    ) -> Result<Out<'a>, Error> {
+        self.seed
+            .erased_deserialize_seed(deserializer)
+            .map(Out::new)
+    }
+}
+
+// DESERIALIZER ADAPTER ////////////////////////////////////////////////////////
+
+impl<'de, D> de::Deserializer<'de> for Erased<D>
+where
+    D: Deserializer<'de>,
+{
+    fn erased_deserialize_any(
+        &mut self,
+        visitor: &mut dyn de::Visitor<'de>,
+    ) -> Result<de::Out, Error> {
+        self.inner
+            .erased_deserialize_any(Visit::new(visitor))
+            .map(Out::into_inner)
+    }
+
+    fn erased_deserialize_bool(
+        &mut self,
+        visitor: &mut dyn de::Visitor<'de>,
+    ) -> Result<de::Out, Error> {
+        self.inner
+            .erased_deserialize_bool(Visit::new(visitor))
+            .map(Out::into_inner)
+    }
+
+    fn erased_deserialize_i8(
+        &mut self,
+        visitor: &mut dyn de::Visitor<'de>,
+    ) -> Result<de::Out, Error> {
+        self.inner
+            .erased_deserialize_i8(Visit::new(visitor))
+            .map(Out::into_inner)
+    }
+
+    fn erased_deserialize_i16(
+        &mut self,
+        visitor: &mut dyn de::Visitor<'de>,
+    ) -> Result<de::Out, Error> {
+        self.inner
+            .erased_deserialize_i16(Visit::new(visitor))
+            .map(Out::into_inner)
+    }
+
+    fn erased_deserialize_i32(
+        &mut self,
+        visitor: &mut dyn de::Visitor<'de>,
+    ) -> Result<de::Out, Error> {
+        self.inner
+            .erased_deserialize_i32(Visit::new(visitor))
+            .map(Out::into_inner)
+    }
+
+    fn erased_deserialize_i64(
+        &mut self,
+        visitor: &mut dyn de::Visitor<'de>,
+    ) -> Result<de::Out, Error> {
+        self.inner
+            .erased_deserialize_i64(Visit::new(visitor))
+            .map(Out::into_inner)
+    }
+
+    fn erased_deserialize_i128(
+        &mut self,
+        visitor: &mut dyn de::Visitor<'de>,
+    ) -> Result<de::Out, Error> {
+        self.inner
+            .erased_deserialize_i128(Visit::new(visitor))
+            .map(Out::into_inner)
+    }
+
+    fn erased_deserialize_u8(
+        &mut self,
+        visitor: &mut dyn de::Visitor<'de>,
+    ) -> Result<de::Out, Error> {
+        self.inner
+            .erased_deserialize_u8(Visit::new(visitor))
+            .map(Out::into_inner)
+    }
+
+    fn erased_deserialize_u16(
+        &mut self,
+        visitor: &mut dyn de::Visitor<'de>,
+    ) -> Result<de::Out, Error> {
+        self.inner
+            .erased_deserialize_u16(Visit::new(visitor))
+            .map(Out::into_inner)
+    }
+
+    fn erased_deserialize_u32(
+        &mut self,
+        visitor: &mut dyn de::Visitor<'de>,
+    ) -> Result<de::Out, Error> {
+        self.inner
+            .erased_deserialize_u32(Visit::new(visitor))
+            .map(Out::into_inner)
+    }
+
+    fn erased_deserialize_u64(
+        &mut self,
+        visitor: &mut dyn de::Visitor<'de>,
+    ) -> Result<de::Out, Error> {
+        self.inner
+            .erased_deserialize_u64(Visit::new(visitor))
+            .map(Out::into_inner)
+    }
+
+    fn erased_deserialize_u128(
+        &mut self,
+        visitor: &mut dyn de::Visitor<'de>,
+    ) -> Result<de::Out, Error> {
+        self.inner
+            .erased_deserialize_u128(Visit::new(visitor))
+            .map(Out::into_inner)
+    }
+
+    fn erased_deserialize_f32(
+        &mut self,
+        visitor: &mut dyn de::Visitor<'de>,
+    ) -> Result<de::Out, Error> {
+        self.inner
+            .erased_deserialize_f32(Visit::new(visitor))
+            .map(Out::into_inner)
+    }
+
+    fn erased_deserialize_f64(
+        &mut self,
+        visitor: &mut dyn de::Visitor<'de>,
+    ) -> Result<de::Out, Error> {
+        self.inner
+            .erased_deserialize_f64(Visit::new(visitor))
+            .map(Out::into_inner)
+    }
+
+    fn erased_deserialize_char(
+        &mut self,
+        visitor: &mut dyn de::Visitor<'de>,
+    ) -> Result<de::Out, Error> {
+        self.inner
+            .erased_deserialize_char(Visit::new(visitor))
+            .map(Out::into_inner)
+    }
+
+    fn erased_deserialize_str(
+        &mut self,
+        visitor: &mut dyn de::Visitor<'de>,
+    ) -> Result<de::Out, Error> {
+        self.inner
+            .erased_deserialize_str(Visit::new(visitor))
+            .map(Out::into_inner)
+    }
+
+    fn erased_deserialize_string(
+        &mut self,
+        visitor: &mut dyn de::Visitor<'de>,
+    ) -> Result<de::Out, Error> {
+        self.inner
+            .erased_deserialize_string(Visit::new(visitor))
+            .map(Out::into_inner)
+    }
+
+    fn erased_deserialize_bytes(
+        &mut self,
+        visitor: &mut dyn de::Visitor<'de>,
+    ) -> Result<de::Out, Error> {
+        self.inner
+            .erased_deserialize_bytes(Visit::new(visitor))
+            .map(Out::into_inner)
+    }
+
+    fn erased_deserialize_byte_buf(
+        &mut self,
+        visitor: &mut dyn de::Visitor<'de>,
+    ) -> Result<de::Out, Error> {
+        self.inner
+            .erased_deserialize_byte_buf(Visit::new(visitor))
+            .map(Out::into_inner)
+    }
+
+    fn erased_deserialize_option(
+        &mut self,
+        visitor: &mut dyn de::Visitor<'de>,
+    ) -> Result<de::Out, Error> {
+        self.inner
+            .erased_deserialize_option(Visit::new(visitor))
+            .map(Out::into_inner)
+    }
+
+    fn erased_deserialize_unit(
+        &mut self,
+        visitor: &mut dyn de::Visitor<'de>,
+    ) -> Result<de::Out, Error> {
+        self.inner
+            .erased_deserialize_unit(Visit::new(visitor))
+            .map(Out::into_inner)
+    }
+
+    fn erased_deserialize_unit_struct(
+        &mut self,
+        name: &'static str,
+        visitor: &mut dyn de::Visitor<'de>,
+    ) -> Result<de::Out, Error> {
+        self.inner
+            .erased_deserialize_unit_struct(name, Visit::new(visitor))
+            .map(Out::into_inner)
+    }
+
+    fn erased_deserialize_newtype_struct(
+        &mut self,
+        name: &'static str,
+        visitor: &mut dyn de::Visitor<'de>,
+    ) -> Result<de::Out, Error> {
+        self.inner
+            .erased_deserialize_newtype_struct(name, Visit::new(visitor))
+            .map(Out::into_inner)
+    }
+
+    fn erased_deserialize_seq(
+        &mut self,
+        visitor: &mut dyn de::Visitor<'de>,
+    ) -> Result<de::Out, Error> {
+        self.inner
+            .erased_deserialize_seq(Visit::new(visitor))
+            .map(Out::into_inner)
+    }
+
+    fn erased_deserialize_tuple(
+        &mut self,
+        len: usize,
+        visitor: &mut dyn de::Visitor<'de>,
+    ) -> Result<de::Out, Error> {
+        self.inner
+            .erased_deserialize_tuple(len, Visit::new(visitor))
+            .map(Out::into_inner)
+    }
+
+    fn erased_deserialize_tuple_struct(
+        &mut self,
+        name: &'static str,
+        len: usize,
+        visitor: &mut dyn de::Visitor<'de>,
+    ) -> Result<de::Out, Error> {
+        self.inner
+            .erased_deserialize_tuple_struct(name, len, Visit::new(visitor))
+            .map(Out::into_inner)
+    }
+
+    fn erased_deserialize_map(
+        &mut self,
+        visitor: &mut dyn de::Visitor<'de>,
+    ) -> Result<de::Out, Error> {
+        self.inner
+            .erased_deserialize_map(Visit::new(visitor))
+            .map(Out::into_inner)
+    }
+
+    fn erased_deserialize_struct(
+        &mut self,
+        name: &'static str,
+        fields: &'static [&'static str],
+        visitor: &mut dyn de::Visitor<'de>,
+    ) -> Result<de::Out, Error> {
+        self.inner
+            .erased_deserialize_struct(name, fields, Visit::new(visitor))
+            .map(Out::into_inner)
+    }
+
+    fn erased_deserialize_identifier(
+        &mut self,
+        visitor: &mut dyn de::Visitor<'de>,
+    ) -> Result<de::Out, Error> {
+        self.inner
+            .erased_deserialize_identifier(Visit::new(visitor))
+            .map(Out::into_inner)
+    }
+
+    fn erased_deserialize_enum(
+        &mut self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: &mut dyn de::Visitor<'de>,
+    ) -> Result<de::Out, Error> {
+        self.inner
+            .erased_deserialize_enum(name, variants, Visit::new(visitor))
+            .map(Out::into_inner)
+    }
+
+    fn erased_deserialize_ignored_any(
+        &mut self,
+        visitor: &mut dyn de::Visitor<'de>,
+    ) -> Result<de::Out, Error> {
+        self.inner
+            .erased_deserialize_ignored_any(Visit::new(visitor))
+            .map(Out::into_inner)
+    }
+
+    fn erased_is_human_readable(&self) -> bool {
+        self.inner.erased_is_human_readable()
+    }
+
+    fn erased_as_deserializer(&mut self) -> &mut dyn de::Deserializer<'de> {
+        self
+    }
+}
+
+impl<'de, D> sealed::deserializer::Sealed for Erased<D> where D: Deserializer<'de> {}
+
+struct SeqAdapter<'a, 'de> {
+    seq: &'a mut dyn SeqAccess<'de>,
+}
+
+impl<'de> de::SeqAccess<'de> for SeqAdapter<'_, 'de> {
+    fn erased_next_element(
+        &mut self,
+        seed: &mut dyn de::DeserializeSeed<'de>,
+    ) -> Result<Option<de::Out>, Error> {
+        let out = self.seq.erased_next_element(Seed::new(seed))?;
+        Ok(out.map(Out::into_inner))
+    }
+
+    fn erased_size_hint(&self) -> Option<usize> {
+        self.seq.erased_size_hint()
+    }
+}
+
+impl sealed::seq_access::Sealed for SeqAdapter<'_, '_> {}
+
+struct MapAdapter<'a, 'de> {
+    map: &'a mut dyn MapAccess<'de>,
+}
+
+impl<'de> de::MapAccess<'de> for MapAdapter<'_, 'de> {
+    fn erased_next_key(
+        &mut self,
+        seed: &mut dyn de::DeserializeSeed<'de>,
+    ) -> Result<Option<de::Out>, Error> {
+        let out = self.map.erased_next_key(Seed::new(seed))?;
+        Ok(out.map(Out::into_inner))
+    }
+
+    fn erased_next_value(
+        &mut self,
+        seed: &mut dyn de::DeserializeSeed<'de>,
+    ) -> Result<de::Out, Error> {
+        self.map
+            .erased_next_value(Seed::new(seed))
+            .map(Out::into_inner)
+    }
+
+    fn erased_next_entry(
+        &mut self,
+        kseed: &mut dyn de::DeserializeSeed<'de>,
+        vseed: &mut dyn de::DeserializeSeed<'de>,
+    ) -> Result<Option<(de::Out, de::Out)>, Error> {
+        match self.erased_next_key(kseed)? {
+            Some(key) => {
+                let value = self.erased_next_value(vseed)?;
+                Ok(Some((key, value)))
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn erased_size_hint(&self) -> Option<usize> {
+        self.map.erased_size_hint()
+    }
+}
+
+impl sealed::map_access::Sealed for MapAdapter<'_, '_> {}
+
+struct EnumAdapter<'a, 'de> {
+    data: Option<&'a mut dyn EnumAccess<'de>>,
+}
+
+impl<'de> de::EnumAccess<'de> for EnumAdapter<'_, 'de> {
+    fn erased_variant_seed(
+        &mut self,
+        seed: &mut dyn de::DeserializeSeed<'de>,
+    ) -> Result<(de::Out, de::Variant<'de>), Error> {
+        let data = self.data.take().unwrap();
+        let out = data.erased_variant_seed(Seed::new(seed))?;
+        // The variant is consumed within the visitor's visit_enum, which
+        // returns before this borrow of `data` ends.
+        let variant = de::Variant {
+            data: unsafe { Any::new(data) },
+            unit_variant: {
+                unsafe fn unit_variant(a: Any) -> Result<(), Error> {
+                    unsafe { a.take::<&mut dyn EnumAccess>() }.erased_unit_variant()
+                }
+                unit_variant
+            },
+            visit_newtype: {
+                unsafe fn visit_newtype<'de>(
+                    a: Any,
+                    seed: &mut dyn de::DeserializeSeed<'de>,
+                ) -> Result<de::Out, Error> {
+                    unsafe { a.take::<&mut dyn EnumAccess<'de>>() }
+                        .erased_newtype_variant_seed(Seed::new(seed))
+                        .map(Out::into_inner)
+                }
+                visit_newtype
+            },
+            tuple_variant: {
+                unsafe fn tuple_variant<'de>(
+                    a: Any,
+                    len: usize,
+                    visitor: &mut dyn de::Visitor<'de>,
+                ) -> Result<de::Out, Error> {
+                    unsafe { a.take::<&mut dyn EnumAccess<'de>>() }
+                        .erased_tuple_variant(len, Visit::new(visitor))
+                        .map(Out::into_inner)
+                }
+                tuple_variant
+            },
+            struct_variant: {
+                unsafe fn struct_variant<'de>(
+                    a: Any,
+                    fields: &'static [&'static str],
+                    visitor: &mut dyn de::Visitor<'de>,
+                ) -> Result<de::Out, Error> {
+                    unsafe { a.take::<&mut dyn EnumAccess<'de>>() }
+                        .erased_struct_variant(fields, Visit::new(visitor))
+                        .map(Out::into_inner)
+                }
+                struct_variant
+            },
+        };
+        Ok((out.into_inner(), variant))
+    }
+}
+
+impl sealed::enum_access::Sealed for EnumAdapter<'_, '_> {}
+
+// IMPL CUSTOM FOR CUSTOM //////////////////////////////////////////////////////
+
+macro_rules! deref_custom_serializer {
+    (<$T:ident> $ty:ty) => {
+        impl<$T> Serializer for $ty
+        where
+            $T: ?Sized + Serializer,
+        {
+            fn erased_serialize_bool(&mut self, v: bool) -> Result<(), Error> {
+                (**self).erased_serialize_bool(v)
+            }
+
+            fn erased_serialize_i8(&mut self, v: i8) -> Result<(), Error> {
+                (**self).erased_serialize_i8(v)
+            }
+
+            fn erased_serialize_i16(&mut self, v: i16) -> Result<(), Error> {
+                (**self).erased_serialize_i16(v)
+            }
+
+            fn erased_serialize_i32(&mut self, v: i32) -> Result<(), Error> {
+                (**self).erased_serialize_i32(v)
+            }
+
+            fn erased_serialize_i64(&mut self, v: i64) -> Result<(), Error> {
+                (**self).erased_serialize_i64(v)
+            }
+
+            fn erased_serialize_i128(&mut self, v: i128) -> Result<(), Error> {
+                (**self).erased_serialize_i128(v)
+            }
+
+            fn erased_serialize_u8(&mut self, v: u8) -> Result<(), Error> {
+                (**self).erased_serialize_u8(v)
+            }
+
+            fn erased_serialize_u16(&mut self, v: u16) -> Result<(), Error> {
+                (**self).erased_serialize_u16(v)
+            }
+
+            fn erased_serialize_u32(&mut self, v: u32) -> Result<(), Error> {
+                (**self).erased_serialize_u32(v)
+            }
+
+            fn erased_serialize_u64(&mut self, v: u64) -> Result<(), Error> {
+                (**self).erased_serialize_u64(v)
+            }
+
+            fn erased_serialize_u128(&mut self, v: u128) -> Result<(), Error> {
+                (**self).erased_serialize_u128(v)
+            }
+
+            fn erased_serialize_f32(&mut self, v: f32) -> Result<(), Error> {
+                (**self).erased_serialize_f32(v)
+            }
+
+            fn erased_serialize_f64(&mut self, v: f64) -> Result<(), Error> {
+                (**self).erased_serialize_f64(v)
+            }
+
+            fn erased_serialize_char(&mut self, v: char) -> Result<(), Error> {
+                (**self).erased_serialize_char(v)
+            }
+
+            fn erased_serialize_str(&mut self, v: &str) -> Result<(), Error> {
+                (**self).erased_serialize_str(v)
+            }
+
+            fn erased_serialize_bytes(&mut self, v: &[u8]) -> Result<(), Error> {
+                (**self).erased_serialize_bytes(v)
+            }
+
+            fn erased_serialize_none(&mut self) -> Result<(), Error> {
+                (**self).erased_serialize_none()
+            }
+
+            fn erased_serialize_some(&mut self, value: &dyn Serialize) -> Result<(), Error> {
+                (**self).erased_serialize_some(value)
+            }
+
+            fn erased_serialize_unit(&mut self) -> Result<(), Error> {
+                (**self).erased_serialize_unit()
+            }
+
+            fn erased_serialize_unit_struct(&mut self, name: &'static str) -> Result<(), Error> {
+                (**self).erased_serialize_unit_struct(name)
+            }
+
+            fn erased_serialize_unit_variant(
+                &mut self,
+                name: &'static str,
+                variant_index: u32,
+                variant: &'static str,
+            ) -> Result<(), Error> {
+                (**self).erased_serialize_unit_variant(name, variant_index, variant)
+            }
+
+            fn erased_serialize_newtype_struct(
+                &mut self,
+                name: &'static str,
+                value: &dyn Serialize,
+            ) -> Result<(), Error> {
+                (**self).erased_serialize_newtype_struct(name, value)
+            }
+
+            fn erased_serialize_newtype_variant(
+                &mut self,
+                name: &'static str,
+                variant_index: u32,
+                variant: &'static str,
+                value: &dyn Serialize,
+            ) -> Result<(), Error> {
+                (**self).erased_serialize_newtype_variant(name, variant_index, variant, value)
+            }
+
+            fn erased_serialize_seq(&mut self, len: Option<usize>) -> Result<(), Error> {
+                (**self).erased_serialize_seq(len)
+            }
+
+            fn erased_serialize_tuple(&mut self, len: usize) -> Result<(), Error> {
+                (**self).erased_serialize_tuple(len)
+            }
+
+            fn erased_serialize_tuple_struct(
+                &mut self,
+                name: &'static str,
+                len: usize,
+            ) -> Result<(), Error> {
+                (**self).erased_serialize_tuple_struct(name, len)
+            }
+
+            fn erased_serialize_tuple_variant(
+                &mut self,
+                name: &'static str,
+                variant_index: u32,
+                variant: &'static str,
+                len: usize,
+            ) -> Result<(), Error> {
+                (**self).erased_serialize_tuple_variant(name, variant_index, variant, len)
+            }
+
+            fn erased_serialize_map(&mut self, len: Option<usize>) -> Result<(), Error> {
+                (**self).erased_serialize_map(len)
+            }
+
+            fn erased_serialize_struct(
+                &mut self,
+                name: &'static str,
+                len: usize,
+            ) -> Result<(), Error> {
+                (**self).erased_serialize_struct(name, len)
+            }
+
+            fn erased_serialize_struct_variant(
+                &mut self,
+                name: &'static str,
+                variant_index: u32,
+                variant: &'static str,
+                len: usize,
+            ) -> Result<(), Error> {
+                (**self).erased_serialize_struct_variant(name, variant_index, variant, len)
+            }
+
+            fn erased_is_human_readable(&self) -> bool {
+                (**self).erased_is_human_readable()
+            }
+        }
+
+        impl<$T> SerializeSeq for $ty
+        where
+            $T: ?Sized + SerializeSeq,
+        {
+            fn erased_serialize_element(&mut self, value: &dyn Serialize) -> Result<(), Error> {
+                (**self).erased_serialize_element(value)
+            }
+
+            fn erased_end(&mut self) -> Result<(), Error> {
+                (**self).erased_end()
+            }
+        }
+
+        impl<$T> SerializeTuple for $ty
+        where
+            $T: ?Sized + SerializeTuple,
+        {
+            fn erased_serialize_element(&mut self, value: &dyn Serialize) -> Result<(), Error> {
+                (**self).erased_serialize_element(value)
+            }
+
+            fn erased_end(&mut self) -> Result<(), Error> {
+                (**self).erased_end()
+            }
+        }
+
+        impl<$T> SerializeTupleStruct for $ty
+        where
+            $T: ?Sized + SerializeTupleStruct,
+        {
+            fn erased_serialize_field(&mut self, value: &dyn Serialize) -> Result<(), Error> {
+                (**self).erased_serialize_field(value)
+            }
+
+            fn erased_end(&mut self) -> Result<(), Error> {
+                (**self).erased_end()
+            }
+        }
+
+        impl<$T> SerializeTupleVariant for $ty
+        where
+            $T: ?Sized + SerializeTupleVariant,
+        {
+            fn erased_serialize_field(&mut self, value: &dyn Serialize) -> Result<(), Error> {
+                (**self).erased_serialize_field(value)
+            }
+
+            fn erased_end(&mut self) -> Result<(), Error> {
+                (**self).erased_end()
+            }
+        }
+
+        impl<$T> SerializeMap for $ty
+        where
+            $T: ?Sized + SerializeMap,
+        {
+            fn erased_serialize_key(&mut self, key: &dyn Serialize) -> Result<(), Error> {
+                (**self).erased_serialize_key(key)
+            }
+
+            fn erased_serialize_value(&mut self, value: &dyn Serialize) -> Result<(), Error> {
+                (**self).erased_serialize_value(value)
+            }
+
+            fn erased_serialize_entry(
+                &mut self,
+                key: &dyn Serialize,
+                value: &dyn Serialize,
+            ) -> Result<(), Error> {
+                (**self).erased_serialize_entry(key, value)
+            }
+
+            fn erased_end(&mut self) -> Result<(), Error> {
+                (**self).erased_end()
+            }
+        }
+
+        impl<$T> SerializeStruct for $ty
+        where
+            $T: ?Sized + SerializeStruct,
+        {
+            fn erased_serialize_field(
+                &mut self,
+                key: &'static str,
+                value: &dyn Serialize,
+            ) -> Result<(), Error> {
+                (**self).erased_serialize_field(key, value)
+            }
+
+            fn erased_skip_field(&mut self, key: &'static str) -> Result<(), Error> {
+                (**self).erased_skip_field(key)
+            }
+
+            fn erased_end(&mut self) -> Result<(), Error> {
+                (**self).erased_end()
+            }
+        }
+
+        impl<$T> SerializeStructVariant for $ty
+        where
+            $T: ?Sized + SerializeStructVariant,
+        {
+            fn erased_serialize_field(
+                &mut self,
+                key: &'static str,
+                value: &dyn Serialize,
+            ) -> Result<(), Error> {
+                (**self).erased_serialize_field(key, value)
+            }
+
+            fn erased_skip_field(&mut self, key: &'static str) -> Result<(), Error> {
+                (**self).erased_skip_field(key)
+            }
+
+            fn erased_end(&mut self) -> Result<(), Error> {
+                (**self).erased_end()
+            }
+        }
+    };
+}
+
+deref_custom_serializer!(<T> &mut T);
+deref_custom_serializer!(<T> Box<T>);
+
+macro_rules! deref_custom_deserializer {
+    (<$T:ident> $ty:ty) => {
+        impl<'de, $T> Deserializer<'de> for $ty
+        where
+            $T: ?Sized + Deserializer<'de>,
+        {
+            fn erased_deserialize_any<'a>(
+                &mut self,
+                visitor: Visit<'a, 'de>,
+            ) -> Result<Out<'a>, Error> {
+                (**self).erased_deserialize_any(visitor)
+            }
+
+            fn erased_deserialize_bool<'a>(
+                &mut self,
+                visitor: Visit<'a, 'de>,
+            ) -> Result<Out<'a>, Error> {
+                (**self).erased_deserialize_bool(visitor)
+            }
+
+            fn erased_deserialize_i8<'a>(
+                &mut self,
+                visitor: Visit<'a, 'de>,
+            ) -> Result<Out<'a>, Error> {
+                (**self).erased_deserialize_i8(visitor)
+            }
+
+            fn erased_deserialize_i16<'a>(
+                &mut self,
+                visitor: Visit<'a, 'de>,
+            ) -> Result<Out<'a>, Error> {
+                (**self).erased_deserialize_i16(visitor)
+            }
+
+            fn erased_deserialize_i32<'a>(
+                &mut self,
+                visitor: Visit<'a, 'de>,
+            ) -> Result<Out<'a>, Error> {
+                (**self).erased_deserialize_i32(visitor)
+            }
+
+            fn erased_deserialize_i64<'a>(
+                &mut self,
+                visitor: Visit<'a, 'de>,
+            ) -> Result<Out<'a>, Error> {
+                (**self).erased_deserialize_i64(visitor)
+            }
+
+            fn erased_deserialize_i128<'a>(
+                &mut self,
+                visitor: Visit<'a, 'de>,
+            ) -> Result<Out<'a>, Error> {
+                (**self).erased_deserialize_i128(visitor)
+            }
+
+            fn erased_deserialize_u8<'a>(
+                &mut self,
+                visitor: Visit<'a, 'de>,
+            ) -> Result<Out<'a>, Error> {
+                (**self).erased_deserialize_u8(visitor)
+            }
+
+            fn erased_deserialize_u16<'a>(
+                &mut self,
+                visitor: Visit<'a, 'de>,
+            ) -> Result<Out<'a>, Error> {
+                (**self).erased_deserialize_u16(visitor)
+            }
+
+            fn erased_deserialize_u32<'a>(
+                &mut self,
+                visitor: Visit<'a, 'de>,
+            ) -> Result<Out<'a>, Error> {
+                (**self).erased_deserialize_u32(visitor)
+            }
+
+            fn erased_deserialize_u64<'a>(
+                &mut self,
+                visitor: Visit<'a, 'de>,
+            ) -> Result<Out<'a>, Error> {
+                (**self).erased_deserialize_u64(visitor)
+            }
+
+            fn erased_deserialize_u128<'a>(
+                &mut self,
+                visitor: Visit<'a, 'de>,
+            ) -> Result<Out<'a>, Error> {
+                (**self).erased_deserialize_u128(visitor)
+            }
+
+            fn erased_deserialize_f32<'a>(
+                &mut self,
+                visitor: Visit<'a, 'de>,
+            ) -> Result<Out<'a>, Error> {
+                (**self).erased_deserialize_f32(visitor)
+            }
+
+            fn erased_deserialize_f64<'a>(
+                &mut self,
+                visitor: Visit<'a, 'de>,
+            ) -> Result<Out<'a>, Error> {
+                (**self).erased_deserialize_f64(visitor)
+            }
+
+            fn erased_deserialize_char<'a>(
+                &mut self,
+                visitor: Visit<'a, 'de>,
+            ) -> Result<Out<'a>, Error> {
+                (**self).erased_deserialize_char(visitor)
+            }
+
+            fn erased_deserialize_str<'a>(
+                &mut self,
+                visitor: Visit<'a, 'de>,
+            ) -> Result<Out<'a>, Error> {
+                (**self).erased_deserialize_str(visitor)
+            }
+
+            fn erased_deserialize_string<'a>(
+                &mut self,
+                visitor: Visit<'a, 'de>,
+            ) -> Result<Out<'a>, Error> {
+                (**self).erased_deserialize_string(visitor)
+            }
+
+            fn erased_deserialize_bytes<'a>(
+                &mut self,
+                visitor: Visit<'a, 'de>,
+            ) -> Result<Out<'a>, Error> {
+                (**self).erased_deserialize_bytes(visitor)
+            }
+
+            fn erased_deserialize_byte_buf<'a>(
+                &mut self,
+                visitor: Visit<'a, 'de>,
+            ) -> Result<Out<'a>, Error> {
+                (**self).erased_deserialize_byte_buf(visitor)
+            }
+
+            fn erased_deserialize_option<'a>(
+                &mut self,
+                visitor: Visit<'a, 'de>,
+            ) -> Result<Out<'a>, Error> {
+                (**self).erased_deserialize_option(visitor)
+            }
+
+            fn erased_deserialize_unit<'a>(
+                &mut self,
+                visitor: Visit<'a, 'de>,
+            ) -> Result<Out<'a>, Error> {
+                (**self).erased_deserialize_unit(visitor)
+            }
+
+            fn erased_deserialize_unit_struct<'a>(
+                &mut self,
+                name: &'static str,
+                visitor: Visit<'a, 'de>,
+            ) -> Result<Out<'a>, Error> {
+                (**self).erased_deserialize_unit_struct(name, visitor)
+            }
+
+            fn erased_deserialize_newtype_struct<'a>(
+                &mut self,
+                name: &'static str,
+                visitor: Visit<'a, 'de>,
+            ) -> Result<Out<'a>, Error> {
+                (**self).erased_deserialize_newtype_struct(name, visitor)
+            }
+
+            fn erased_deserialize_seq<'a>(
+                &mut self,
+                visitor: Visit<'a, 'de>,
+            ) -> Result<Out<'a>, Error> {
+                (**self).erased_deserialize_seq(visitor)
+            }
+
+            fn erased_deserialize_tuple<'a>(
+                &mut self,
+                len: usize,
+                visitor: Visit<'a, 'de>,
+            ) -> Result<Out<'a>, Error> {
+                (**self).erased_deserialize_tuple(len, visitor)
+            }
+
+            fn erased_deserialize_tuple_struct<'a>(
+                &mut self,
+                name: &'static str,
+                len: usize,
+                visitor: Visit<'a, 'de>,
+            ) -> Result<Out<'a>, Error> {
+                (**self).erased_deserialize_tuple_struct(name, len, visitor)
+            }
+
+            fn erased_deserialize_map<'a>(
+                &mut self,
+                visitor: Visit<'a, 'de>,
+            ) -> Result<Out<'a>, Error> {
+                (**self).erased_deserialize_map(visitor)
+            }
+
+            fn erased_deserialize_struct<'a>(
+                &mut self,
+                name: &'static str,
+                fields: &'static [&'static str],
+                visitor: Visit<'a, 'de>,
+            ) -> Result<Out<'a>, Error> {
+                (**self).erased_deserialize_struct(name, fields, visitor)
+            }
+
+            fn erased_deserialize_identifier<'a>(
+                &mut self,
+                visitor: Visit<'a, 'de>,
+            ) -> Result<Out<'a>, Error> {
+                (**self).erased_deserialize_identifier(visitor)
+            }
+
+            fn erased_deserialize_enum<'a>(
+                &mut self,
+                name: &'static str,
+                variants: &'static [&'static str],
+                visitor: Visit<'a, 'de>,
+            ) -> Result<Out<'a>, Error> {
+                (**self).erased_deserialize_enum(name, variants, visitor)
+            }
+
+            fn erased_deserialize_ignored_any<'a>(
+                &mut self,
+                visitor: Visit<'a, 'de>,
+            ) -> Result<Out<'a>, Error> {
+                (**self).erased_deserialize_ignored_any(visitor)
+            }
+
+            fn erased_is_human_readable(&self) -> bool {
+                (**self).erased_is_human_readable()
+            }
+        }
+    };
+}
+
+deref_custom_deserializer!(<T> &mut T);
+deref_custom_deserializer!(<T> Box<T>);
+
+// TEST ////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ErrorKind;
+    use alloc::borrow::ToOwned;
+    use alloc::string::{String, ToString};
+    use alloc::vec;
+    use alloc::vec::Vec;
+    use serde::de::Unexpected;
+    use serde::ser::Error as _;
+    use serde_derive::{Deserialize, Serialize};
+
+    // Writes a compact text form of the value, with `(` `)` around compound
+    // values and `,` after each element.
+    #[derive(Default)]
+    struct Text {
+        out: String,
+    }
+
+    #[allow(clippy::unnecessary_wraps)]
+    impl Text {
+        fn leaf(&mut self, v: impl ToString) -> Result<(), Error> {
+            self.out += &v.to_string();
+            Ok(())
+        }
+
+        fn open(&mut self, name: &str) -> Result<(), Error> {
+            self.out += name;
+            self.out += "(";
+            Ok(())
+        }
+
+        fn element(&mut self, value: &dyn Serialize) -> Result<(), Error> {
+            let mut erased = Erased::new(&mut *self);
+            value.erased_serialize(&mut erased)?;
+            erased.into_result()?;
+            self.out += ",";
+            Ok(())
+        }
+
+        fn close(&mut self) -> Result<(), Error> {
+            self.out += ")";
+            Ok(())
+        }
+    }
+
+    impl Serializer for Text {
+        fn erased_serialize_bool(&mut self, v: bool) -> Result<(), Error> {
+            self.leaf(v)
+        }
+
+        fn erased_serialize_i8(&mut self, v: i8) -> Result<(), Error> {
+            self.leaf(v)
+        }
+
+        fn erased_serialize_i16(&mut self, v: i16) -> Result<(), Error> {
+            self.leaf(v)
+        }
+
+        fn erased_serialize_i32(&mut self, v: i32) -> Result<(), Error> {
+            self.leaf(v)
+        }
+
+        fn erased_serialize_i64(&mut self, v: i64) -> Result<(), Error> {
+            self.leaf(v)
+        }
+
+        fn erased_serialize_i128(&mut self, v: i128) -> Result<(), Error> {
+            self.leaf(v)
+        }
+
+        fn erased_serialize_u8(&mut self, v: u8) -> Result<(), Error> {
+            self.leaf(v)
+        }
+
+        fn erased_serialize_u16(&mut self, v: u16) -> Result<(), Error> {
+            self.leaf(v)
+        }
+
+        fn erased_serialize_u32(&mut self, v: u32) -> Result<(), Error> {
+            self.leaf(v)
+        }
+
+        fn erased_serialize_u64(&mut self, v: u64) -> Result<(), Error> {
+            self.leaf(v)
+        }
+
+        fn erased_serialize_u128(&mut self, v: u128) -> Result<(), Error> {
+            self.leaf(v)
+        }
+
+        fn erased_serialize_f32(&mut self, v: f32) -> Result<(), Error> {
+            self.leaf(v)
+        }
+
+        fn erased_serialize_f64(&mut self, v: f64) -> Result<(), Error> {
+            self.leaf(v)
+        }
+
+        fn erased_serialize_char(&mut self, v: char) -> Result<(), Error> {
+            self.leaf(v)
+        }
+
+        fn erased_serialize_str(&mut self, v: &str) -> Result<(), Error> {
+            self.leaf(v)
+        }
+
+        fn erased_serialize_bytes(&mut self, _v: &[u8]) -> Result<(), Error> {
+            Err(Error::custom("bytes are not supported"))
+        }
+
+        fn erased_serialize_none(&mut self) -> Result<(), Error> {
+            self.leaf("None")
+        }
+
+        fn erased_serialize_some(&mut self, value: &dyn Serialize) -> Result<(), Error> {
+            self.open("Some")?;
+            self.element(value)?;
+            self.close()
+        }
+
+        fn erased_serialize_unit(&mut self) -> Result<(), Error> {
+            self.leaf("()")
+        }
+
+        fn erased_serialize_unit_struct(&mut self, name: &'static str) -> Result<(), Error> {
+            self.leaf(name)
+        }
+
+        fn erased_serialize_unit_variant(
+            &mut self,
+            _name: &'static str,
+            _variant_index: u32,
+            variant: &'static str,
+        ) -> Result<(), Error> {
+            self.leaf(variant)
+        }
+
+        fn erased_serialize_newtype_struct(
+            &mut self,
+            name: &'static str,
+            value: &dyn Serialize,
+        ) -> Result<(), Error> {
+            self.open(name)?;
+            self.element(value)?;
+            self.close()
+        }
+
+        fn erased_serialize_newtype_variant(
+            &mut self,
+            _name: &'static str,
+            _variant_index: u32,
+            variant: &'static str,
+            value: &dyn Serialize,
+        ) -> Result<(), Error> {
+            self.open(variant)?;
+            self.element(value)?;
+            self.close()
+        }
+
+        fn erased_serialize_seq(&mut self, _len: Option<usize>) -> Result<(), Error> {
+            self.open("")
+        }
+
+        fn erased_serialize_tuple(&mut self, _len: usize) -> Result<(), Error> {
+            self.open("")
+        }
+
+        fn erased_serialize_tuple_struct(
+            &mut self,
+            name: &'static str,
+            _len: usize,
+        ) -> Result<(), Error> {
+            self.open(name)
+        }
+
+        fn erased_serialize_tuple_variant(
+            &mut self,
+            _name: &'static str,
+            _variant_index: u32,
+            variant: &'static str,
+            _len: usize,
+        ) -> Result<(), Error> {
+            self.open(variant)
+        }
+
+        fn erased_serialize_map(&mut self, _len: Option<usize>) -> Result<(), Error> {
+            self.open("")
+        }
+
+        fn erased_serialize_struct(
+            &mut self,
+            name: &'static str,
+            _len: usize,
+        ) -> Result<(), Error> {
+            self.open(name)
+        }
+
+        fn erased_serialize_struct_variant(
+            &mut self,
+            _name: &'static str,
+            _variant_index: u32,
+            variant: &'static str,
+            _len: usize,
+        ) -> Result<(), Error> {
+            self.open(variant)
+        }
+    }
+
+    impl SerializeSeq for Text {
+        fn erased_serialize_element(&mut self, value: &dyn Serialize) -> Result<(), Error> {
+            self.element(value)
+        }
+
+        fn erased_end(&mut self) -> Result<(), Error> {
+            self.close()
+        }
+    }
+
+    impl SerializeTuple for Text {
+        fn erased_serialize_element(&mut self, value: &dyn Serialize) -> Result<(), Error> {
+            self.element(value)
+        }
+
+        fn erased_end(&mut self) -> Result<(), Error> {
+            self.close()
+        }
+    }
+
+    impl SerializeTupleStruct for Text {
+        fn erased_serialize_field(&mut self, value: &dyn Serialize) -> Result<(), Error> {
+            self.element(value)
+        }
+
+        fn erased_end(&mut self) -> Result<(), Error> {
+            self.close()
+        }
+    }
+
+    impl SerializeTupleVariant for Text {
+        fn erased_serialize_field(&mut self, value: &dyn Serialize) -> Result<(), Error> {
+            self.element(value)
+        }
+
+        fn erased_end(&mut self) -> Result<(), Error> {
+            self.close()
+        }
+    }
+
+    impl SerializeMap for Text {
+        fn erased_serialize_key(&mut self, key: &dyn Serialize) -> Result<(), Error> {
+            let mut erased = Erased::new(&mut *self);
+            key.erased_serialize(&mut erased)?;
+            erased.into_result()?;
+            self.out += "=";
+            Ok(())
+        }
+
+        fn erased_serialize_value(&mut self, value: &dyn Serialize) -> Result<(), Error> {
+            self.element(value)
+        }
+
+        fn erased_end(&mut self) -> Result<(), Error> {
+            self.close()
+        }
+    }
+
+    impl SerializeStruct for Text {
+        fn erased_serialize_field(
+            &mut self,
+            key: &'static str,
+            value: &dyn Serialize,
+        ) -> Result<(), Error> {
+            self.out += key;
+            self.out += "=";
+            self.element(value)
+        }
+
+        fn erased_end(&mut self) -> Result<(), Error> {
+            self.close()
+        }
+    }
+
+    impl SerializeStructVariant for Text {
+        fn erased_serialize_field(
+            &mut self,
+            key: &'static str,
+            value: &dyn Serialize,
+        ) -> Result<(), Error> {
+            self.out += key;
+            self.out += "=";
+            self.element(value)
+        }
+
+        fn erased_end(&mut self) -> Result<(), Error> {
+            Err(Error::custom("struct variants cannot be closed"))
+        }
+    }
+
+    #[derive(Serialize)]
+    struct Point {
+        x: i32,
+        y: Option<i32>,
+    }
+
+    #[derive(Serialize)]
+    enum Shape {
+        Polygon(Vec<Point>),
+        Circle { radius: u32 },
+    }
+
+    #[test]
+    fn test_erased_serialize() {
+        let value: &dyn Serialize = &(Shape::Polygon(vec![Point { x: 1, y: None }]), 'c');
+        let mut erased = Erased::new(Text::default());
+        value.erased_serialize(&mut erased).unwrap();
+        let text = erased.into_result().unwrap();
+        assert_eq!(text.out, "(Polygon((Point(x=1,y=None,),),),c,)");
+    }
+
+    #[test]
+    fn test_serde_serializer() {
+        let value: &dyn Serialize = &[("a", Point { x: 0, y: Some(2) })];
+        let mut erased = Erased::new(Text::default());
+        crate::serialize(value, &mut erased).unwrap();
+        let text = erased.into_result().unwrap();
+        assert_eq!(text.out, "((a,Point(x=0,y=Some(2,),),),)");
+    }
+
+    #[test]
+    fn test_errors() {
+        let value: &dyn Serialize = &vec![serde_bytes_like()];
+        let mut erased = Erased::new(Text::default());
+        let err = value.erased_serialize(&mut erased).unwrap_err();
+        assert_eq!(err.to_string(), "bytes are not supported");
+
+        let value: &dyn Serialize = &Shape::Circle { radius: 1 };
+        let mut erased = Erased::new(Text::default());
+        value.erased_serialize(&mut erased).unwrap();
+        let err = erased.into_result().err().unwrap();
+        assert_eq!(err.to_string(), "struct variants cannot be closed");
+
+        let mut boxed: Box<dyn Serializer> = Box::new(Text::default());
+        let mut erased = Erased::new(&mut boxed);
+        let err = crate::serialize(value, &mut erased).unwrap_err();
+        assert_eq!(err.to_string(), "struct variants cannot be closed");
+    }
+
+    // A value from some dynamic object model.
+    enum Dyn {
+        Int(i64),
+        Str(&'static str),
+        List(Vec<Dyn>),
+        Map(Vec<(&'static str, Dyn)>),
+    }
+
+    impl<'de> Deserializer<'de> for &Dyn {
+        fn erased_deserialize_any<'a>(
+            &mut self,
+            visitor: Visit<'a, 'de>,
+        ) -> Result<Out<'a>, Error> {
+            match self {
+                Dyn::Int(n) => visitor.visit_i64(*n),
+                Dyn::Str(s) => visitor.visit_str(s),
+                Dyn::List(list) => visitor.visit_seq(&mut DynSeq(list.iter())),
+                Dyn::Map(map) => visitor.visit_map(&mut DynMap(map.iter(), None)),
+            }
+        }
+
+        fn erased_deserialize_enum<'a>(
+            &mut self,
+            _name: &'static str,
+            _variants: &'static [&'static str],
+            visitor: Visit<'a, 'de>,
+        ) -> Result<Out<'a>, Error> {
+            match self {
+                Dyn::Str(_) => visitor.visit_enum(&mut DynEnum(self, None)),
+                Dyn::Map(map) if map.len() == 1 => {
+                    let (variant, content) = &map[0];
+                    visitor.visit_enum(&mut DynEnum(&Dyn::Str(variant), Some(content)))
+                }
+                _ => Err(serde::de::Error::invalid_type(
+                    Unexpected::Other("dyn"),
+                    &visitor,
+                )),
+            }
+        }
+    }
+
+    struct DynSeq<'a>(core::slice::Iter<'a, Dyn>);
+
+    impl<'de> SeqAccess<'de> for DynSeq<'_> {
+        fn erased_next_element<'a>(
+            &mut self,
+            seed: Seed<'a, 'de>,
+        ) -> Result<Option<Out<'a>>, Error> {
+            match self.0.next() {
+                Some(element) => seed.deserialize(&mut Erased::new(element)).map(Some),
+                None => Ok(None),
+            }
+        }
+
+        fn erased_size_hint(&self) -> Option<usize> {
+            Some(self.0.len())
+        }
+    }
+
+    struct DynMap<'a>(core::slice::Iter<'a, (&'static str, Dyn)>, Option<&'a Dyn>);
+
+    impl<'de> MapAccess<'de> for DynMap<'_> {
+        fn erased_next_key<'a>(&mut self, seed: Seed<'a, 'de>) -> Result<Option<Out<'a>>, Error> {
+            match self.0.next() {
+                Some((key, value)) => {
+                    self.1 = Some(value);
+                    seed.deserialize(&mut Erased::new(&Dyn::Str(key))).map(Some)
+                }
+                None => Ok(None),
+            }
+        }
+
+        fn erased_next_value<'a>(&mut self, seed: Seed<'a, 'de>) -> Result<Out<'a>, Error> {
+            let value = self.1.take().unwrap();
+            seed.deserialize(&mut Erased::new(value))
+        }
+    }
+
+    struct DynEnum<'a>(&'a Dyn, Option<&'a Dyn>);
+
+    impl DynEnum<'_> {
+        fn content(&mut self) -> Result<&Dyn, Error> {
+            self.1.take().ok_or_else(|| {
+                serde::de::Error::invalid_type(Unexpected::UnitVariant, &"variant content")
+            })
+        }
+    }
+
+    impl<'de> EnumAccess<'de> for DynEnum<'_> {
+        fn erased_variant_seed<'a>(&mut self, seed: Seed<'a, 'de>) -> Result<Out<'a>, Error> {
+            seed.deserialize(&mut Erased::new(self.0))
+        }
+
+        fn erased_unit_variant(&mut self) -> Result<(), Error> {
+            match self.1 {
+                None => Ok(()),
+                Some(_) => Err(serde::de::Error::invalid_type(
+                    Unexpected::NewtypeVariant,
+                    &"unit variant",
+                )),
+            }
+        }
+
+        fn erased_newtype_variant_seed<'a>(
+            &mut self,
+            seed: Seed<'a, 'de>,
+        ) -> Result<Out<'a>, Error> {
+            let content = self.content()?;
+            seed.deserialize(&mut Erased::new(content))
+        }
+
+        fn erased_tuple_variant<'a>(
+            &mut self,
+            _len: usize,
+            visitor: Visit<'a, 'de>,
+        ) -> Result<Out<'a>, Error> {
+            let mut content = self.content()?;
+            content.erased_deserialize_any(visitor)
+        }
+
+        fn erased_struct_variant<'a>(
+            &mut self,
+            _fields: &'static [&'static str],
+            visitor: Visit<'a, 'de>,
+        ) -> Result<Out<'a>, Error> {
+            let mut content = self.content()?;
+            content.erased_deserialize_any(visitor)
+        }
+    }
+
+    #[derive(Deserialize, PartialEq, Debug)]
+    struct Config {
+        name: String,
+        ports: Vec<u16>,
+        mode: Mode,
+    }
+
+    #[derive(Deserialize, PartialEq, Debug)]
+    enum Mode {
+        Off,
+        Fixed(u8),
+        Range { lo: u8, hi: u8 },
+    }
+
+    #[test]
+    fn test_deserialize() {
+        let object = Dyn::Map(vec![
+            ("name", Dyn::Str("local")),
+            ("ports", Dyn::List(vec![Dyn::Int(80), Dyn::Int(443)])),
+            ("mode", Dyn::Map(vec![("Fixed", Dyn::Int(3))])),
+        ]);
+        let mut erased = Erased::new(&object);
+        let config: Config = crate::deserialize(&mut erased).unwrap();
+        let expected = Config {
+            name: "local".to_owned(),
+            ports: vec![80, 443],
+            mode: Mode::Fixed(3),
+        };
+        assert_eq!(config, expected);
+
+        let object = Dyn::Str("Off");
+        let mut boxed: Box<dyn crate::Deserializer> = Box::new(Erased::new(&object));
+        let mode: Mode = crate::deserialize(&mut boxed).unwrap();
+        assert_eq!(mode, Mode::Off);
+
+        let object = Dyn::Map(vec![(
+            "Range",
+            Dyn::Map(vec![("lo", Dyn::Int(1)), ("hi", Dyn::Int(2))]),
+        )]);
+        let mut erased = Erased::new(&object);
+        let mode: Mode = crate::deserialize(&mut erased).unwrap();
+        assert_eq!(mode, Mode::Range { lo: 1, hi: 2 });
+    }
+
+    #[test]
+    fn test_deserialize_errors() {
+        let object = Dyn::List(vec![Dyn::Int(1), Dyn::Str("x")]);
+        let mut erased = Erased::new(&object);
+        let err = crate::deserialize::<Vec<u8>>(&mut erased).unwrap_err();
+        assert_eq!(err.to_string(), "invalid type: string \"x\", expected u8");
+        assert_eq!(err.kind(), ErrorKind::InvalidType);
+
+        let object = Dyn::Str("Fixed");
+        let mut erased = Erased::new(&object);
+        let err = crate::deserialize::<Mode>(&mut erased).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "invalid type: unit variant, expected variant content",
+        );
+
+        let object = Dyn::Int(0);
+        let mut erased = Erased::new(&object);
+        let err = crate::deserialize::<Mode>(&mut erased).unwrap_err();
+        assert_eq!(err.to_string(), "invalid type: dyn, expected enum Mode");
+    }
+
+    fn serde_bytes_like() -> impl serde::Serialize {
+        struct Bytes;
+
+        impl serde::Serialize for Bytes {
+            fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                S: serde::Serializer,
+            {
+                serializer.serialize_bytes(b"\0")
+            }
+        }
+
+        Bytes
+    }
+}
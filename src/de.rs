@@ -1,6 +1,64 @@
+//! Object-safe equivalents of Serde's deserialization traits.
+//!
+//! Besides [`Deserializer`], this module names the traits through which an
+//! erased deserializer talks to the `Deserialize` impl driving it, so that
+//! functions can accept a `&mut dyn Visitor<'de>` or `&mut dyn SeqAccess<'de>`
+//! and pass it along. These traits are sealed. A Serde visitor or seed is
+//! turned into one with [`<dyn Visitor>::erase`][erase-visitor] or
+//! [`<dyn DeserializeSeed>::erase`][erase-seed], and the [`Out`] it produces
+//! is turned back into its value with [`ErasedVisitor::take_value`] or
+//! [`ErasedDeserializeSeed::take_value`].
+//!
+//! [erase-visitor]: trait.Visitor.html#method.erase
+//! [erase-seed]: trait.DeserializeSeed.html#method.erase
+//!
+//! ```rust
+//! use erased_serde::de::{Out, Visitor};
+//! use erased_serde::{Deserializer, Error};
+//! use serde::de::{IgnoredAny, SeqAccess};
+//! use std::fmt;
+//!
+//! // Hands the visitor to whichever deserializer method suits the data.
+//! fn drive<'de>(
+//!     deserializer: &mut dyn Deserializer<'de>,
+//!     visitor: &mut dyn Visitor<'de>,
+//! ) -> Result<Out, Error> {
+//!     deserializer.erased_deserialize_seq(visitor)
+//! }
+//!
+//! struct Count;
+//!
+//! impl<'de> serde::de::Visitor<'de> for Count {
+//!     type Value = usize;
+//!
+//!     fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+//!         formatter.write_str("a sequence")
+//!     }
+//!
+//!     fn visit_seq<A>(self, mut seq: A) -> Result<usize, A::Error>
+//!     where
+//!         A: SeqAccess<'de>,
+//!     {
+//!         let mut count = 0;
+//!         while seq.next_element::<IgnoredAny>()?.is_some() {
+//!             count += 1;
+//!         }
+//!         Ok(count)
+//!     }
+//! }
+//!
+//! let json = &mut serde_json::Deserializer::from_str("[1, [2, 3], 4]");
+//! let mut deserializer = <dyn Deserializer>::erase(json);
+//! let mut visitor = <dyn Visitor>::erase(Count);
+//! let out = drive(&mut deserializer, &mut visitor).unwrap();
+//! assert_eq!(visitor.take_value(out), 3);
+//! ```
+
 use crate::any::Any;
-use crate::error::{erase_de as erase, unerase_de as unerase, Error};
+use crate::detach::Detach;
+use crate::error::{erase_de as erase, unerase_de as unerase, with_cause, Error};
 use crate::map::{OptionExt, ResultExt};
+use crate::sealed;
 use crate::sealed::deserializer::Sealed;
 use alloc::boxed::Box;
 #[cfg(feature = "alloc")]
@@ -46,11 +104,21 @@ where
 
 // TRAITS //////////////////////////////////////////////////////////////////////
 
-pub trait DeserializeSeed<'de> {
+/// An object-safe equivalent of Serde's `DeserializeSeed` trait.
+///
+/// This trait is sealed. A Serde `DeserializeSeed` can be converted to one
+/// using [`<dyn DeserializeSeed>::erase`][erase].
+///
+/// [erase]: trait.DeserializeSeed.html#method.erase
+pub trait DeserializeSeed<'de>: sealed::deserialize_seed::Sealed {
     fn erased_deserialize_seed(
         &mut self,
         deserializer: &mut dyn Deserializer<'de>,
     ) -> Result<Out, Error>;
+    #[doc(hidden)]
+    fn erased_keep_error(&mut self, error: Error);
+    #[doc(hidden)]
+    fn erased_take_error(&mut self) -> Option<Error>;
 }
 
 /// An object-safe equivalent of Serde's `Deserializer` trait.
@@ -87,7 +155,9 @@ pub trait DeserializeSeed<'de> {
 /// ```
 ///
 /// This trait is sealed and can only be implemented via a
-/// `serde::Deserializer<'de>` impl.
+/// `serde::Deserializer<'de>` impl, or by implementing
+/// [`custom::Deserializer`][crate::custom::Deserializer] and wrapping it in
+/// [`custom::Erased`][crate::custom::Erased].
 pub trait Deserializer<'de>: Sealed {
     fn erased_deserialize_any(&mut self, visitor: &mut dyn Visitor<'de>) -> Result<Out, Error>;
     fn erased_deserialize_bool(&mut self, visitor: &mut dyn Visitor<'de>) -> Result<Out, Error>;
@@ -155,9 +225,17 @@ pub trait Deserializer<'de>: Sealed {
         visitor: &mut dyn Visitor<'de>,
     ) -> Result<Out, Error>;
     fn erased_is_human_readable(&self) -> bool;
+    #[doc(hidden)]
+    fn erased_as_deserializer(&mut self) -> &mut dyn Deserializer<'de>;
 }
 
-pub trait Visitor<'de> {
+/// An object-safe equivalent of Serde's `Visitor` trait.
+///
+/// This trait is sealed. A Serde `Visitor` can be converted to one using
+/// [`<dyn Visitor>::erase`][erase].
+///
+/// [erase]: trait.Visitor.html#method.erase
+pub trait Visitor<'de>: sealed::visitor::Sealed {
     fn erased_expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result;
     fn erased_visit_bool(&mut self, v: bool) -> Result<Out, Error>;
     fn erased_visit_i8(&mut self, v: i8) -> Result<Out, Error>;
@@ -192,9 +270,17 @@ pub trait Visitor<'de> {
     fn erased_visit_seq(&mut self, seq: &mut dyn SeqAccess<'de>) -> Result<Out, Error>;
     fn erased_visit_map(&mut self, map: &mut dyn MapAccess<'de>) -> Result<Out, Error>;
     fn erased_visit_enum(&mut self, data: &mut dyn EnumAccess<'de>) -> Result<Out, Error>;
+    #[doc(hidden)]
+    fn erased_keep_error(&mut self, error: Error);
+    #[doc(hidden)]
+    fn erased_take_error(&mut self) -> Option<Error>;
 }
 
-pub trait SeqAccess<'de> {
+/// An object-safe equivalent of Serde's `SeqAccess` trait, through which a
+/// [`Visitor`] receives the elements of a sequence.
+///
+/// This trait is sealed.
+pub trait SeqAccess<'de>: sealed::seq_access::Sealed {
     fn erased_next_element(
         &mut self,
         seed: &mut dyn DeserializeSeed<'de>,
@@ -202,7 +288,11 @@ pub trait SeqAccess<'de> {
     fn erased_size_hint(&self) -> Option<usize>;
 }
 
-pub trait MapAccess<'de> {
+/// An object-safe equivalent of Serde's `MapAccess` trait, through which a
+/// [`Visitor`] receives the entries of a map.
+///
+/// This trait is sealed.
+pub trait MapAccess<'de>: sealed::map_access::Sealed {
     fn erased_next_key(
         &mut self,
         seed: &mut dyn DeserializeSeed<'de>,
@@ -216,7 +306,11 @@ pub trait MapAccess<'de> {
     fn erased_size_hint(&self) -> Option<usize>;
 }
 
-pub trait EnumAccess<'de> {
+/// An object-safe equivalent of Serde's `EnumAccess` trait, through which a
+/// [`Visitor`] receives an enum variant.
+///
+/// This trait is sealed.
+pub trait EnumAccess<'de>: sealed::enum_access::Sealed {
     fn erased_variant_seed(
         &mut self,
         seed: &mut dyn DeserializeSeed<'de>,
@@ -253,17 +347,206 @@ impl<'de> dyn Deserializer<'de> {
         ///     println!("{}", data["A"] + data["B"]);
         /// }
         /// ```
-        pub fn erase<D>(deserializer: D) -> impl Deserializer<'de> [erase::Deserializer<D>]
+        pub fn erase<D>(deserializer: D) -> impl Deserializer<'de> [erase::Deserializer<D, D::Error>]
         where
             D: serde::Deserializer<'de>,
         {
             erase::Deserializer::new(deserializer)
         }
     }
+
+    return_impl_trait! {
+        /// Convert any Serde `Deserializer` to a trait object, keeping its
+        /// errors intact.
+        ///
+        /// Unlike `erase`, which reduces a failure to its message, errors
+        /// from this deserializer are stored inside the resulting
+        /// `erased_serde::Error` and can be recovered with
+        /// [`Error::downcast_ref`] or [`Error::downcast`].
+        ///
+        /// Only errors returned by the deserializer itself are preserved this
+        /// way. An error which the format raises while handing out the
+        /// elements of a sequence or map passes through the `Deserialize`
+        /// impl on its way out, and arrives carrying just its message.
+        ///
+        /// ```rust
+        /// use erased_serde::Deserializer;
+        ///
+        /// let json = &mut serde_json::Deserializer::from_slice(b"tru");
+        /// let format = &mut <dyn Deserializer>::erase_preserving_errors(json);
+        ///
+        /// let error = erased_serde::deserialize::<bool>(format).unwrap_err();
+        /// let json_error = error.downcast_ref::<serde_json::Error>().unwrap();
+        /// assert!(json_error.is_eof());
+        /// assert_eq!(json_error.column(), 3);
+        /// ```
+        pub fn erase_preserving_errors<D>(deserializer: D) -> impl Deserializer<'de> [erase::Deserializer<D, D::Error>]
+        where
+            D: serde::Deserializer<'de>,
+            D::Error: Send + Sync + 'static,
+        {
+            let mut erased = erase::Deserializer::new(deserializer);
+            erased.preserve = Some(crate::error::preserve::<D::Error>);
+            erased
+        }
+    }
+
+    return_impl_trait! {
+        /// Convert a Serde `Deserializer` that is used through a mutable
+        /// reference, like `serde_json::Deserializer`, to a trait object that
+        /// owns it.
+        ///
+        /// Unlike `erase`, which needs a `&mut serde_json::Deserializer`
+        /// borrowed from somewhere, the result can be returned and boxed
+        /// without the format deserializer being kept alive elsewhere. Each
+        /// call deserializes one value through a fresh `&mut` to the format
+        /// deserializer, so a format that holds several values in a row hands
+        /// out the next one on every call.
+        ///
+        /// ```rust
+        /// use erased_serde::Deserializer;
+        ///
+        /// fn json(input: &str) -> Box<dyn Deserializer + '_> {
+        ///     let json = serde_json::Deserializer::from_str(input);
+        ///     Box::new(<dyn Deserializer>::erase_owned(json))
+        /// }
+        ///
+        /// let mut format = json("1 2");
+        /// let one: u8 = erased_serde::deserialize(&mut format).unwrap();
+        /// let two: u8 = erased_serde::deserialize(&mut format).unwrap();
+        /// assert_eq!((one, two), (1, 2));
+        /// ```
+        pub fn erase_owned<D>(deserializer: D) -> impl Deserializer<'de> [erase::OwnedDeserializer<D>]
+        where
+            for<'a> &'a mut D: serde::Deserializer<'de>,
+        {
+            erase::OwnedDeserializer::new(deserializer)
+        }
+    }
+
+    return_impl_trait! {
+        /// Like `erase_owned`, for input that holds
+        /// exactly one value.
+        ///
+        /// Plain `erase_owned` leaves whatever follows a value in the input
+        /// for the next call, so a trailing `garbage` goes unnoticed by a
+        /// caller that deserializes once. Here, after every value, `end` is
+        /// called on the format deserializer to check that the rest of the
+        /// input is empty, and its error is returned if not. For `serde_json`
+        /// and `serde_cbor` this is `|de| de.end()`.
+        ///
+        /// ```rust
+        /// use erased_serde::Deserializer;
+        ///
+        /// fn json(input: &str) -> Box<dyn Deserializer + '_> {
+        ///     let json = serde_json::Deserializer::from_str(input);
+        ///     Box::new(<dyn Deserializer>::erase_owned_with_end(json, |json| json.end()))
+        /// }
+        ///
+        /// let one: u8 = erased_serde::deserialize(&mut json("1")).unwrap();
+        /// assert_eq!(one, 1);
+        ///
+        /// let error = erased_serde::deserialize::<u8>(&mut json("1 garbage")).unwrap_err();
+        /// assert_eq!(error.to_string(), "trailing characters at line 1 column 3");
+        /// ```
+        pub fn erase_owned_with_end<D, F, E>(deserializer: D, end: F) -> impl Deserializer<'de> [erase::OwnedDeserializerWithEnd<D, F>]
+        where
+            for<'a> &'a mut D: serde::Deserializer<'de>,
+            F: FnMut(&mut D) -> Result<(), E>,
+            E: serde::de::Error,
+        {
+            erase::OwnedDeserializerWithEnd {
+                owned: erase::OwnedDeserializer::new(deserializer),
+                end,
+            }
+        }
+    }
+
+    return_impl_trait! {
+        /// Convert any Serde `Deserializer` to a trait object that lends no
+        /// data for `'de`.
+        ///
+        /// Strings and bytes which the deserializer would lend out of its input
+        /// are passed to the `Deserialize` impl as transient ones instead. The
+        /// result is a `Deserializer<'static>`, from which types that implement
+        /// `DeserializeOwned` can be deserialized even when the input is only
+        /// borrowed for a short time, such as a `&mut dyn Deserializer<'de>`
+        /// over a request buffer.
+        ///
+        /// ```rust
+        /// use erased_serde::Deserializer;
+        /// use serde::de::DeserializeOwned;
+        ///
+        /// fn load<T: DeserializeOwned>(format: &mut dyn Deserializer<'static>) -> T {
+        ///     erased_serde::deserialize(format).unwrap()
+        /// }
+        ///
+        /// fn handle(request: &mut dyn Deserializer) -> Vec<String> {
+        ///     load(&mut <dyn Deserializer>::detach(request))
+        /// }
+        ///
+        /// let buffer = br#"["GET", "/index.html"]"#.to_vec();
+        /// let json = &mut serde_json::Deserializer::from_slice(&buffer);
+        /// let request = handle(&mut <dyn Deserializer>::erase(json));
+        /// assert_eq!(request, ["GET", "/index.html"]);
+        /// ```
+        pub fn detach<D>(deserializer: D) -> impl Deserializer<'static> [erase::Deserializer<Detach<'de, D>, D::Error>]
+        where
+            D: serde::Deserializer<'de>,
+        {
+            erase::Deserializer::new(Detach::new(deserializer))
+        }
+    }
+}
+
+impl<'de> dyn Visitor<'de> {
+    /// Convert any Serde `Visitor` to a trait object.
+    ///
+    /// The visitor's value must be `'static`, since the [`Out`] holding it is
+    /// not tied to any lifetime.
+    ///
+    /// ```rust
+    /// use erased_serde::de::Visitor;
+    /// use erased_serde::Deserializer;
+    /// use serde::de::IgnoredAny;
+    ///
+    /// let json = &mut serde_json::Deserializer::from_str("[1, 2]");
+    /// let mut visitor = <dyn Visitor>::erase(IgnoredAny);
+    /// let out = <dyn Deserializer>::erase(json)
+    ///     .erased_deserialize_any(&mut visitor)
+    ///     .unwrap();
+    /// let IgnoredAny = visitor.take_value(out);
+    /// ```
+    pub fn erase<V>(visitor: V) -> ErasedVisitor<V>
+    where
+        V: serde::de::Visitor<'de>,
+        V::Value: 'static,
+    {
+        erase::Visitor::new(visitor)
+    }
+}
+
+impl<'de> dyn DeserializeSeed<'de> {
+    /// Convert any Serde `DeserializeSeed` to a trait object.
+    ///
+    /// The seed's value must be `'static`, since the [`Out`] holding it is not
+    /// tied to any lifetime.
+    pub fn erase<S>(seed: S) -> ErasedDeserializeSeed<S>
+    where
+        S: serde::de::DeserializeSeed<'de>,
+        S::Value: 'static,
+    {
+        erase::DeserializeSeed::new(seed)
+    }
 }
 
 // OUT /////////////////////////////////////////////////////////////////////////
 
+/// The value produced by a [`Visitor`] or [`DeserializeSeed`], in erased form.
+///
+/// It is turned back into that value by the visitor or seed which produced
+/// it, using [`ErasedVisitor::take_value`] or
+/// [`ErasedDeserializeSeed::take_value`].
 pub struct Out(Any);
 
 impl Out {
@@ -278,14 +561,29 @@ impl Out {
 
 // IMPL ERASED SERDE FOR SERDE /////////////////////////////////////////////////
 
-mod erase {
+pub use self::erase::{DeserializeSeed as ErasedDeserializeSeed, Visitor as ErasedVisitor};
+
+pub(crate) mod erase {
+    use crate::error::Error;
+
+    /// A Serde `DeserializeSeed` converted into an
+    /// `erased_serde::de::DeserializeSeed`.
+    ///
+    /// This is the type returned by
+    /// [`<dyn DeserializeSeed>::erase`][erase].
+    ///
+    /// [erase]: trait.DeserializeSeed.html#method.erase
     pub struct DeserializeSeed<D> {
         state: Option<D>,
+        pub(crate) error: Option<Error>,
     }
 
     impl<D> DeserializeSeed<D> {
         pub(crate) fn new(seed: D) -> Self {
-            DeserializeSeed { state: Some(seed) }
+            DeserializeSeed {
+                state: Some(seed),
+                error: None,
+            }
         }
 
         pub(crate) fn take(&mut self) -> D {
@@ -293,14 +591,18 @@ mod erase {
         }
     }
 
-    pub struct Deserializer<D> {
+    // E is always the error type of D. It is a separate parameter only because
+    // naming D::Error needs the 'de lifetime, which this struct does not have.
+    pub struct Deserializer<D, E> {
         state: Option<D>,
+        pub(crate) preserve: Option<fn(E) -> Error>,
     }
 
-    impl<D> Deserializer<D> {
+    impl<D, E> Deserializer<D, E> {
         pub(crate) fn new(deserializer: D) -> Self {
             Deserializer {
                 state: Some(deserializer),
+                preserve: None,
             }
         }
 
@@ -313,14 +615,44 @@ mod erase {
         }
     }
 
+    pub struct OwnedDeserializer<D> {
+        pub(crate) deserializer: D,
+        pub(crate) human_readable: bool,
+    }
+
+    pub struct OwnedDeserializerWithEnd<D, F> {
+        pub(crate) owned: OwnedDeserializer<D>,
+        pub(crate) end: F,
+    }
+
+    impl<D> OwnedDeserializer<D> {
+        pub(crate) fn new<'de>(mut deserializer: D) -> Self
+        where
+            for<'a> &'a mut D: serde::Deserializer<'de>,
+        {
+            let human_readable = serde::Deserializer::is_human_readable(&&mut deserializer);
+            OwnedDeserializer {
+                deserializer,
+                human_readable,
+            }
+        }
+    }
+
+    /// A Serde `Visitor` converted into an `erased_serde::de::Visitor`.
+    ///
+    /// This is the type returned by [`<dyn Visitor>::erase`][erase].
+    ///
+    /// [erase]: trait.Visitor.html#method.erase
     pub struct Visitor<D> {
         state: Option<D>,
+        pub(crate) error: Option<Error>,
     }
 
     impl<D> Visitor<D> {
         pub(crate) fn new(visitor: D) -> Self {
             Visitor {
                 state: Some(visitor),
+                error: None,
             }
         }
 
@@ -386,6 +718,21 @@ mod erase {
     }
 }
 
+impl<'de, T> erase::DeserializeSeed<T>
+where
+    T: serde::de::DeserializeSeed<'de>,
+    T::Value: 'static,
+{
+    /// Unwrap the value which this seed produced.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `out` holds a value of some other type.
+    pub fn take_value(&self, out: Out) -> T::Value {
+        unsafe { out.take() }
+    }
+}
+
 impl<'de, T> DeserializeSeed<'de> for erase::DeserializeSeed<T>
 where
     T: serde::de::DeserializeSeed<'de>,
@@ -396,97 +743,152 @@ where
     ) -> Result<Out, Error> {
         unsafe { self.take().deserialize(deserializer).unsafe_map(Out::new) }
     }
+
+    fn erased_keep_error(&mut self, error: Error) {
+        self.error = Some(error);
+    }
+
+    fn erased_take_error(&mut self) -> Option<Error> {
+        self.error.take()
+    }
+}
+
+impl<'de, T> sealed::deserialize_seed::Sealed for erase::DeserializeSeed<T> where
+    T: serde::de::DeserializeSeed<'de>
+{
 }
 
-impl<'de, T> Deserializer<'de> for erase::Deserializer<T>
+impl<'de, T> Deserializer<'de> for erase::Deserializer<T, T::Error>
 where
     T: serde::Deserializer<'de>,
 {
     fn erased_deserialize_any(&mut self, visitor: &mut dyn Visitor<'de>) -> Result<Out, Error> {
-        self.take().deserialize_any(visitor).map_err(erase)
+        self.take()
+            .deserialize_any(&mut *visitor)
+            .map_err(|err| self.erase_error(err, visitor))
     }
 
     fn erased_deserialize_bool(&mut self, visitor: &mut dyn Visitor<'de>) -> Result<Out, Error> {
-        self.take().deserialize_bool(visitor).map_err(erase)
+        self.take()
+            .deserialize_bool(&mut *visitor)
+            .map_err(|err| self.erase_error(err, visitor))
     }
 
     fn erased_deserialize_i8(&mut self, visitor: &mut dyn Visitor<'de>) -> Result<Out, Error> {
-        self.take().deserialize_i8(visitor).map_err(erase)
+        self.take()
+            .deserialize_i8(&mut *visitor)
+            .map_err(|err| self.erase_error(err, visitor))
     }
 
     fn erased_deserialize_i16(&mut self, visitor: &mut dyn Visitor<'de>) -> Result<Out, Error> {
-        self.take().deserialize_i16(visitor).map_err(erase)
+        self.take()
+            .deserialize_i16(&mut *visitor)
+            .map_err(|err| self.erase_error(err, visitor))
     }
 
     fn erased_deserialize_i32(&mut self, visitor: &mut dyn Visitor<'de>) -> Result<Out, Error> {
-        self.take().deserialize_i32(visitor).map_err(erase)
+        self.take()
+            .deserialize_i32(&mut *visitor)
+            .map_err(|err| self.erase_error(err, visitor))
     }
 
     fn erased_deserialize_i64(&mut self, visitor: &mut dyn Visitor<'de>) -> Result<Out, Error> {
-        self.take().deserialize_i64(visitor).map_err(erase)
+        self.take()
+            .deserialize_i64(&mut *visitor)
+            .map_err(|err| self.erase_error(err, visitor))
     }
 
     fn erased_deserialize_i128(&mut self, visitor: &mut dyn Visitor<'de>) -> Result<Out, Error> {
-        self.take().deserialize_i128(visitor).map_err(erase)
+        self.take()
+            .deserialize_i128(&mut *visitor)
+            .map_err(|err| self.erase_error(err, visitor))
     }
 
     fn erased_deserialize_u8(&mut self, visitor: &mut dyn Visitor<'de>) -> Result<Out, Error> {
-        self.take().deserialize_u8(visitor).map_err(erase)
+        self.take()
+            .deserialize_u8(&mut *visitor)
+            .map_err(|err| self.erase_error(err, visitor))
     }
 
     fn erased_deserialize_u16(&mut self, visitor: &mut dyn Visitor<'de>) -> Result<Out, Error> {
-        self.take().deserialize_u16(visitor).map_err(erase)
+        self.take()
+            .deserialize_u16(&mut *visitor)
+            .map_err(|err| self.erase_error(err, visitor))
     }
 
     fn erased_deserialize_u32(&mut self, visitor: &mut dyn Visitor<'de>) -> Result<Out, Error> {
-        self.take().deserialize_u32(visitor).map_err(erase)
+        self.take()
+            .deserialize_u32(&mut *visitor)
+            .map_err(|err| self.erase_error(err, visitor))
     }
 
     fn erased_deserialize_u64(&mut self, visitor: &mut dyn Visitor<'de>) -> Result<Out, Error> {
-        self.take().deserialize_u64(visitor).map_err(erase)
+        self.take()
+            .deserialize_u64(&mut *visitor)
+            .map_err(|err| self.erase_error(err, visitor))
     }
 
     fn erased_deserialize_u128(&mut self, visitor: &mut dyn Visitor<'de>) -> Result<Out, Error> {
-        self.take().deserialize_u128(visitor).map_err(erase)
+        self.take()
+            .deserialize_u128(&mut *visitor)
+            .map_err(|err| self.erase_error(err, visitor))
     }
 
     fn erased_deserialize_f32(&mut self, visitor: &mut dyn Visitor<'de>) -> Result<Out, Error> {
-        self.take().deserialize_f32(visitor).map_err(erase)
+        self.take()
+            .deserialize_f32(&mut *visitor)
+            .map_err(|err| self.erase_error(err, visitor))
     }
 
     fn erased_deserialize_f64(&mut self, visitor: &mut dyn Visitor<'de>) -> Result<Out, Error> {
-        self.take().deserialize_f64(visitor).map_err(erase)
+        self.take()
+            .deserialize_f64(&mut *visitor)
+            .map_err(|err| self.erase_error(err, visitor))
     }
 
     fn erased_deserialize_char(&mut self, visitor: &mut dyn Visitor<'de>) -> Result<Out, Error> {
-        self.take().deserialize_char(visitor).map_err(erase)
+        self.take()
+            .deserialize_char(&mut *visitor)
+            .map_err(|err| self.erase_error(err, visitor))
     }
 
     fn erased_deserialize_str(&mut self, visitor: &mut dyn Visitor<'de>) -> Result<Out, Error> {
-        self.take().deserialize_str(visitor).map_err(erase)
+        self.take()
+            .deserialize_str(&mut *visitor)
+            .map_err(|err| self.erase_error(err, visitor))
     }
 
     fn erased_deserialize_string(&mut self, visitor: &mut dyn Visitor<'de>) -> Result<Out, Error> {
-        self.take().deserialize_string(visitor).map_err(erase)
+        self.take()
+            .deserialize_string(&mut *visitor)
+            .map_err(|err| self.erase_error(err, visitor))
     }
 
     fn erased_deserialize_bytes(&mut self, visitor: &mut dyn Visitor<'de>) -> Result<Out, Error> {
-        self.take().deserialize_bytes(visitor).map_err(erase)
+        self.take()
+            .deserialize_bytes(&mut *visitor)
+            .map_err(|err| self.erase_error(err, visitor))
     }
 
     fn erased_deserialize_byte_buf(
         &mut self,
         visitor: &mut dyn Visitor<'de>,
     ) -> Result<Out, Error> {
-        self.take().deserialize_byte_buf(visitor).map_err(erase)
+        self.take()
+            .deserialize_byte_buf(&mut *visitor)
+            .map_err(|err| self.erase_error(err, visitor))
     }
 
     fn erased_deserialize_option(&mut self, visitor: &mut dyn Visitor<'de>) -> Result<Out, Error> {
-        self.take().deserialize_option(visitor).map_err(erase)
+        self.take()
+            .deserialize_option(&mut *visitor)
+            .map_err(|err| self.erase_error(err, visitor))
     }
 
     fn erased_deserialize_unit(&mut self, visitor: &mut dyn Visitor<'de>) -> Result<Out, Error> {
-        self.take().deserialize_unit(visitor).map_err(erase)
+        self.take()
+            .deserialize_unit(&mut *visitor)
+            .map_err(|err| self.erase_error(err, visitor))
     }
 
     fn erased_deserialize_unit_struct(
@@ -495,8 +897,8 @@ where
         visitor: &mut dyn Visitor<'de>,
     ) -> Result<Out, Error> {
         self.take()
-            .deserialize_unit_struct(name, visitor)
-            .map_err(erase)
+            .deserialize_unit_struct(name, &mut *visitor)
+            .map_err(|err| self.erase_error(err, visitor))
     }
 
     fn erased_deserialize_newtype_struct(
@@ -505,12 +907,14 @@ where
         visitor: &mut dyn Visitor<'de>,
     ) -> Result<Out, Error> {
         self.take()
-            .deserialize_newtype_struct(name, visitor)
-            .map_err(erase)
+            .deserialize_newtype_struct(name, &mut *visitor)
+            .map_err(|err| self.erase_error(err, visitor))
     }
 
     fn erased_deserialize_seq(&mut self, visitor: &mut dyn Visitor<'de>) -> Result<Out, Error> {
-        self.take().deserialize_seq(visitor).map_err(erase)
+        self.take()
+            .deserialize_seq(&mut *visitor)
+            .map_err(|err| self.erase_error(err, visitor))
     }
 
     fn erased_deserialize_tuple(
@@ -518,7 +922,9 @@ where
         len: usize,
         visitor: &mut dyn Visitor<'de>,
     ) -> Result<Out, Error> {
-        self.take().deserialize_tuple(len, visitor).map_err(erase)
+        self.take()
+            .deserialize_tuple(len, &mut *visitor)
+            .map_err(|err| self.erase_error(err, visitor))
     }
 
     fn erased_deserialize_tuple_struct(
@@ -528,12 +934,14 @@ where
         visitor: &mut dyn Visitor<'de>,
     ) -> Result<Out, Error> {
         self.take()
-            .deserialize_tuple_struct(name, len, visitor)
-            .map_err(erase)
+            .deserialize_tuple_struct(name, len, &mut *visitor)
+            .map_err(|err| self.erase_error(err, visitor))
     }
 
     fn erased_deserialize_map(&mut self, visitor: &mut dyn Visitor<'de>) -> Result<Out, Error> {
-        self.take().deserialize_map(visitor).map_err(erase)
+        self.take()
+            .deserialize_map(&mut *visitor)
+            .map_err(|err| self.erase_error(err, visitor))
     }
 
     fn erased_deserialize_struct(
@@ -543,15 +951,17 @@ where
         visitor: &mut dyn Visitor<'de>,
     ) -> Result<Out, Error> {
         self.take()
-            .deserialize_struct(name, fields, visitor)
-            .map_err(erase)
+            .deserialize_struct(name, fields, &mut *visitor)
+            .map_err(|err| self.erase_error(err, visitor))
     }
 
     fn erased_deserialize_identifier(
         &mut self,
         visitor: &mut dyn Visitor<'de>,
     ) -> Result<Out, Error> {
-        self.take().deserialize_identifier(visitor).map_err(erase)
+        self.take()
+            .deserialize_identifier(&mut *visitor)
+            .map_err(|err| self.erase_error(err, visitor))
     }
 
     fn erased_deserialize_enum(
@@ -561,23 +971,57 @@ where
         visitor: &mut dyn Visitor<'de>,
     ) -> Result<Out, Error> {
         self.take()
-            .deserialize_enum(name, variants, visitor)
-            .map_err(erase)
+            .deserialize_enum(name, variants, &mut *visitor)
+            .map_err(|err| self.erase_error(err, visitor))
     }
 
     fn erased_deserialize_ignored_any(
         &mut self,
         visitor: &mut dyn Visitor<'de>,
     ) -> Result<Out, Error> {
-        self.take().deserialize_ignored_any(visitor).map_err(erase)
+        self.take()
+            .deserialize_ignored_any(&mut *visitor)
+            .map_err(|err| self.erase_error(err, visitor))
     }
 
     fn erased_is_human_readable(&self) -> bool {
         self.as_ref().is_human_readable()
     }
+
+    fn erased_as_deserializer(&mut self) -> &mut dyn Deserializer<'de> {
+        self
+    }
 }
 
-impl<'de, T> Sealed for erase::Deserializer<T> where T: serde::Deserializer<'de> {}
+impl<'de, T> Sealed for erase::Deserializer<T, T::Error> where T: serde::Deserializer<'de> {}
+
+impl<'de, T> erase::Deserializer<T, T::Error>
+where
+    T: serde::Deserializer<'de>,
+{
+    fn erase_error(&self, err: T::Error, visitor: &mut dyn Visitor<'de>) -> Error {
+        let error = match self.preserve {
+            Some(preserve) => preserve(err),
+            None => erase(err),
+        };
+        with_cause(error, visitor.erased_take_error())
+    }
+}
+
+impl<'de, T> erase::Visitor<T>
+where
+    T: serde::de::Visitor<'de>,
+    T::Value: 'static,
+{
+    /// Unwrap the value which this visitor produced.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `out` holds a value of some other type.
+    pub fn take_value(&self, out: Out) -> T::Value {
+        unsafe { out.take() }
+    }
+}
 
 impl<'de, T> Visitor<'de> for erase::Visitor<T>
 where
@@ -706,8 +1150,18 @@ where
     fn erased_visit_enum(&mut self, data: &mut dyn EnumAccess<'de>) -> Result<Out, Error> {
         unsafe { self.take().visit_enum(data).unsafe_map(Out::new) }
     }
+
+    fn erased_keep_error(&mut self, error: Error) {
+        self.error = Some(error);
+    }
+
+    fn erased_take_error(&mut self) -> Option<Error> {
+        self.error.take()
+    }
 }
 
+impl<'de, T> sealed::visitor::Sealed for erase::Visitor<T> where T: serde::de::Visitor<'de> {}
+
 impl<'de, T> SeqAccess<'de> for erase::SeqAccess<T>
 where
     T: serde::de::SeqAccess<'de>,
@@ -716,7 +1170,9 @@ where
         &mut self,
         seed: &mut dyn DeserializeSeed<'de>,
     ) -> Result<Option<Out>, Error> {
-        self.as_mut().next_element_seed(seed).map_err(erase)
+        self.as_mut()
+            .next_element_seed(&mut *seed)
+            .map_err(|err| with_cause(erase(err), seed.erased_take_error()))
     }
 
     fn erased_size_hint(&self) -> Option<usize> {
@@ -724,6 +1180,8 @@ where
     }
 }
 
+impl<'de, T> sealed::seq_access::Sealed for erase::SeqAccess<T> where T: serde::de::SeqAccess<'de> {}
+
 impl<'de, T> MapAccess<'de> for erase::MapAccess<T>
 where
     T: serde::de::MapAccess<'de>,
@@ -732,11 +1190,15 @@ where
         &mut self,
         seed: &mut dyn DeserializeSeed<'de>,
     ) -> Result<Option<Out>, Error> {
-        self.as_mut().next_key_seed(seed).map_err(erase)
+        self.as_mut()
+            .next_key_seed(&mut *seed)
+            .map_err(|err| with_cause(erase(err), seed.erased_take_error()))
     }
 
     fn erased_next_value(&mut self, seed: &mut dyn DeserializeSeed<'de>) -> Result<Out, Error> {
-        self.as_mut().next_value_seed(seed).map_err(erase)
+        self.as_mut()
+            .next_value_seed(&mut *seed)
+            .map_err(|err| with_cause(erase(err), seed.erased_take_error()))
     }
 
     fn erased_next_entry(
@@ -744,7 +1206,14 @@ where
         kseed: &mut dyn DeserializeSeed<'de>,
         vseed: &mut dyn DeserializeSeed<'de>,
     ) -> Result<Option<(Out, Out)>, Error> {
-        self.as_mut().next_entry_seed(kseed, vseed).map_err(erase)
+        self.as_mut()
+            .next_entry_seed(&mut *kseed, &mut *vseed)
+            .map_err(|err| {
+                let cause = kseed
+                    .erased_take_error()
+                    .or_else(|| vseed.erased_take_error());
+                with_cause(erase(err), cause)
+            })
     }
 
     fn erased_size_hint(&self) -> Option<usize> {
@@ -752,6 +1221,8 @@ where
     }
 }
 
+impl<'de, T> sealed::map_access::Sealed for erase::MapAccess<T> where T: serde::de::MapAccess<'de> {}
+
 impl<'de, T> EnumAccess<'de> for erase::EnumAccess<T>
 where
     T: serde::de::EnumAccess<'de>,
@@ -761,7 +1232,7 @@ where
         seed: &mut dyn DeserializeSeed<'de>,
     ) -> Result<(Out, Variant<'de>), Error> {
         self.take()
-            .variant_seed(seed)
+            .variant_seed(&mut *seed)
             .map(|(out, variant)| {
                 use serde::de::VariantAccess;
                 let erased = Variant {
@@ -785,8 +1256,8 @@ where
                         {
                             unsafe {
                                 a.take::<T::Variant>()
-                                    .newtype_variant_seed(seed)
-                                    .map_err(erase)
+                                    .newtype_variant_seed(&mut *seed)
+                                    .map_err(|err| with_cause(erase(err), seed.erased_take_error()))
                             }
                         }
                         visit_newtype::<T>
@@ -802,8 +1273,10 @@ where
                         {
                             unsafe {
                                 a.take::<T::Variant>()
-                                    .tuple_variant(len, visitor)
-                                    .map_err(erase)
+                                    .tuple_variant(len, &mut *visitor)
+                                    .map_err(|err| {
+                                        with_cause(erase(err), visitor.erased_take_error())
+                                    })
                             }
                         }
                         tuple_variant::<T>
@@ -819,8 +1292,10 @@ where
                         {
                             unsafe {
                                 a.take::<T::Variant>()
-                                    .struct_variant(fields, visitor)
-                                    .map_err(erase)
+                                    .struct_variant(fields, &mut *visitor)
+                                    .map_err(|err| {
+                                        with_cause(erase(err), visitor.erased_take_error())
+                                    })
                             }
                         }
                         struct_variant::<T>
@@ -828,12 +1303,33 @@ where
                 };
                 (out, erased)
             })
-            .map_err(erase)
+            .map_err(|err| with_cause(erase(err), seed.erased_take_error()))
     }
 }
 
+impl<'de, T> sealed::enum_access::Sealed for erase::EnumAccess<T> where T: serde::de::EnumAccess<'de>
+{}
+
 // IMPL SERDE FOR ERASED SERDE /////////////////////////////////////////////////
 
+// The format gets its own copy of the error, and the original stays behind to
+// be reattached as the cause once the format's error makes it back out.
+impl dyn DeserializeSeed<'_> + '_ {
+    fn unerase<E: serde::de::Error>(&mut self, error: Error) -> E {
+        let unerased = unerase(&error);
+        self.erased_keep_error(error);
+        unerased
+    }
+}
+
+impl dyn Visitor<'_> + '_ {
+    fn unerase<E: serde::de::Error>(&mut self, error: Error) -> E {
+        let unerased = unerase(&error);
+        self.erased_keep_error(error);
+        unerased
+    }
+}
+
 impl<'de> serde::de::DeserializeSeed<'de> for &mut (dyn DeserializeSeed<'de> + '_) {
     type Value = Out;
     fn deserialize<D>(self, deserializer: D) -> Result<Out, D::Error>
@@ -841,7 +1337,8 @@ impl<'de> serde::de::DeserializeSeed<'de> for &mut (dyn DeserializeSeed<'de> + '
         D: serde::Deserializer<'de>,
     {
         let mut erased = erase::Deserializer::new(deserializer);
-        self.erased_deserialize_seed(&mut erased).map_err(unerase)
+        self.erased_deserialize_seed(&mut erased)
+            .map_err(|err| self.unerase(err))
     }
 }
 
@@ -1125,112 +1622,113 @@ impl<'de> serde::de::Visitor<'de> for &mut (dyn Visitor<'de> + '_) {
     where
         E: serde::de::Error,
     {
-        self.erased_visit_bool(v).map_err(unerase)
+        self.erased_visit_bool(v).map_err(|err| self.unerase(err))
     }
 
     fn visit_i8<E>(self, v: i8) -> Result<Out, E>
     where
         E: serde::de::Error,
     {
-        self.erased_visit_i8(v).map_err(unerase)
+        self.erased_visit_i8(v).map_err(|err| self.unerase(err))
     }
 
     fn visit_i16<E>(self, v: i16) -> Result<Out, E>
     where
         E: serde::de::Error,
     {
-        self.erased_visit_i16(v).map_err(unerase)
+        self.erased_visit_i16(v).map_err(|err| self.unerase(err))
     }
 
     fn visit_i32<E>(self, v: i32) -> Result<Out, E>
     where
         E: serde::de::Error,
     {
-        self.erased_visit_i32(v).map_err(unerase)
+        self.erased_visit_i32(v).map_err(|err| self.unerase(err))
     }
 
     fn visit_i64<E>(self, v: i64) -> Result<Out, E>
     where
         E: serde::de::Error,
     {
-        self.erased_visit_i64(v).map_err(unerase)
+        self.erased_visit_i64(v).map_err(|err| self.unerase(err))
     }
 
     fn visit_i128<E>(self, v: i128) -> Result<Out, E>
     where
         E: serde::de::Error,
     {
-        self.erased_visit_i128(v).map_err(unerase)
+        self.erased_visit_i128(v).map_err(|err| self.unerase(err))
     }
 
     fn visit_u8<E>(self, v: u8) -> Result<Out, E>
     where
         E: serde::de::Error,
     {
-        self.erased_visit_u8(v).map_err(unerase)
+        self.erased_visit_u8(v).map_err(|err| self.unerase(err))
     }
 
     fn visit_u16<E>(self, v: u16) -> Result<Out, E>
     where
         E: serde::de::Error,
     {
-        self.erased_visit_u16(v).map_err(unerase)
+        self.erased_visit_u16(v).map_err(|err| self.unerase(err))
     }
 
     fn visit_u32<E>(self, v: u32) -> Result<Out, E>
     where
         E: serde::de::Error,
     {
-        self.erased_visit_u32(v).map_err(unerase)
+        self.erased_visit_u32(v).map_err(|err| self.unerase(err))
     }
 
     fn visit_u64<E>(self, v: u64) -> Result<Out, E>
     where
         E: serde::de::Error,
     {
-        self.erased_visit_u64(v).map_err(unerase)
+        self.erased_visit_u64(v).map_err(|err| self.unerase(err))
     }
 
     fn visit_u128<E>(self, v: u128) -> Result<Out, E>
     where
         E: serde::de::Error,
     {
-        self.erased_visit_u128(v).map_err(unerase)
+        self.erased_visit_u128(v).map_err(|err| self.unerase(err))
     }
 
     fn visit_f32<E>(self, v: f32) -> Result<Out, E>
     where
         E: serde::de::Error,
     {
-        self.erased_visit_f32(v).map_err(unerase)
+        self.erased_visit_f32(v).map_err(|err| self.unerase(err))
     }
 
     fn visit_f64<E>(self, v: f64) -> Result<Out, E>
     where
         E: serde::de::Error,
     {
-        self.erased_visit_f64(v).map_err(unerase)
+        self.erased_visit_f64(v).map_err(|err| self.unerase(err))
     }
 
     fn visit_char<E>(self, v: char) -> Result<Out, E>
     where
         E: serde::de::Error,
     {
-        self.erased_visit_char(v).map_err(unerase)
+        self.erased_visit_char(v).map_err(|err| self.unerase(err))
     }
 
     fn visit_str<E>(self, v: &str) -> Result<Out, E>
     where
         E: serde::de::Error,
     {
-        self.erased_visit_str(v).map_err(unerase)
+        self.erased_visit_str(v).map_err(|err| self.unerase(err))
     }
 
     fn visit_borrowed_str<E>(self, v: &'de str) -> Result<Out, E>
     where
         E: serde::de::Error,
     {
-        self.erased_visit_borrowed_str(v).map_err(unerase)
+        self.erased_visit_borrowed_str(v)
+            .map_err(|err| self.unerase(err))
     }
 
     #[cfg(feature = "alloc")]
@@ -1238,21 +1736,22 @@ impl<'de> serde::de::Visitor<'de> for &mut (dyn Visitor<'de> + '_) {
     where
         E: serde::de::Error,
     {
-        self.erased_visit_string(v).map_err(unerase)
+        self.erased_visit_string(v).map_err(|err| self.unerase(err))
     }
 
     fn visit_bytes<E>(self, v: &[u8]) -> Result<Out, E>
     where
         E: serde::de::Error,
     {
-        self.erased_visit_bytes(v).map_err(unerase)
+        self.erased_visit_bytes(v).map_err(|err| self.unerase(err))
     }
 
     fn visit_borrowed_bytes<E>(self, v: &'de [u8]) -> Result<Out, E>
     where
         E: serde::de::Error,
     {
-        self.erased_visit_borrowed_bytes(v).map_err(unerase)
+        self.erased_visit_borrowed_bytes(v)
+            .map_err(|err| self.unerase(err))
     }
 
     #[cfg(feature = "alloc")]
@@ -1260,14 +1759,15 @@ impl<'de> serde::de::Visitor<'de> for &mut (dyn Visitor<'de> + '_) {
     where
         E: serde::de::Error,
     {
-        self.erased_visit_byte_buf(v).map_err(unerase)
+        self.erased_visit_byte_buf(v)
+            .map_err(|err| self.unerase(err))
     }
 
     fn visit_none<E>(self) -> Result<Out, E>
     where
         E: serde::de::Error,
     {
-        self.erased_visit_none().map_err(unerase)
+        self.erased_visit_none().map_err(|err| self.unerase(err))
     }
 
     fn visit_some<D>(self, deserializer: D) -> Result<Out, D::Error>
@@ -1275,14 +1775,15 @@ impl<'de> serde::de::Visitor<'de> for &mut (dyn Visitor<'de> + '_) {
         D: serde::Deserializer<'de>,
     {
         let mut erased = erase::Deserializer::new(deserializer);
-        self.erased_visit_some(&mut erased).map_err(unerase)
+        self.erased_visit_some(&mut erased)
+            .map_err(|err| self.unerase(err))
     }
 
     fn visit_unit<E>(self) -> Result<Out, E>
     where
         E: serde::de::Error,
     {
-        self.erased_visit_unit().map_err(unerase)
+        self.erased_visit_unit().map_err(|err| self.unerase(err))
     }
 
     fn visit_newtype_struct<D>(self, deserializer: D) -> Result<Out, D::Error>
@@ -1291,7 +1792,7 @@ impl<'de> serde::de::Visitor<'de> for &mut (dyn Visitor<'de> + '_) {
     {
         let mut erased = erase::Deserializer::new(deserializer);
         self.erased_visit_newtype_struct(&mut erased)
-            .map_err(unerase)
+            .map_err(|err| self.unerase(err))
     }
 
     fn visit_seq<V>(self, seq: V) -> Result<Out, V::Error>
@@ -1299,7 +1800,8 @@ impl<'de> serde::de::Visitor<'de> for &mut (dyn Visitor<'de> + '_) {
         V: serde::de::SeqAccess<'de>,
     {
         let mut erased = erase::SeqAccess::new(seq);
-        self.erased_visit_seq(&mut erased).map_err(unerase)
+        self.erased_visit_seq(&mut erased)
+            .map_err(|err| self.unerase(err))
     }
 
     fn visit_map<V>(self, map: V) -> Result<Out, V::Error>
@@ -1307,7 +1809,8 @@ impl<'de> serde::de::Visitor<'de> for &mut (dyn Visitor<'de> + '_) {
         V: serde::de::MapAccess<'de>,
     {
         let mut erased = erase::MapAccess::new(map);
-        self.erased_visit_map(&mut erased).map_err(unerase)
+        self.erased_visit_map(&mut erased)
+            .map_err(|err| self.unerase(err))
     }
 
     fn visit_enum<V>(self, data: V) -> Result<Out, V::Error>
@@ -1315,7 +1818,8 @@ impl<'de> serde::de::Visitor<'de> for &mut (dyn Visitor<'de> + '_) {
         V: serde::de::EnumAccess<'de>,
     {
         let mut erased = erase::EnumAccess::new(data);
-        self.erased_visit_enum(&mut erased).map_err(unerase)
+        self.erased_visit_enum(&mut erased)
+            .map_err(|err| self.unerase(err))
     }
 }
 
@@ -1387,12 +1891,16 @@ impl<'de> serde::de::EnumAccess<'de> for &mut (dyn EnumAccess<'de> + '_) {
     }
 }
 
+/// The variant access produced by an [`EnumAccess`], usable as a Serde
+/// `VariantAccess`.
 pub struct Variant<'de> {
-    data: Any,
-    unit_variant: unsafe fn(Any) -> Result<(), Error>,
-    visit_newtype: unsafe fn(Any, seed: &mut dyn DeserializeSeed<'de>) -> Result<Out, Error>,
-    tuple_variant: unsafe fn(Any, len: usize, visitor: &mut dyn Visitor<'de>) -> Result<Out, Error>,
-    struct_variant: unsafe fn(
+    pub(crate) data: Any,
+    pub(crate) unit_variant: unsafe fn(Any) -> Result<(), Error>,
+    pub(crate) visit_newtype:
+        unsafe fn(Any, seed: &mut dyn DeserializeSeed<'de>) -> Result<Out, Error>,
+    pub(crate) tuple_variant:
+        unsafe fn(Any, len: usize, visitor: &mut dyn Visitor<'de>) -> Result<Out, Error>,
+    pub(crate) struct_variant: unsafe fn(
         Any,
         fields: &'static [&'static str],
         visitor: &mut dyn Visitor<'de>,
@@ -1567,6 +2075,10 @@ macro_rules! deref_erased_deserializer {
             fn erased_is_human_readable(&self) -> bool {
                 (**self).erased_is_human_readable()
             }
+
+            fn erased_as_deserializer(&mut self) -> &mut dyn Deserializer<'de> {
+                self
+            }
         }
 
         impl<'de $(, $T)*> Sealed for $ty $(where $($where)*)? {}
@@ -1576,12 +2088,137 @@ macro_rules! deref_erased_deserializer {
 deref_erased_deserializer!(<'de, T> Deserializer<'de> for &mut T where T: ?Sized + Deserializer<'de>);
 deref_erased_deserializer!(<'de, T> Deserializer<'de> for Box<T> where T: ?Sized + Deserializer<'de>);
 
+// Each call erases a fresh `&mut D`, which is consumed by that one call.
+macro_rules! erased_deserialize_owned {
+    ($($erased:ident($($arg:ident: $ty:ty),*);)*) => {
+        $(
+            fn $erased(&mut self, $($arg: $ty,)* visitor: &mut dyn Visitor<'de>) -> Result<Out, Error> {
+                erase::Deserializer::new(&mut self.deserializer).$erased($($arg,)* visitor)
+            }
+        )*
+    };
+}
+
+impl<'de, D> Deserializer<'de> for erase::OwnedDeserializer<D>
+where
+    for<'a> &'a mut D: serde::Deserializer<'de>,
+{
+    erased_deserialize_owned! {
+        erased_deserialize_any();
+        erased_deserialize_bool();
+        erased_deserialize_i8();
+        erased_deserialize_i16();
+        erased_deserialize_i32();
+        erased_deserialize_i64();
+        erased_deserialize_i128();
+        erased_deserialize_u8();
+        erased_deserialize_u16();
+        erased_deserialize_u32();
+        erased_deserialize_u64();
+        erased_deserialize_u128();
+        erased_deserialize_f32();
+        erased_deserialize_f64();
+        erased_deserialize_char();
+        erased_deserialize_str();
+        erased_deserialize_string();
+        erased_deserialize_bytes();
+        erased_deserialize_byte_buf();
+        erased_deserialize_option();
+        erased_deserialize_unit();
+        erased_deserialize_unit_struct(name: &'static str);
+        erased_deserialize_newtype_struct(name: &'static str);
+        erased_deserialize_seq();
+        erased_deserialize_tuple(len: usize);
+        erased_deserialize_tuple_struct(name: &'static str, len: usize);
+        erased_deserialize_map();
+        erased_deserialize_struct(name: &'static str, fields: &'static [&'static str]);
+        erased_deserialize_identifier();
+        erased_deserialize_enum(name: &'static str, variants: &'static [&'static str]);
+        erased_deserialize_ignored_any();
+    }
+
+    fn erased_is_human_readable(&self) -> bool {
+        self.human_readable
+    }
+
+    fn erased_as_deserializer(&mut self) -> &mut dyn Deserializer<'de> {
+        self
+    }
+}
+
+impl<D> Sealed for erase::OwnedDeserializer<D> {}
+
+macro_rules! erased_deserialize_owned_with_end {
+    ($($erased:ident($($arg:ident: $ty:ty),*);)*) => {
+        $(
+            fn $erased(&mut self, $($arg: $ty,)* visitor: &mut dyn Visitor<'de>) -> Result<Out, Error> {
+                let out = self.owned.$erased($($arg,)* visitor)?;
+                (self.end)(&mut self.owned.deserializer).map_err(erase)?;
+                Ok(out)
+            }
+        )*
+    };
+}
+
+impl<'de, D, F, E> Deserializer<'de> for erase::OwnedDeserializerWithEnd<D, F>
+where
+    for<'a> &'a mut D: serde::Deserializer<'de>,
+    F: FnMut(&mut D) -> Result<(), E>,
+    E: serde::de::Error,
+{
+    erased_deserialize_owned_with_end! {
+        erased_deserialize_any();
+        erased_deserialize_bool();
+        erased_deserialize_i8();
+        erased_deserialize_i16();
+        erased_deserialize_i32();
+        erased_deserialize_i64();
+        erased_deserialize_i128();
+        erased_deserialize_u8();
+        erased_deserialize_u16();
+        erased_deserialize_u32();
+        erased_deserialize_u64();
+        erased_deserialize_u128();
+        erased_deserialize_f32();
+        erased_deserialize_f64();
+        erased_deserialize_char();
+        erased_deserialize_str();
+        erased_deserialize_string();
+        erased_deserialize_bytes();
+        erased_deserialize_byte_buf();
+        erased_deserialize_option();
+        erased_deserialize_unit();
+        erased_deserialize_unit_struct(name: &'static str);
+        erased_deserialize_newtype_struct(name: &'static str);
+        erased_deserialize_seq();
+        erased_deserialize_tuple(len: usize);
+        erased_deserialize_tuple_struct(name: &'static str, len: usize);
+        erased_deserialize_map();
+        erased_deserialize_struct(name: &'static str, fields: &'static [&'static str]);
+        erased_deserialize_identifier();
+        erased_deserialize_enum(name: &'static str, variants: &'static [&'static str]);
+        erased_deserialize_ignored_any();
+    }
+
+    fn erased_is_human_readable(&self) -> bool {
+        self.owned.human_readable
+    }
+
+    fn erased_as_deserializer(&mut self) -> &mut dyn Deserializer<'de> {
+        self
+    }
+}
+
+impl<D, F> Sealed for erase::OwnedDeserializerWithEnd<D, F> {}
+
 // TEST ////////////////////////////////////////////////////////////////////////
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use alloc::borrow::ToOwned;
+    use alloc::format;
+    use alloc::string::ToString;
     use core::fmt::Debug;
     use serde_derive::Deserialize;
 
@@ -1643,6 +2280,119 @@ mod tests {
         test_json::<&str>(&bytes);
     }
 
+    #[test]
+    fn test_error_kind() {
+        #[derive(Deserialize, Debug)]
+        struct Outer {
+            #[allow(dead_code)]
+            users: Vec<User>,
+        }
+
+        #[derive(Deserialize, Debug)]
+        struct User {
+            #[allow(dead_code)]
+            id: u8,
+        }
+
+        let mut de = serde_json::Deserializer::from_slice(br#"{"users": [{"id": 300}]}"#);
+        let de = &mut <dyn Deserializer>::erase(&mut de);
+        let err = deserialize::<Outer>(de).unwrap_err();
+        assert_eq!(err.kind(), crate::ErrorKind::InvalidValue);
+        assert_eq!(err.unexpected(), Some(serde::de::Unexpected::Unsigned(300)));
+        assert_eq!(err.expected(), Some("u8"));
+        assert_eq!(err.field(), None);
+        assert_eq!(
+            err.to_string(),
+            "invalid value: integer `300`, expected u8 at line 1 column 21",
+        );
+
+        let mut de = serde_json::Deserializer::from_slice(br#"{"users": [{}]}"#);
+        let de = &mut <dyn Deserializer>::erase_preserving_errors(&mut de);
+        let err = deserialize::<Outer>(de).unwrap_err();
+        assert_eq!(err.kind(), crate::ErrorKind::MissingField);
+        assert_eq!(err.field(), Some("id"));
+        assert!(err.downcast_ref::<serde_json::Error>().is_some());
+
+        // Rejected by serde_json itself, not by the Deserialize impl.
+        let mut de = serde_json::Deserializer::from_slice(br#"{"users": [{"id": "x"}]}"#);
+        let de = &mut <dyn Deserializer>::erase(&mut de);
+        let err = deserialize::<Outer>(de).unwrap_err();
+        assert_eq!(err.kind(), crate::ErrorKind::Custom);
+        assert_eq!(err.unexpected(), None);
+    }
+
+    #[test]
+    fn test_suggestion() {
+        #[derive(Deserialize, Debug)]
+        enum Level {
+            Debug,
+            Warning,
+        }
+
+        let mut de = serde_json::Deserializer::from_slice(br#""Warnign""#);
+        let de = &mut <dyn Deserializer>::erase(&mut de);
+        let err = deserialize::<Level>(de).unwrap_err();
+        assert_eq!(err.variant(), Some("Warnign"));
+        assert_eq!(err.suggestion(), Some("Warning"));
+
+        let mut de = serde_json::Deserializer::from_slice(br#""Trace""#);
+        let de = &mut <dyn Deserializer>::erase(&mut de);
+        let err = deserialize::<Level>(de).unwrap_err();
+        assert_eq!(err.variant(), Some("Trace"));
+        assert_eq!(err.suggestion(), None);
+    }
+
+    #[test]
+    fn test_preserved_error() {
+        let mut de = serde_json::Deserializer::from_slice(b"[true]");
+        let de = &mut <dyn Deserializer>::erase(&mut de);
+        let err = deserialize::<bool>(de).unwrap_err();
+        let err = err.downcast::<serde_json::Error>().unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "invalid type: sequence, expected a boolean at line 1 column 0"
+        );
+
+        let mut de = serde_json::Deserializer::from_slice(b"[true]");
+        let de = &mut <dyn Deserializer>::erase_preserving_errors(&mut de);
+        let err = deserialize::<bool>(de).unwrap_err();
+        assert!(err.downcast_ref::<serde_json::Value>().is_none());
+        let expected = format!("{err:?}");
+        let err = err.downcast::<serde_json::Error>().unwrap();
+        assert_eq!(format!("{err:?}"), expected);
+        assert!(err.is_data());
+    }
+
+    #[test]
+    fn test_error_source() {
+        use std::error::Error as _;
+
+        #[derive(Deserialize, Debug)]
+        #[allow(dead_code)]
+        struct S {
+            a: u8,
+            b: u8,
+        }
+
+        let mut de = serde_json::Deserializer::from_slice(br#"{"a": 1}"#);
+        let de = &mut <dyn Deserializer>::erase_preserving_errors(&mut de);
+        let err = deserialize::<S>(de).unwrap_err();
+
+        let mut source = err.source();
+        let json_error = loop {
+            let error = source.unwrap();
+            if let Some(json_error) = error.downcast_ref::<serde_json::Error>() {
+                break json_error;
+            }
+            source = error.source();
+        };
+        assert_eq!(json_error.classify(), serde_json::error::Category::Data);
+        assert_eq!(
+            json_error.to_string(),
+            "missing field `b` at line 1 column 8"
+        );
+    }
+
     #[test]
     fn assert_deserializer() {
         fn assert<'de, T: serde::Deserializer<'de>>() {}
@@ -1666,4 +2416,36 @@ mod tests {
         let _erased_deserializer = <dyn Deserializer>::erase(&mut json_deserializer);
         drop(json_deserializer);
     }
+
+    #[test]
+    fn test_erased_visitor_and_seed() {
+        use alloc::collections::BTreeMap as Map;
+        use alloc::string::String;
+        use core::marker::PhantomData;
+
+        let json = &mut serde_json::Deserializer::from_str(r#""a""#);
+        let mut deserializer = <dyn Deserializer>::erase(json);
+
+        let visitor = &mut <dyn Visitor>::erase(serde::de::IgnoredAny);
+        let out = deserializer.erased_deserialize_str(visitor).unwrap();
+        let serde::de::IgnoredAny = visitor.take_value(out);
+
+        let json = &mut serde_json::Deserializer::from_str(r#"{"a": 1}"#);
+        let mut deserializer = <dyn Deserializer>::erase(json);
+        let seed = &mut <dyn DeserializeSeed>::erase(PhantomData::<Map<String, u8>>);
+        let out = seed.erased_deserialize_seed(&mut deserializer).unwrap();
+        assert_eq!(seed.take_value(out)["a"], 1);
+    }
+
+    #[test]
+    #[should_panic = "invalid cast"]
+    fn test_take_value_mismatch() {
+        let json = &mut serde_json::Deserializer::from_str("1");
+        let mut deserializer = <dyn Deserializer>::erase(json);
+        let seed = <dyn DeserializeSeed>::erase(core::marker::PhantomData::<u8>);
+        let out = deserializer
+            .erased_deserialize_any(&mut <dyn Visitor>::erase(serde::de::IgnoredAny))
+            .unwrap();
+        seed.take_value(out);
+    }
 }
@@ -0,0 +1,418 @@
+use core::fmt;
+use core::marker::PhantomData;
+use serde::de::{
+    DeserializeSeed, Deserializer, EnumAccess, MapAccess, SeqAccess, VariantAccess, Visitor,
+};
+
+// Presents something that works with data borrowed for 'a as working with data
+// borrowed for 'static, by giving every borrowed string or byte slice to the
+// 'static side as a transient one. Nothing borrowed from the input survives
+// the call that sees it.
+//
+// The same wrapper serves as a Deserializer, SeqAccess, MapAccess, EnumAccess
+// and VariantAccess around the 'a side, and as a Visitor and DeserializeSeed
+// around the 'static side.
+pub struct Detach<'a, T> {
+    delegate: T,
+    lifetime: PhantomData<fn(&'a ())>,
+}
+
+impl<T> Detach<'_, T> {
+    pub(crate) fn new(delegate: T) -> Self {
+        Detach {
+            delegate,
+            lifetime: PhantomData,
+        }
+    }
+}
+
+macro_rules! detach_deserialize {
+    ($($method:ident($($arg:ident: $ty:ty),*);)*) => {
+        $(
+            fn $method<V>(self, $($arg: $ty,)* visitor: V) -> Result<V::Value, D::Error>
+            where
+                V: Visitor<'static>,
+            {
+                self.delegate.$method($($arg,)* Detach::new(visitor))
+            }
+        )*
+    };
+}
+
+impl<'a, D> Deserializer<'static> for Detach<'a, D>
+where
+    D: Deserializer<'a>,
+{
+    type Error = D::Error;
+
+    detach_deserialize! {
+        deserialize_any();
+        deserialize_bool();
+        deserialize_i8();
+        deserialize_i16();
+        deserialize_i32();
+        deserialize_i64();
+        deserialize_i128();
+        deserialize_u8();
+        deserialize_u16();
+        deserialize_u32();
+        deserialize_u64();
+        deserialize_u128();
+        deserialize_f32();
+        deserialize_f64();
+        deserialize_char();
+        deserialize_str();
+        deserialize_string();
+        deserialize_bytes();
+        deserialize_byte_buf();
+        deserialize_option();
+        deserialize_unit();
+        deserialize_unit_struct(name: &'static str);
+        deserialize_newtype_struct(name: &'static str);
+        deserialize_seq();
+        deserialize_tuple(len: usize);
+        deserialize_tuple_struct(name: &'static str, len: usize);
+        deserialize_map();
+        deserialize_struct(name: &'static str, fields: &'static [&'static str]);
+        deserialize_enum(name: &'static str, variants: &'static [&'static str]);
+        deserialize_identifier();
+        deserialize_ignored_any();
+    }
+
+    fn is_human_readable(&self) -> bool {
+        self.delegate.is_human_readable()
+    }
+}
+
+macro_rules! detach_visit {
+    ($($method:ident($ty:ty);)*) => {
+        $(
+            fn $method<E>(self, v: $ty) -> Result<V::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                self.delegate.$method(v)
+            }
+        )*
+    };
+}
+
+impl<'a, V> Visitor<'a> for Detach<'a, V>
+where
+    V: Visitor<'static>,
+{
+    type Value = V::Value;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        self.delegate.expecting(formatter)
+    }
+
+    detach_visit! {
+        visit_bool(bool);
+        visit_i8(i8);
+        visit_i16(i16);
+        visit_i32(i32);
+        visit_i64(i64);
+        visit_i128(i128);
+        visit_u8(u8);
+        visit_u16(u16);
+        visit_u32(u32);
+        visit_u64(u64);
+        visit_u128(u128);
+        visit_f32(f32);
+        visit_f64(f64);
+        visit_char(char);
+        visit_str(&str);
+        visit_string(alloc::string::String);
+        visit_bytes(&[u8]);
+        visit_byte_buf(alloc::vec::Vec<u8>);
+    }
+
+    fn visit_borrowed_str<E>(self, v: &'a str) -> Result<V::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        self.delegate.visit_str(v)
+    }
+
+    fn visit_borrowed_bytes<E>(self, v: &'a [u8]) -> Result<V::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        self.delegate.visit_bytes(v)
+    }
+
+    fn visit_none<E>(self) -> Result<V::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        self.delegate.visit_none()
+    }
+
+    fn visit_some<D>(self, deserializer: D) -> Result<V::Value, D::Error>
+    where
+        D: Deserializer<'a>,
+    {
+        self.delegate.visit_some(Detach::new(deserializer))
+    }
+
+    fn visit_unit<E>(self) -> Result<V::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        self.delegate.visit_unit()
+    }
+
+    fn visit_newtype_struct<D>(self, deserializer: D) -> Result<V::Value, D::Error>
+    where
+        D: Deserializer<'a>,
+    {
+        self.delegate
+            .visit_newtype_struct(Detach::new(deserializer))
+    }
+
+    fn visit_seq<A>(self, seq: A) -> Result<V::Value, A::Error>
+    where
+        A: SeqAccess<'a>,
+    {
+        self.delegate.visit_seq(Detach::new(seq))
+    }
+
+    fn visit_map<A>(self, map: A) -> Result<V::Value, A::Error>
+    where
+        A: MapAccess<'a>,
+    {
+        self.delegate.visit_map(Detach::new(map))
+    }
+
+    fn visit_enum<A>(self, data: A) -> Result<V::Value, A::Error>
+    where
+        A: EnumAccess<'a>,
+    {
+        self.delegate.visit_enum(Detach::new(data))
+    }
+}
+
+impl<'a, T> DeserializeSeed<'a> for Detach<'a, T>
+where
+    T: DeserializeSeed<'static>,
+{
+    type Value = T::Value;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<T::Value, D::Error>
+    where
+        D: Deserializer<'a>,
+    {
+        self.delegate.deserialize(Detach::new(deserializer))
+    }
+}
+
+impl<'a, A> SeqAccess<'static> for Detach<'a, A>
+where
+    A: SeqAccess<'a>,
+{
+    type Error = A::Error;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, A::Error>
+    where
+        T: DeserializeSeed<'static>,
+    {
+        self.delegate.next_element_seed(Detach::new(seed))
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        self.delegate.size_hint()
+    }
+}
+
+impl<'a, A> MapAccess<'static> for Detach<'a, A>
+where
+    A: MapAccess<'a>,
+{
+    type Error = A::Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, A::Error>
+    where
+        K: DeserializeSeed<'static>,
+    {
+        self.delegate.next_key_seed(Detach::new(seed))
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, A::Error>
+    where
+        V: DeserializeSeed<'static>,
+    {
+        self.delegate.next_value_seed(Detach::new(seed))
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        self.delegate.size_hint()
+    }
+}
+
+impl<'a, A> EnumAccess<'static> for Detach<'a, A>
+where
+    A: EnumAccess<'a>,
+{
+    type Error = A::Error;
+    type Variant = Detach<'a, A::Variant>;
+
+    fn variant_seed<V>(self, seed: V) -> Result<(V::Value, Self::Variant), A::Error>
+    where
+        V: DeserializeSeed<'static>,
+    {
+        let (value, variant) = self.delegate.variant_seed(Detach::new(seed))?;
+        Ok((value, Detach::new(variant)))
+    }
+}
+
+impl<'a, A> VariantAccess<'static> for Detach<'a, A>
+where
+    A: VariantAccess<'a>,
+{
+    type Error = A::Error;
+
+    fn unit_variant(self) -> Result<(), A::Error> {
+        self.delegate.unit_variant()
+    }
+
+    fn newtype_variant_seed<T>(self, seed: T) -> Result<T::Value, A::Error>
+    where
+        T: DeserializeSeed<'static>,
+    {
+        self.delegate.newtype_variant_seed(Detach::new(seed))
+    }
+
+    fn tuple_variant<V>(self, len: usize, visitor: V) -> Result<V::Value, A::Error>
+    where
+        V: Visitor<'static>,
+    {
+        self.delegate.tuple_variant(len, Detach::new(visitor))
+    }
+
+    fn struct_variant<V>(
+        self,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, A::Error>
+    where
+        V: Visitor<'static>,
+    {
+        self.delegate.struct_variant(fields, Detach::new(visitor))
+    }
+}
+
+// TEST ////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use crate::Deserializer;
+    use alloc::borrow::ToOwned;
+    use alloc::collections::BTreeMap;
+    use alloc::string::{String, ToString};
+    use alloc::vec;
+    use alloc::vec::Vec;
+    use core::fmt;
+    use serde::de::{DeserializeOwned, Deserializer as _, Visitor};
+    use serde_derive::Deserialize;
+
+    fn owned<T: DeserializeOwned>(format: &mut dyn Deserializer<'static>) -> crate::Result<T> {
+        crate::deserialize(format)
+    }
+
+    // Records how the format handed over the string.
+    struct Str;
+
+    impl<'de> Visitor<'de> for Str {
+        type Value = (&'static str, String);
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("a string")
+        }
+
+        fn visit_str<E>(self, v: &str) -> Result<Self::Value, E> {
+            Ok(("transient", v.to_owned()))
+        }
+
+        fn visit_borrowed_str<E>(self, v: &'de str) -> Result<Self::Value, E> {
+            Ok(("borrowed", v.to_owned()))
+        }
+    }
+
+    #[test]
+    fn test_borrowed_str() {
+        let json = &mut serde_json::Deserializer::from_str(r#""x""#);
+        let format: &mut dyn Deserializer = &mut <dyn Deserializer>::erase(json);
+        let string = format.deserialize_str(Str).unwrap();
+        assert_eq!(string, ("borrowed", "x".to_owned()));
+
+        let json = &mut serde_json::Deserializer::from_str(r#""x""#);
+        let format: &mut dyn Deserializer = &mut <dyn Deserializer>::erase(json);
+        let format: &mut dyn Deserializer = &mut <dyn Deserializer>::detach(format);
+        let string = format.deserialize_str(Str).unwrap();
+        assert_eq!(string, ("transient", "x".to_owned()));
+    }
+
+    #[test]
+    fn test_nested() {
+        #[derive(Deserialize, PartialEq, Debug)]
+        enum Body {
+            Text(String),
+            Form(BTreeMap<String, Vec<String>>),
+        }
+
+        #[derive(Deserialize, PartialEq, Debug)]
+        struct Request {
+            path: String,
+            query: Option<String>,
+            body: Body,
+        }
+
+        let buffer = serde_json::to_vec(&serde_json::json!({
+            "path": "/search",
+            "query": "q=1",
+            "body": {"Form": {"tags": ["a", "b"]}},
+        }))
+        .unwrap();
+        let json = &mut serde_json::Deserializer::from_slice(&buffer);
+        let format: &mut dyn Deserializer = &mut <dyn Deserializer>::erase(json);
+        let request: Request = owned(&mut <dyn Deserializer>::detach(format)).unwrap();
+
+        let mut form = BTreeMap::new();
+        form.insert("tags".to_owned(), vec!["a".to_owned(), "b".to_owned()]);
+        let expected = Request {
+            path: "/search".to_owned(),
+            query: Some("q=1".to_owned()),
+            body: Body::Form(form),
+        };
+        assert_eq!(request, expected);
+        drop(buffer);
+
+        let cbor = serde_cbor::to_vec(&serde_json::json!({"Text": "hi"})).unwrap();
+        let cbor = &mut serde_cbor::Deserializer::from_slice(&cbor);
+        let body: Body = owned(&mut <dyn Deserializer>::detach(cbor)).unwrap();
+        assert_eq!(body, Body::Text("hi".to_owned()));
+    }
+
+    #[test]
+    fn test_borrowing_type() {
+        let json = &mut serde_json::Deserializer::from_str(r#""x""#);
+        let format = &mut <dyn Deserializer>::detach(json);
+        let error = crate::deserialize::<&str>(format).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "invalid type: string \"x\", expected a borrowed string at line 1 column 3",
+        );
+    }
+
+    #[test]
+    fn test_human_readable() {
+        let json = &mut serde_json::Deserializer::from_str("null");
+        let format = <dyn Deserializer>::detach(json);
+        assert!((&format as &dyn Deserializer).erased_is_human_readable());
+
+        let cbor = &mut serde_cbor::Deserializer::from_slice(&[0xf6]);
+        let format = <dyn Deserializer>::detach(cbor);
+        assert!(!(&format as &dyn Deserializer).erased_is_human_readable());
+    }
+}
@@ -1,11 +1,34 @@
+use crate::path::Path;
 use alloc::borrow::ToOwned;
 use alloc::boxed::Box;
 use alloc::string::{String, ToString};
 use alloc::vec::Vec;
+use core::any::Any;
 use core::fmt::{self, Debug, Display};
+use core::mem::ManuallyDrop;
+use core::ptr;
 use serde::de::Expected;
+use serde::ser::StdError;
 
 /// Error when a `Serializer` or `Deserializer` trait object fails.
+///
+/// If the trait object was created with `erase_preserving_errors`, the
+/// format's own error type is kept inside and can be recovered using
+/// [`downcast_ref`][Error::downcast_ref] or [`downcast`][Error::downcast].
+/// With the `std` feature it is also the `source()` of this error, so code
+/// that only holds a `&dyn std::error::Error` can walk down to it.
+///
+/// ```rust
+/// use erased_serde::Deserializer;
+///
+/// let json = &mut serde_json::Deserializer::from_slice(b"  -1");
+/// let format = &mut <dyn Deserializer>::erase_preserving_errors(json);
+///
+/// let error = erased_serde::deserialize::<u8>(format).unwrap_err();
+/// let json_error = error.downcast_ref::<serde_json::Error>().unwrap();
+/// assert!(json_error.is_data());
+/// assert_eq!(json_error.column(), 4);
+/// ```
 pub struct Error {
     imp: Box<ErrorImpl>,
 }
@@ -17,10 +40,46 @@ pub(crate) fn erase_de<E: serde::de::Error>(e: E) -> Error {
     serde::de::Error::custom(e)
 }
 
-pub(crate) fn unerase_de<E: serde::de::Error>(e: Error) -> E {
+pub(crate) fn unerase_de<E: serde::de::Error>(e: &Error) -> E {
     e.as_serde_de_error()
 }
 
+// Like unerase_de, but when the deserializer's error type is erased_serde::Error
+// itself, hands the error back untouched so none of its structure is lost.
+pub(crate) fn unerase_de_owned<E: serde::de::Error>(e: Error) -> E {
+    if typeid::of::<E>() == typeid::of::<Error>() {
+        let e = ManuallyDrop::new(e);
+        unsafe { ptr::read(ptr::addr_of!(*e).cast::<E>()) }
+    } else {
+        unerase_de(&e)
+    }
+}
+
+// Attaches the error that a Visitor or DeserializeSeed handed to the format, so
+// that its kind survives the trip back out through the format's error type.
+pub(crate) fn with_cause(error: Error, cause: Option<Error>) -> Error {
+    match cause {
+        Some(cause) => {
+            let imp = Box::new(ErrorImpl::Caused { error, cause });
+            Error { imp }
+        }
+        None => error,
+    }
+}
+
+pub(crate) fn with_path(error: Error, path: Path) -> Error {
+    let imp = Box::new(ErrorImpl::Located { path, error });
+    Error { imp }
+}
+
+pub(crate) fn preserve<E>(e: E) -> Error
+where
+    E: Preservable,
+{
+    let imp = Box::new(ErrorImpl::Preserved(Box::new(e)));
+    Error { imp }
+}
+
 impl Display for Error {
     fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
         let error = self.as_serde_de_error::<serde::de::value::Error>();
@@ -30,14 +89,106 @@ impl Display for Error {
 
 impl Debug for Error {
     fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self.imp.as_ref() {
+            ErrorImpl::Preserved(error) => return Debug::fmt(error, formatter),
+            ErrorImpl::Caused { error, .. } => return Debug::fmt(error, formatter),
+            _ => {}
+        }
         let error = self.as_serde_de_error::<serde::de::value::Error>();
         Debug::fmt(&error, formatter)
     }
 }
 
-impl serde::ser::StdError for Error {}
+impl StdError for Error {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        match self.imp.as_ref() {
+            #[cfg(feature = "std")]
+            ErrorImpl::Preserved(error) => Some((**error).as_std_error()),
+            ErrorImpl::Caused { error, .. } | ErrorImpl::Located { error, .. } => Some(error),
+            _ => None,
+        }
+    }
+}
+
+// The errors that a format can hand over to be preserved. Serde's error traits
+// only require std::error::Error when its std feature is enabled, so without it
+// a preserved error cannot be reached through Error::source.
+#[cfg(feature = "std")]
+pub(crate) trait Preservable: StdError + Send + Sync + 'static {}
+
+#[cfg(feature = "std")]
+impl<E> Preservable for E where E: StdError + Send + Sync + 'static {}
+
+#[cfg(not(feature = "std"))]
+pub(crate) trait Preservable: Debug + Display + Send + Sync + 'static {}
+
+#[cfg(not(feature = "std"))]
+impl<E> Preservable for E where E: Debug + Display + Send + Sync + 'static {}
+
+// A format's own error type, held onto for downcasting.
+trait Preserved: Preservable {
+    #[cfg(feature = "std")]
+    fn as_std_error(&self) -> &(dyn StdError + 'static);
+    fn as_any(&self) -> &dyn Any;
+    fn into_any(self: Box<Self>) -> Box<dyn Any>;
+}
+
+impl<E> Preserved for E
+where
+    E: Preservable,
+{
+    #[cfg(feature = "std")]
+    fn as_std_error(&self) -> &(dyn StdError + 'static) {
+        self
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn into_any(self: Box<Self>) -> Box<dyn Any> {
+        self
+    }
+}
+
+/// The category of an [`Error`], as reported by [`Error::kind`].
+///
+/// The details that go with each kind are available from accessors on
+/// `Error`, such as [`Error::field`] and [`Error::unexpected`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ErrorKind {
+    /// A free-form message, either from a `custom` error or from the format
+    /// itself.
+    Custom,
+    /// Input of the wrong type, as reported by `invalid_type`.
+    InvalidType,
+    /// Input of the right type but the wrong value, as reported by
+    /// `invalid_value`.
+    InvalidValue,
+    /// A sequence or map with the wrong number of elements, as reported by
+    /// `invalid_length`.
+    InvalidLength,
+    /// An enum variant that is not one of the expected ones.
+    UnknownVariant,
+    /// A struct field that is not one of the expected ones.
+    UnknownField,
+    /// A struct field that is required but was not present in the input.
+    MissingField,
+    /// A struct field that appeared more than once in the input.
+    DuplicateField,
+}
 
 enum ErrorImpl {
+    Preserved(Box<dyn Preserved>),
+    Caused {
+        error: Error,
+        cause: Error,
+    },
+    Located {
+        path: Path,
+        error: Error,
+    },
     Custom(String),
     InvalidType {
         unexpected: Unexpected,
@@ -153,8 +304,232 @@ impl serde::de::Error for Error {
 }
 
 impl Error {
+    /// The category of this error.
+    ///
+    /// Errors raised by a `Deserialize` impl keep their kind even after passing
+    /// through a type-erased `Deserializer`, so a missing field deep inside a
+    /// JSON document still reports `ErrorKind::MissingField` here, while its
+    /// `Display` carries the format's usual message and position.
+    ///
+    /// Errors which the format raises on its own come through as
+    /// `ErrorKind::Custom`. For example `serde_json` rejects a string in place
+    /// of a number before the `Deserialize` impl ever sees it. Such errors can
+    /// still be inspected by erasing the format with `erase_preserving_errors`
+    /// and downcasting.
+    ///
+    /// ```rust
+    /// use erased_serde::{Deserializer, ErrorKind};
+    /// use serde_derive::Deserialize;
+    ///
+    /// #[derive(Deserialize, Debug)]
+    /// #[serde(deny_unknown_fields)]
+    /// struct Request {
+    ///     user_id: u64,
+    /// }
+    ///
+    /// let json = &mut serde_json::Deserializer::from_slice(br#"{"userid": 1}"#);
+    /// let format = &mut <dyn Deserializer>::erase(json);
+    ///
+    /// let error = erased_serde::deserialize::<Request>(format).unwrap_err();
+    /// assert_eq!(error.kind(), ErrorKind::UnknownField);
+    /// assert_eq!(error.field(), Some("userid"));
+    /// assert_eq!(error.expected_one_of(), Some(&["user_id"][..]));
+    /// ```
+    #[must_use]
+    pub fn kind(&self) -> ErrorKind {
+        match self.structured() {
+            ErrorImpl::Preserved(_)
+            | ErrorImpl::Caused { .. }
+            | ErrorImpl::Located { .. }
+            | ErrorImpl::Custom(_) => ErrorKind::Custom,
+            ErrorImpl::InvalidType { .. } => ErrorKind::InvalidType,
+            ErrorImpl::InvalidValue { .. } => ErrorKind::InvalidValue,
+            ErrorImpl::InvalidLength { .. } => ErrorKind::InvalidLength,
+            ErrorImpl::UnknownVariant { .. } => ErrorKind::UnknownVariant,
+            ErrorImpl::UnknownField { .. } => ErrorKind::UnknownField,
+            ErrorImpl::MissingField { .. } => ErrorKind::MissingField,
+            ErrorImpl::DuplicateField { .. } => ErrorKind::DuplicateField,
+        }
+    }
+
+    /// The name of the offending field, for `UnknownField`, `MissingField`
+    /// and `DuplicateField` errors.
+    #[must_use]
+    pub fn field(&self) -> Option<&str> {
+        match self.structured() {
+            ErrorImpl::UnknownField { field, .. } => Some(field),
+            ErrorImpl::MissingField { field } | ErrorImpl::DuplicateField { field } => Some(field),
+            _ => None,
+        }
+    }
+
+    /// The name of the offending variant, for `UnknownVariant` errors.
+    #[must_use]
+    pub fn variant(&self) -> Option<&str> {
+        match self.structured() {
+            ErrorImpl::UnknownVariant { variant, .. } => Some(variant),
+            _ => None,
+        }
+    }
+
+    /// The input that was received instead of what was expected, for
+    /// `InvalidType` and `InvalidValue` errors.
+    #[must_use]
+    pub fn unexpected(&self) -> Option<serde::de::Unexpected> {
+        match self.structured() {
+            ErrorImpl::InvalidType { unexpected, .. }
+            | ErrorImpl::InvalidValue { unexpected, .. } => Some(unexpected.as_serde()),
+            _ => None,
+        }
+    }
+
+    /// A description of what was expected, for `InvalidType`, `InvalidValue`
+    /// and `InvalidLength` errors.
+    #[must_use]
+    pub fn expected(&self) -> Option<&str> {
+        match self.structured() {
+            ErrorImpl::InvalidType { expected, .. }
+            | ErrorImpl::InvalidValue { expected, .. }
+            | ErrorImpl::InvalidLength { expected, .. } => Some(expected),
+            _ => None,
+        }
+    }
+
+    /// The names that would have been accepted, for `UnknownField` and
+    /// `UnknownVariant` errors.
+    #[must_use]
+    pub fn expected_one_of(&self) -> Option<&'static [&'static str]> {
+        match self.structured() {
+            ErrorImpl::UnknownField { expected, .. }
+            | ErrorImpl::UnknownVariant { expected, .. } => Some(expected),
+            _ => None,
+        }
+    }
+
+    /// The length that was received, for `InvalidLength` errors.
+    #[must_use]
+    pub fn invalid_length(&self) -> Option<usize> {
+        match self.structured() {
+            ErrorImpl::InvalidLength { len, .. } => Some(*len),
+            _ => None,
+        }
+    }
+
+    /// The expected name closest to the one that was received, for
+    /// `UnknownField` and `UnknownVariant` errors, if any is close enough to
+    /// plausibly be what was meant.
+    ///
+    /// ```rust
+    /// use erased_serde::Deserializer;
+    /// use serde_derive::Deserialize;
+    ///
+    /// #[derive(Deserialize, Debug)]
+    /// #[serde(deny_unknown_fields)]
+    /// struct Config {
+    ///     timeout: u64,
+    ///     retries: u64,
+    /// }
+    ///
+    /// let json = &mut serde_json::Deserializer::from_slice(br#"{"timout": 1}"#);
+    /// let format = &mut <dyn Deserializer>::erase(json);
+    ///
+    /// let error = erased_serde::deserialize::<Config>(format).unwrap_err();
+    /// if let (Some(field), Some(suggestion)) = (error.field(), error.suggestion()) {
+    ///     let message = format!("unknown field `{field}`, did you mean `{suggestion}`?");
+    ///     assert_eq!(message, "unknown field `timout`, did you mean `timeout`?");
+    /// }
+    /// # assert_eq!(error.suggestion(), Some("timeout"));
+    /// ```
+    #[must_use]
+    pub fn suggestion(&self) -> Option<&'static str> {
+        let (name, expected) = match self.structured() {
+            ErrorImpl::UnknownField { field, expected } => (field, expected),
+            ErrorImpl::UnknownVariant { variant, expected } => (variant, expected),
+            _ => return None,
+        };
+        // Allow about one typo for every three characters, like rustc does
+        // for misspelled identifiers.
+        let threshold = (name.chars().count() / 3).max(1);
+        let mut best = None;
+        let mut best_distance = threshold + 1;
+        for candidate in *expected {
+            let distance = edit_distance(name, candidate);
+            if distance < best_distance {
+                best = Some(*candidate);
+                best_distance = distance;
+            }
+        }
+        best
+    }
+
+    /// Where in the value or input the error occurred, if the error was
+    /// produced by [`serialize_with_path`][crate::serialize_with_path] or by
+    /// deserializing through a [`PathDeserializer`][crate::PathDeserializer].
+    #[must_use]
+    pub fn path(&self) -> Option<&Path> {
+        match self.imp.as_ref() {
+            ErrorImpl::Located { path, .. } => Some(path),
+            ErrorImpl::Caused { cause, .. } => cause.path(),
+            _ => None,
+        }
+    }
+
+    fn structured(&self) -> &ErrorImpl {
+        match self.imp.as_ref() {
+            ErrorImpl::Caused { cause, .. } => cause.structured(),
+            ErrorImpl::Located { error, .. } => error.structured(),
+            imp => imp,
+        }
+    }
+
+    /// Borrow the format's own error, if this error was raised by a serializer
+    /// or deserializer erased with `erase_preserving_errors` and it has type
+    /// `E`.
+    #[must_use]
+    pub fn downcast_ref<E>(&self) -> Option<&E>
+    where
+        E: 'static,
+    {
+        match self.imp.as_ref() {
+            ErrorImpl::Preserved(error) => (**error).as_any().downcast_ref(),
+            ErrorImpl::Caused { error, .. } | ErrorImpl::Located { error, .. } => {
+                error.downcast_ref()
+            }
+            _ => None,
+        }
+    }
+
+    /// Take out the format's own error, if this error was raised by a
+    /// serializer or deserializer erased with `erase_preserving_errors` and it
+    /// has type `E`. Otherwise the original error is handed back.
+    pub fn downcast<E>(self) -> core::result::Result<E, Self>
+    where
+        E: 'static,
+    {
+        if self.downcast_ref::<E>().is_none() {
+            return Err(self);
+        }
+        match *self.imp {
+            ErrorImpl::Preserved(error) => match error.into_any().downcast() {
+                Ok(error) => Ok(*error),
+                Err(_) => unreachable!(),
+            },
+            ErrorImpl::Caused { error, .. } | ErrorImpl::Located { error, .. } => error.downcast(),
+            _ => unreachable!(),
+        }
+    }
+
     fn as_serde_de_error<E: serde::de::Error>(&self) -> E {
         match self.imp.as_ref() {
+            ErrorImpl::Preserved(error) => E::custom(error),
+            ErrorImpl::Caused { error, .. } => error.as_serde_de_error(),
+            ErrorImpl::Located { path, error } => {
+                if path.is_empty() {
+                    error.as_serde_de_error()
+                } else {
+                    E::custom(format_args!("{path}: {error}"))
+                }
+            }
             ErrorImpl::Custom(msg) => E::custom(msg),
             ErrorImpl::InvalidType {
                 unexpected,
@@ -177,6 +552,25 @@ impl Error {
     }
 }
 
+// Levenshtein distance, counting in chars rather than bytes.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let mut row: Vec<usize> = (0..=b.chars().count()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.chars().enumerate() {
+            let above = row[j + 1];
+            row[j + 1] = if ca == cb {
+                diagonal
+            } else {
+                1 + diagonal.min(above).min(row[j])
+            };
+            diagonal = above;
+        }
+    }
+    row[row.len() - 1]
+}
+
 impl Unexpected {
     fn from_serde(unexpected: serde::de::Unexpected) -> Self {
         match unexpected {
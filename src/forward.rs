@@ -0,0 +1,36 @@
+use crate::de::Deserializer;
+use crate::error::{unerase_de_owned, Error};
+use crate::ser::{erase, Serializer};
+use serde::ser::Error as _;
+
+// Not public API. Used by erase_trait! to run an erased method against the
+// concrete serializer that the generic method was called with.
+#[doc(hidden)]
+pub fn serialize_with<S, F>(serializer: S, f: F) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+    F: FnOnce(&mut dyn Serializer) -> Result<(), Error>,
+{
+    let mut erased = erase::Serializer::new(serializer);
+    let result = f(&mut erased);
+    match (result, erased.take()) {
+        (_, erase::State::Error(err)) => Err(err),
+        (Err(error), _) => Err(S::Error::custom(error)),
+        (Ok(()), erase::State::Complete(ok)) => Ok(ok),
+        (Ok(()), _) => Err(S::Error::custom(
+            "erased serializer has not completed serialization",
+        )),
+    }
+}
+
+// Not public API. Used by erase_trait! to run an erased method against the
+// concrete deserializer that the generic method was called with.
+#[doc(hidden)]
+pub fn deserialize_with<'de, D, F, T>(deserializer: D, f: F) -> Result<T, D::Error>
+where
+    D: serde::Deserializer<'de>,
+    F: FnOnce(&mut dyn Deserializer<'de>) -> Result<T, Error>,
+{
+    let mut erased = <dyn Deserializer>::erase(deserializer);
+    f(&mut erased).map_err(unerase_de_owned)
+}
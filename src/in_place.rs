@@ -0,0 +1,175 @@
+use crate::de::Deserializer;
+use crate::error::{unerase_de_owned, Error};
+use crate::sealed;
+
+/// An object-safe way to deserialize into an existing value, equivalent to
+/// Serde's `Deserialize::deserialize_in_place`.
+///
+/// Every type that implements `serde::Deserialize<'de>` implements this trait.
+/// Using it as a supertrait makes values reloadable in place from behind a
+/// trait object, without knowing their concrete type.
+///
+/// ```
+/// use erased_serde::{deserialize_in_place_trait_object, DeserializeInPlace};
+/// use serde::de::DeserializeSeed;
+/// use serde_derive::Deserialize;
+///
+/// trait ConfigSection: for<'de> DeserializeInPlace<'de> {
+///     fn name(&self) -> &str;
+/// }
+///
+/// deserialize_in_place_trait_object!(ConfigSection);
+///
+/// #[derive(Deserialize)]
+/// struct Server {
+///     port: u16,
+/// }
+///
+/// impl ConfigSection for Server {
+///     fn name(&self) -> &str {
+///         "server"
+///     }
+/// }
+///
+/// let mut sections: Vec<Box<dyn ConfigSection>> = vec![Box::new(Server { port: 80 })];
+///
+/// for section in &mut sections {
+///     let json = &mut serde_json::Deserializer::from_str(r#"{"port": 8080}"#);
+///     (&mut **section).deserialize(json).unwrap();
+/// }
+/// ```
+///
+/// This trait is sealed and can only be implemented via a
+/// `serde::Deserialize<'de>` impl.
+pub trait DeserializeInPlace<'de>: sealed::deserialize_in_place::Sealed<'de> {
+    fn erased_deserialize_in_place(
+        &mut self,
+        deserializer: &mut dyn Deserializer<'de>,
+    ) -> Result<(), Error>;
+}
+
+impl<'de, T> DeserializeInPlace<'de> for T
+where
+    T: serde::Deserialize<'de>,
+{
+    fn erased_deserialize_in_place(
+        &mut self,
+        deserializer: &mut dyn Deserializer<'de>,
+    ) -> Result<(), Error> {
+        T::deserialize_in_place(deserializer, self)
+    }
+}
+
+impl<'de, T> sealed::deserialize_in_place::Sealed<'de> for T where T: serde::Deserialize<'de> {}
+
+/// Deserialize into an existing value whose type is erased, using a Serde
+/// deserializer.
+///
+/// This is the function that the `DeserializeSeed` impls generated by
+/// [`deserialize_in_place_trait_object!`][crate::deserialize_in_place_trait_object]
+/// call.
+///
+/// ```
+/// use erased_serde::DeserializeInPlace;
+///
+/// let mut numbers = vec![1, 2, 3];
+/// let place: &mut dyn DeserializeInPlace = &mut numbers;
+///
+/// let json = &mut serde_json::Deserializer::from_str("[4, 5]");
+/// erased_serde::deserialize_in_place(place, json).unwrap();
+/// assert_eq!(numbers, [4, 5]);
+/// ```
+pub fn deserialize_in_place<'de, T, D>(place: &mut T, deserializer: D) -> Result<(), D::Error>
+where
+    T: ?Sized + DeserializeInPlace<'de>,
+    D: serde::Deserializer<'de>,
+{
+    let mut erased = <dyn Deserializer>::erase(deserializer);
+    place
+        .erased_deserialize_in_place(&mut erased)
+        .map_err(unerase_de_owned)
+}
+
+deserialize_in_place_trait_object!(<'de> DeserializeInPlace<'de>);
+
+// TEST ////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use crate::{DeserializeInPlace, Deserializer, ErrorKind};
+    use alloc::borrow::ToOwned;
+    use alloc::boxed::Box;
+    use alloc::string::{String, ToString};
+    use alloc::vec;
+    use alloc::vec::Vec;
+    use serde::de::DeserializeSeed;
+    use serde_derive::Deserialize;
+
+    #[derive(Deserialize, PartialEq, Debug)]
+    struct Server {
+        host: String,
+        ports: Vec<u16>,
+    }
+
+    trait Section: for<'de> DeserializeInPlace<'de> + Send {}
+
+    impl Section for Server {}
+
+    deserialize_in_place_trait_object!(Section);
+
+    fn server() -> Server {
+        Server {
+            host: "localhost".to_owned(),
+            ports: vec![80],
+        }
+    }
+
+    #[test]
+    fn test_erased_deserializer() {
+        let mut value = server();
+        let place: &mut dyn DeserializeInPlace = &mut value;
+
+        let json = br#"{"host": "example.com", "ports": [80, 443]}"#;
+        let json = &mut serde_json::Deserializer::from_slice(json);
+        place
+            .erased_deserialize_in_place(&mut <dyn Deserializer>::erase(json))
+            .unwrap();
+
+        let expected = Server {
+            host: "example.com".to_owned(),
+            ports: vec![80, 443],
+        };
+        assert_eq!(value, expected);
+    }
+
+    #[test]
+    fn test_trait_object() {
+        let mut section: Box<dyn Section + Send> = Box::new(server());
+
+        let cbor = serde_cbor::to_vec(&serde_json::json!({
+            "host": "example.com",
+            "ports": [8080],
+        }))
+        .unwrap();
+        let cbor = &mut serde_cbor::Deserializer::from_slice(&cbor);
+        (&mut *section).deserialize(cbor).unwrap();
+
+        let json = &mut serde_json::Deserializer::from_str(r#"{"host": 1}"#);
+        let error = (&mut *section).deserialize(json).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "invalid type: integer `1`, expected a string at line 1 column 10",
+        );
+    }
+
+    #[test]
+    fn test_error() {
+        let mut value = server();
+        let place: &mut dyn DeserializeInPlace = &mut value;
+
+        let json = &mut serde_json::Deserializer::from_str(r#"{"host": "example.com"}"#);
+        let format = &mut <dyn Deserializer>::erase(json);
+        let error = place.erased_deserialize_in_place(format).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::MissingField);
+    }
+}
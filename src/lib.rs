@@ -127,16 +127,85 @@ extern crate serde_core as serde;
 mod macros;
 
 mod any;
-mod de;
+pub mod custom;
+pub mod de;
+mod detach;
 mod error;
 mod features_check;
+mod forward;
+mod in_place;
 mod map;
+mod owned;
+mod path;
+mod registry;
 mod sealed;
-mod ser;
+mod seed_table;
+pub mod ser;
+mod stream;
+mod tag;
+mod transcode;
+mod value;
 
 pub use crate::de::{deserialize, Deserializer};
-pub use crate::error::{Error, Result};
+pub use crate::error::{Error, ErrorKind, Result};
+pub use crate::in_place::{deserialize_in_place, DeserializeInPlace};
+pub use crate::owned::OwnedDeserializer;
+pub use crate::path::{serialize_with_path, Path, PathDeserializer, Segment};
+pub use crate::registry::{Registry, Upcast};
+pub use crate::seed_table::{SeedTable, TableSeed};
+pub use crate::ser::erase::Serializer as ErasedSerializer;
 pub use crate::ser::{serialize, Serialize, Serializer};
+pub use crate::stream::StreamDeserializer;
+pub use crate::transcode::transcode;
+pub use crate::value::{to_value, MapEntry, StructField, Value};
+
+/// Make a trait usable as `dyn Trait` in Serde serialization, without
+/// [`serialize_trait_object!`].
+///
+/// *Requires the `"macros"` feature of erased-serde, and Rust 1.71 or newer.*
+///
+/// The attribute adds `erased_serde::Serialize` as a supertrait and implements
+/// `serde::Serialize` for `dyn Trait`, `dyn Trait + Send`, `dyn Trait + Sync`
+/// and `dyn Trait + Send + Sync`. Generic parameters, `where` clauses and
+/// associated types of any form are carried over to the impls, and the trait
+/// object impls are generic over the associated type bindings.
+///
+/// ```
+/// use serde_derive::Serialize;
+///
+/// #[erased_serde::serializable]
+/// trait Handler<'a, T: Clone> {
+///     type Output;
+///
+///     fn handle(&self, input: &'a T) -> Self::Output;
+/// }
+///
+/// #[derive(Serialize)]
+/// struct Echo {
+///     prefix: String,
+/// }
+///
+/// impl<'a> Handler<'a, String> for Echo {
+///     type Output = String;
+///
+///     fn handle(&self, input: &'a String) -> String {
+///         format!("{}{}", self.prefix, input)
+///     }
+/// }
+///
+/// fn main() {
+///     let prefix = "> ".to_owned();
+///     let handler: Box<dyn Handler<String, Output = String>> = Box::new(Echo { prefix });
+///     let j = serde_json::to_string(&handler).unwrap();
+///     assert_eq!(j, r#"{"prefix":"> "}"#);
+/// }
+/// ```
+///
+/// The auto trait combinations to implement besides the bare `dyn Trait` can
+/// be chosen with `#[erased_serde::serializable(auto_traits(Send + Sync,
+/// Unpin))]`.
+#[cfg(feature = "macros")]
+pub use erased_serde_impl::serializable;
 
 // Not public API.
 #[doc(hidden)]
@@ -22,111 +22,1008 @@
 ///
 /// serialize_trait_object!(<T> Difficult<T> where T: Copy);
 /// ```
+///
+/// Lifetime parameters and associated type bindings are written the same way
+/// they would be in the trait object type.
+///
+/// ```
+/// # use erased_serde::serialize_trait_object;
+/// #
+/// # struct Json;
+/// #
+/// trait Handler<'a>: erased_serde::Serialize {
+///     type Output;
+/// }
+///
+/// serialize_trait_object!(<'a> Handler<'a, Output = Json>);
+/// ```
+///
+/// # Auto traits
+///
+/// Besides the bare `dyn Trait`, the impl is repeated for `dyn Trait + Send`,
+/// `dyn Trait + Sync` and `dyn Trait + Send + Sync`. A leading
+/// `#[auto_traits(...)]` attribute replaces those three with a comma-separated
+/// list of auto trait combinations of your choosing.
+///
+/// ```
+/// # use erased_serde::serialize_trait_object;
+/// #
+/// # trait Event: erased_serde::Serialize {}
+/// #
+/// serialize_trait_object!(
+///     #[auto_traits(Send + Sync, Send + Unpin, std::panic::UnwindSafe)]
+///     Event
+/// );
+/// ```
+///
+/// # Tagging
+///
+/// By default the trait object serializes exactly as the concrete value
+/// inside of it, with nothing to say which type that was. A leading attribute
+/// wraps each value with a tag, in the same representations that Serde offers
+/// for enums:
+///
+/// - `#[external]` &mdash; `{"Login": {"user": "alice"}}`
+/// - `#[internal(tag = "type")]` &mdash; `{"type": "Login", "user": "alice"}`
+/// - `#[adjacent(tag = "t", content = "c")]` &mdash;
+///   `{"t": "Login", "c": {"user": "alice"}}`
+///
+/// The tag is the result of the trait method named by `name = ...`, which may
+/// return `&str` or `String`, or else the concrete type's
+/// [`core::any::type_name`]. The externally tagged representation is the one
+/// that [`deserialize_trait_object!`][crate::deserialize_trait_object] reads
+/// back.
+///
+/// Adjacently tagged values serialize as a struct named after the trait,
+/// without its path or generic arguments, the same way that Serde names an
+/// adjacently tagged enum's struct after the enum.
+///
+/// An internal tag is inserted as the first entry of the map or struct that
+/// the value serializes as, so values that serialize as anything else, such as
+/// a sequence or a bare string, fail to serialize with that representation.
+///
+/// ```
+/// use erased_serde::serialize_trait_object;
+/// use serde_derive::Serialize;
+///
+/// trait Event: erased_serde::Serialize {
+///     fn name(&self) -> &'static str;
+/// }
+///
+/// serialize_trait_object!(#[internal(tag = "type", name = name)] Event);
+///
+/// #[derive(Serialize)]
+/// struct Login {
+///     user: String,
+/// }
+///
+/// impl Event for Login {
+///     fn name(&self) -> &'static str {
+///         "Login"
+///     }
+/// }
+///
+/// fn main() {
+///     let events: Vec<Box<dyn Event>> = vec![Box::new(Login { user: "alice".to_owned() })];
+///     let j = serde_json::to_string(&events).unwrap();
+///     assert_eq!(j, r#"[{"type":"Login","user":"alice"}]"#);
+/// }
+/// ```
 #[macro_export]
 macro_rules! serialize_trait_object {
     ($($path:tt)+) => {
-        $crate::__internal_serialize_trait_object!(begin $($path)+);
+        $crate::__internal_serialize_trait_object!(attrs [] default $($path)+);
     };
 }
 
 #[doc(hidden)]
 #[macro_export]
 macro_rules! __internal_serialize_trait_object {
+    // Auto trait combinations, parsed one comma-separated entry at a time.
+    (attrs $mode:tt $auto:tt # [auto_traits($($combos:tt)*)] $($rest:tt)*) => {
+        $crate::__internal_serialize_trait_object!(combos $mode [] [] ($($combos)*) $($rest)*);
+    };
+
+    // Tagging attribute.
+    (attrs [] $auto:tt # [$mode:ident $(($($args:tt)*))?] $($rest:tt)*) => {
+        $crate::__internal_serialize_trait_object!(attrs [$mode $($($args)*)?] $auto $($rest)*);
+    };
+
+    // No auto_traits attribute, use the default combinations.
+    (attrs $mode:tt default $($rest:tt)*) => {
+        $crate::__internal_parse_trait_object!(__internal_serialize_trait_object [sendsync $mode [
+            ($crate::__private::Send)
+            ($crate::__private::Sync)
+            ($crate::__private::Send + $crate::__private::Sync)
+        ]] begin $($rest)*);
+    };
+
+    // End of attributes.
+    (attrs $mode:tt $auto:tt $($rest:tt)*) => {
+        $crate::__internal_parse_trait_object!(__internal_serialize_trait_object [sendsync $mode $auto] begin $($rest)*);
+    };
+
+    // End of an auto trait combination.
+    (combos $mode:tt [$($done:tt)*] [$($current:tt)+] (, $($combos:tt)*) $($rest:tt)*) => {
+        $crate::__internal_serialize_trait_object!(combos $mode [$($done)* ($($current)+)] [] ($($combos)*) $($rest)*);
+    };
+
+    // End of the last auto trait combination.
+    (combos $mode:tt [$($done:tt)*] [$($current:tt)+] () $($rest:tt)*) => {
+        $crate::__internal_serialize_trait_object!(attrs $mode [$($done)* ($($current)+)] $($rest)*);
+    };
+
+    // End of auto_traits attribute that is empty or has a trailing comma.
+    (combos $mode:tt [$($done:tt)*] [] () $($rest:tt)*) => {
+        $crate::__internal_serialize_trait_object!(attrs $mode [$($done)*] $($rest)*);
+    };
+
+    // Token inside of an auto trait combination.
+    (combos $mode:tt [$($done:tt)*] [$($current:tt)*] ($first:tt $($combos:tt)*) $($rest:tt)*) => {
+        $crate::__internal_serialize_trait_object!(combos $mode [$($done)*] [$($current)* $first] ($($combos)*) $($rest)*);
+    };
+
+    // Expand into an impl for the bare trait object and one for each auto trait
+    // combination.
+    (sendsync $mode:tt $auto:tt ($($generics:tt)*) ($($path:tt)*) ($($bound:tt)*)) => {
+        $crate::__internal_serialize_trait_object!(impl $mode ($($path)*) ($($generics)*) ($($path)*) ($($bound)*) {
+            fn __check_erased_serialize_supertrait<$($generics)* __T>()
+            where
+                __T: ?$crate::__private::Sized + $($path)*,
+                $($bound)*
+            {
+                $crate::__private::require_erased_serialize_impl::<__T>();
+            }
+        });
+        $crate::__internal_serialize_trait_object!(auto $mode $auto ($($generics)*) ($($path)*) ($($bound)*));
+    };
+
+    // No more auto trait combinations.
+    (auto $mode:tt [] $generics:tt $path:tt $bound:tt) => {};
+
+    // Impl for the next auto trait combination.
+    (auto $mode:tt [($($first:tt)*) $($more:tt)*] ($($generics:tt)*) ($($path:tt)*) ($($bound:tt)*)) => {
+        $crate::__internal_serialize_trait_object!(impl $mode ($($path)*) ($($generics)*) ($($path)* + $($first)*) ($($bound)*));
+        $crate::__internal_serialize_trait_object!(auto $mode [$($more)*] ($($generics)*) ($($path)*) ($($bound)*));
+    };
+
+    // The impl.
+    (impl $mode:tt $trait:tt ($($generics:tt)*) ($($path:tt)*) ($($bound:tt)*) $({$($body:tt)*})*) => {
+        impl<'erased, $($generics)*> $crate::__private::serde::Serialize for dyn $($path)* + 'erased
+        where
+            $($bound)*
+        {
+            fn serialize<S>(&self, serializer: S) -> $crate::__private::Result<S::Ok, S::Error>
+            where
+                S: $crate::__private::serde::Serializer,
+            {
+                $($($body)*)*
+                $crate::__internal_serialize_trait_object!(serialize $mode $trait self serializer)
+            }
+        }
+    };
+
+    // Untagged.
+    (serialize [] $trait:tt $value:ident $serializer:ident) => {
+        $crate::serialize($value, $serializer)
+    };
+
+    (serialize [external $(name = $name:ident)?] $trait:tt $value:ident $serializer:ident) => {
+        $crate::__private::serialize_tagged(
+            $value,
+            $crate::__internal_serialize_trait_object!(name $value $($name)?),
+            $crate::__private::Tagging::External,
+            $serializer,
+        )
+    };
+
+    (serialize [internal tag = $tag:literal $(, name = $name:ident)?] $trait:tt $value:ident $serializer:ident) => {
+        $crate::__private::serialize_tagged(
+            $value,
+            $crate::__internal_serialize_trait_object!(name $value $($name)?),
+            $crate::__private::Tagging::Internal { tag: $tag },
+            $serializer,
+        )
+    };
+
+    (serialize [adjacent tag = $tag:literal, content = $content:literal $(, name = $name:ident)?] ($($trait:tt)*) $value:ident $serializer:ident) => {
+        $crate::__private::serialize_tagged(
+            $value,
+            $crate::__internal_serialize_trait_object!(name $value $($name)?),
+            $crate::__private::Tagging::Adjacent {
+                object: $crate::__internal_serialize_trait_object!(object_name $($trait)*),
+                tag: $tag,
+                content: $content,
+            },
+            $serializer,
+        )
+    };
+
+    // Name of the adjacently tagged struct, which is the trait's identifier
+    // without its path or generic arguments.
+    (object_name $name:ident) => {
+        $crate::__private::stringify!($name)
+    };
+
+    (object_name $name:ident < $($args:tt)*) => {
+        $crate::__private::stringify!($name)
+    };
+
+    (object_name $first:tt $($rest:tt)*) => {
+        $crate::__internal_serialize_trait_object!(object_name $($rest)*)
+    };
+
+    // Tag from the concrete type's name.
+    (name $value:ident) => {
+        $crate::Serialize::erased_type_name($value)
+    };
+
+    // Tag from a trait method.
+    (name $value:ident $name:ident) => {
+        &*$value.$name()
+    };
+}
+
+/// Implement `serde::Deserialize` for `Box<dyn Trait>` by looking up the
+/// concrete type in a [`Registry`][crate::Registry].
+///
+/// The `registry` argument is a function that returns the
+/// `&'static Registry<dyn Trait>` to consult. With Serde's `"rc"` feature
+/// enabled, `Rc<dyn Trait>` and `Arc<dyn Trait>` are deserializable too by way
+/// of the `Box` impl.
+///
+/// ```
+/// use erased_serde::{deserialize_trait_object, Registry};
+/// use std::sync::OnceLock;
+///
+/// trait Event {
+///     /* ... */
+/// }
+///
+/// fn registry() -> &'static Registry<dyn Event> {
+///     static REGISTRY: OnceLock<Registry<dyn Event>> = OnceLock::new();
+///     REGISTRY.get_or_init(Registry::new)
+/// }
+///
+/// deserialize_trait_object!(Event, registry = registry);
+/// ```
+///
+/// Auto traits written after the trait name become part of the registry's
+/// trait object type, so every registered type must implement them. From that
+/// one registry, `Box<dyn Trait>` is deserializable together with every
+/// combination of the listed auto traits, each concrete type having been
+/// registered only once.
+///
+/// ```
+/// # use erased_serde::{deserialize_trait_object, Registry};
+/// # use std::sync::OnceLock;
+/// #
+/// # trait Event {}
+/// #
+/// fn registry() -> &'static Registry<dyn Event + Send + Sync> {
+///     static REGISTRY: OnceLock<Registry<dyn Event + Send + Sync>> = OnceLock::new();
+///     REGISTRY.get_or_init(Registry::new)
+/// }
+///
+/// // Box<dyn Event>, Box<dyn Event + Send>, Box<dyn Event + Sync> and
+/// // Box<dyn Event + Send + Sync>.
+/// deserialize_trait_object!(Event + Send + Sync, registry = registry);
+/// ```
+#[macro_export]
+macro_rules! deserialize_trait_object {
+    ($($args:tt)+) => {
+        $crate::__internal_deserialize_trait_object!(object () $($args)+);
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __internal_deserialize_trait_object {
+    // Reached the registry argument, with no auto traits.
+    (object ($($object:tt)*) , registry = $registry:expr $(,)?) => {
+        $crate::__internal_deserialize_trait_object!(impl ($($object)*) ($registry) []);
+    };
+
+    // Start of the auto traits.
+    (object ($($object:tt)*) + $($rest:tt)*) => {
+        $crate::__internal_deserialize_trait_object!(auto ($($object)*) [] () $($rest)*);
+    };
+
+    // Token inside of the trait.
+    (object ($($object:tt)*) $first:tt $($rest:tt)*) => {
+        $crate::__internal_deserialize_trait_object!(object ($($object)* $first) $($rest)*);
+    };
+
+    // Reached the registry argument after the last auto trait.
+    (auto $object:tt [$($done:tt)*] ($($current:tt)+) , registry = $registry:expr $(,)?) => {
+        $crate::__internal_deserialize_trait_object!(impl $object ($registry) [$($done)* ($($current)+)]);
+    };
+
+    // End of an auto trait.
+    (auto $object:tt [$($done:tt)*] ($($current:tt)+) + $($rest:tt)*) => {
+        $crate::__internal_deserialize_trait_object!(auto $object [$($done)* ($($current)+)] () $($rest)*);
+    };
+
+    // Token inside of an auto trait.
+    (auto $object:tt [$($done:tt)*] ($($current:tt)*) $first:tt $($rest:tt)*) => {
+        $crate::__internal_deserialize_trait_object!(auto $object [$($done)*] ($($current)* $first) $($rest)*);
+    };
+
+    // The Upcast impl for the registry's own trait object type.
+    (impl ($($object:tt)*) ($registry:expr) [$(($($auto:tt)*))*]) => {
+        impl<__L> $crate::Upcast<__L> for dyn $($object)* $(+ $($auto)*)*
+        where
+            __L: $($object)* $(+ $($auto)*)* + 'static,
+        {
+            fn upcast(value: __L) -> $crate::__private::Box<Self> {
+                $crate::__private::Box::new(value)
+            }
+        }
+
+        $crate::__internal_deserialize_trait_object!(forms ($($object)*) ($registry) [] [$(($($auto)*))*]);
+    };
+
+    // Each auto trait is either kept or left out.
+    (forms $object:tt ($registry:expr) [$($chosen:tt)*] [$first:tt $($rest:tt)*]) => {
+        $crate::__internal_deserialize_trait_object!(forms $object ($registry) [$($chosen)* $first] [$($rest)*]);
+        $crate::__internal_deserialize_trait_object!(forms $object ($registry) [$($chosen)*] [$($rest)*]);
+    };
+
+    // The Deserialize impl for one combination of auto traits, upcast from the
+    // registry's trait object type.
+    (forms ($($object:tt)*) ($registry:expr) [$(($($auto:tt)*))*] []) => {
+        impl<'de> $crate::__private::serde::Deserialize<'de> for $crate::__private::Box<dyn $($object)* $(+ $($auto)*)*> {
+            fn deserialize<D>(deserializer: D) -> $crate::__private::Result<Self, D::Error>
+            where
+                D: $crate::__private::serde::Deserializer<'de>,
+            {
+                let object: Self = $crate::__private::deserialize_registered($registry(), deserializer)?;
+                $crate::__private::Result::Ok(object)
+            }
+        }
+    };
+}
+
+// Parses the optional generics, trait and optional `where` clause of a trait
+// object macro, then invokes the given macro with the tokens in `[...]`
+// followed by `(generics,) (trait) (where clause)`.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __internal_parse_trait_object {
     // Invocation started with `<`, parse generics.
-    (begin < $($rest:tt)*) => {
-        $crate::__internal_serialize_trait_object!(generics () () $($rest)*);
+    ($callback:ident $state:tt begin < $($rest:tt)*) => {
+        $crate::__internal_parse_trait_object!($callback $state generics () () $($rest)*);
     };
 
     // Invocation did not start with `<`.
-    (begin $first:tt $($rest:tt)*) => {
-        $crate::__internal_serialize_trait_object!(path () ($first) $($rest)*);
+    ($callback:ident $state:tt begin $first:tt $($rest:tt)*) => {
+        $crate::__internal_parse_trait_object!($callback $state path () ($first) $($rest)*);
     };
 
     // End of generics with trailing comma.
-    (generics ($($generics:tt)*) () , > $($rest:tt)*) => {
-        $crate::__internal_serialize_trait_object!(path ($($generics)* ,) () $($rest)*);
+    ($callback:ident $state:tt generics ($($generics:tt)*) () , > $($rest:tt)*) => {
+        $crate::__internal_parse_trait_object!($callback $state path ($($generics)* ,) () $($rest)*);
     };
 
     // End of generics without trailing comma.
-    (generics ($($generics:tt)*) () > $($rest:tt)*) => {
-        $crate::__internal_serialize_trait_object!(path ($($generics)* ,) () $($rest)*);
+    ($callback:ident $state:tt generics ($($generics:tt)*) () > $($rest:tt)*) => {
+        $crate::__internal_parse_trait_object!($callback $state path ($($generics)* ,) () $($rest)*);
     };
 
     // Generics open bracket.
-    (generics ($($generics:tt)*) ($($brackets:tt)*) < $($rest:tt)*) => {
-        $crate::__internal_serialize_trait_object!(generics ($($generics)* <) ($($brackets)* <) $($rest)*);
+    ($callback:ident $state:tt generics ($($generics:tt)*) ($($brackets:tt)*) < $($rest:tt)*) => {
+        $crate::__internal_parse_trait_object!($callback $state generics ($($generics)* <) ($($brackets)* <) $($rest)*);
     };
 
     // Generics close bracket.
-    (generics ($($generics:tt)*) (< $($brackets:tt)*) > $($rest:tt)*) => {
-        $crate::__internal_serialize_trait_object!(generics ($($generics)* >) ($($brackets)*) $($rest)*);
+    ($callback:ident $state:tt generics ($($generics:tt)*) (< $($brackets:tt)*) > $($rest:tt)*) => {
+        $crate::__internal_parse_trait_object!($callback $state generics ($($generics)* >) ($($brackets)*) $($rest)*);
     };
 
     // Token inside of generics.
-    (generics ($($generics:tt)*) ($($brackets:tt)*) $first:tt $($rest:tt)*) => {
-        $crate::__internal_serialize_trait_object!(generics ($($generics)* $first) ($($brackets)*) $($rest)*);
+    ($callback:ident $state:tt generics ($($generics:tt)*) ($($brackets:tt)*) $first:tt $($rest:tt)*) => {
+        $crate::__internal_parse_trait_object!($callback $state generics ($($generics)* $first) ($($brackets)*) $($rest)*);
     };
 
     // End with `where` clause.
-    (path ($($generics:tt)*) ($($path:tt)*) where $($rest:tt)*) => {
-        $crate::__internal_serialize_trait_object!(sendsync ($($generics)*) ($($path)*) ($($rest)*));
+    ($callback:ident [$($state:tt)*] path ($($generics:tt)*) ($($path:tt)*) where $($rest:tt)*) => {
+        $crate::$callback!($($state)* ($($generics)*) ($($path)*) ($($rest)*));
     };
 
     // End without `where` clause.
-    (path ($($generics:tt)*) ($($path:tt)*)) => {
-        $crate::__internal_serialize_trait_object!(sendsync ($($generics)*) ($($path)*) ());
+    ($callback:ident [$($state:tt)*] path ($($generics:tt)*) ($($path:tt)*)) => {
+        $crate::$callback!($($state)* ($($generics)*) ($($path)*) ());
     };
 
     // Token inside of path.
-    (path ($($generics:tt)*) ($($path:tt)*) $first:tt $($rest:tt)*) => {
-        $crate::__internal_serialize_trait_object!(path ($($generics)*) ($($path)* $first) $($rest)*);
+    ($callback:ident $state:tt path ($($generics:tt)*) ($($path:tt)*) $first:tt $($rest:tt)*) => {
+        $crate::__internal_parse_trait_object!($callback $state path ($($generics)*) ($($path)* $first) $($rest)*);
     };
+}
 
-    // Expand into four impls.
-    (sendsync ($($generics:tt)*) ($($path:tt)*) ($($bound:tt)*)) => {
-        $crate::__internal_serialize_trait_object!(impl ($($generics)*) ($($path)*) ($($bound)*) {
-            fn __check_erased_serialize_supertrait<$($generics)* __T>()
+/// Implement `serde::Serializer` for `&mut dyn Trait`, where `Trait` has
+/// `erased_serde::Serializer` as a supertrait.
+///
+/// The impls cover the trait object together with its `Send`, `Sync` and
+/// `Send + Sync` variants, so that a subtrait object can be handed to any
+/// generic Serde code.
+///
+/// ```
+/// use erased_serde::{serializer_trait_object, ErasedSerializer};
+/// use serde::Serialize as _;
+///
+/// trait OutputFormat: erased_serde::Serializer {
+///     fn content_type(&self) -> &str;
+/// }
+///
+/// serializer_trait_object!(OutputFormat);
+///
+/// impl<W: std::io::Write> OutputFormat for ErasedSerializer<&mut serde_json::Serializer<W>> {
+///     fn content_type(&self) -> &str {
+///         "application/json"
+///     }
+/// }
+///
+/// let mut out = Vec::new();
+/// let json = &mut serde_json::Serializer::new(&mut out);
+/// let mut format: Box<dyn OutputFormat> = Box::new(<dyn erased_serde::Serializer>::erase(json));
+///
+/// assert_eq!(format.content_type(), "application/json");
+/// vec![1, 2, 3].serialize(&mut *format).unwrap();
+///
+/// drop(format);
+/// assert_eq!(out, b"[1,2,3]");
+/// ```
+///
+/// There is no impl for `Box<dyn Trait>` because the state of a compound value
+/// borrows the serializer; reborrow the box as `&mut *boxed` instead.
+///
+/// The macro supports traits that have generic parameters and `where` clauses,
+/// written with the generics first, like in
+/// [`serialize_trait_object!`][crate::serialize_trait_object].
+///
+/// ```
+/// # use erased_serde::serializer_trait_object;
+/// #
+/// trait OutputFormat<'a, T: Clone + 'a>: erased_serde::Serializer
+/// where
+///     T: Default,
+/// {
+///     fn header(&self) -> &'a T;
+/// }
+///
+/// serializer_trait_object!(<'a, T: Clone + 'a> OutputFormat<'a, T> where T: Default);
+/// ```
+#[macro_export]
+macro_rules! serializer_trait_object {
+    ($($tokens:tt)+) => {
+        $crate::__internal_parse_trait_object!(__internal_serializer_trait_object [] begin $($tokens)+);
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __internal_serializer_trait_object {
+    // Expand into impls for the bare trait object and its auto trait
+    // combinations.
+    (($($generics:tt)*) ($($path:tt)*) ($($bound:tt)*)) => {
+        $crate::__internal_serializer_trait_object!(impl ($($generics)*) ($($path)*) ($($bound)*));
+        $crate::__internal_serializer_trait_object!(impl ($($generics)*) ($($path)* + $crate::__private::Send) ($($bound)*));
+        $crate::__internal_serializer_trait_object!(impl ($($generics)*) ($($path)* + $crate::__private::Sync) ($($bound)*));
+        $crate::__internal_serializer_trait_object!(impl ($($generics)*) ($($path)* + $crate::__private::Send + $crate::__private::Sync) ($($bound)*));
+    };
+
+    (impl ($($generics:tt)*) ($($object:tt)*) ($($bound:tt)*)) => {
+        impl<'__a, 'erased, $($generics)*> $crate::__private::serde::Serializer for &'__a mut (dyn $($object)* + 'erased)
+        where
+            $($bound)*
+        {
+            type Ok = ();
+            type Error = $crate::Error;
+            type SerializeSeq = $crate::ser::Compound<'__a, dyn $crate::ser::SerializeSeq + '__a>;
+            type SerializeTuple = $crate::ser::Compound<'__a, dyn $crate::ser::SerializeTuple + '__a>;
+            type SerializeTupleStruct = $crate::ser::Compound<'__a, dyn $crate::ser::SerializeTupleStruct + '__a>;
+            type SerializeTupleVariant = $crate::ser::Compound<'__a, dyn $crate::ser::SerializeTupleVariant + '__a>;
+            type SerializeMap = $crate::ser::Compound<'__a, dyn $crate::ser::SerializeMap + '__a>;
+            type SerializeStruct = $crate::ser::Compound<'__a, dyn $crate::ser::SerializeStruct + '__a>;
+            type SerializeStructVariant = $crate::ser::Compound<'__a, dyn $crate::ser::SerializeStructVariant + '__a>;
+
+            $crate::__internal_serializer_trait_object! {
+                forward
+                serialize_bool(v: bool) -> ();
+                serialize_i8(v: i8) -> ();
+                serialize_i16(v: i16) -> ();
+                serialize_i32(v: i32) -> ();
+                serialize_i64(v: i64) -> ();
+                serialize_i128(v: i128) -> ();
+                serialize_u8(v: u8) -> ();
+                serialize_u16(v: u16) -> ();
+                serialize_u32(v: u32) -> ();
+                serialize_u64(v: u64) -> ();
+                serialize_u128(v: u128) -> ();
+                serialize_f32(v: f32) -> ();
+                serialize_f64(v: f64) -> ();
+                serialize_char(v: char) -> ();
+                serialize_str(v: &str) -> ();
+                serialize_bytes(v: &[u8]) -> ();
+                serialize_none() -> ();
+                serialize_unit() -> ();
+                serialize_unit_struct(name: &'static str) -> ();
+                serialize_unit_variant(name: &'static str, variant_index: u32, variant: &'static str) -> ();
+                serialize_seq(len: $crate::__private::Option<usize>) -> Self::SerializeSeq;
+                serialize_tuple(len: usize) -> Self::SerializeTuple;
+                serialize_tuple_struct(name: &'static str, len: usize) -> Self::SerializeTupleStruct;
+                serialize_tuple_variant(name: &'static str, variant_index: u32, variant: &'static str, len: usize) -> Self::SerializeTupleVariant;
+                serialize_map(len: $crate::__private::Option<usize>) -> Self::SerializeMap;
+                serialize_struct(name: &'static str, len: usize) -> Self::SerializeStruct;
+                serialize_struct_variant(name: &'static str, variant_index: u32, variant: &'static str, len: usize) -> Self::SerializeStructVariant;
+            }
+
+            $crate::__internal_serializer_trait_object! {
+                forward_generic
+                serialize_some(value: &__T);
+                serialize_newtype_struct(name: &'static str, value: &__T);
+                serialize_newtype_variant(name: &'static str, variant_index: u32, variant: &'static str, value: &__T);
+            }
+
+            fn is_human_readable(&self) -> bool {
+                $crate::Serializer::erased_is_human_readable(*self)
+            }
+        }
+    };
+
+    (forward $($method:ident($($arg:ident: $ty:ty),*) -> $ret:ty;)*) => {
+        $(
+            fn $method(self, $($arg: $ty),*) -> $crate::__private::Result<$ret, $crate::Error> {
+                let serializer = $crate::Serializer::erased_as_serializer(self);
+                $crate::__private::serde::Serializer::$method(serializer, $($arg),*)
+            }
+        )*
+    };
+
+    (forward_generic $($method:ident($($arg:ident: $ty:ty),*);)*) => {
+        $(
+            fn $method<__T>(self, $($arg: $ty),*) -> $crate::__private::Result<(), $crate::Error>
             where
-                __T: ?$crate::__private::Sized + $($path)*,
-                $($bound)*
+                __T: ?$crate::__private::Sized + $crate::__private::serde::Serialize,
             {
-                $crate::__private::require_erased_serialize_impl::<__T>();
+                let serializer = $crate::Serializer::erased_as_serializer(self);
+                $crate::__private::serde::Serializer::$method(serializer, $($arg),*)
             }
-        });
-        $crate::__internal_serialize_trait_object!(impl ($($generics)*) ($($path)* + $crate::__private::Send) ($($bound)*));
-        $crate::__internal_serialize_trait_object!(impl ($($generics)*) ($($path)* + $crate::__private::Sync) ($($bound)*));
-        $crate::__internal_serialize_trait_object!(impl ($($generics)*) ($($path)* + $crate::__private::Send + $crate::__private::Sync) ($($bound)*));
+        )*
     };
+}
 
-    // The impl.
-    (impl ($($generics:tt)*) ($($path:tt)*) ($($bound:tt)*) $({$($body:tt)*})*) => {
-        impl<'erased, $($generics)*> $crate::__private::serde::Serialize for dyn $($path)* + 'erased
+/// Implement `serde::Deserializer` for `&mut dyn Trait` and `Box<dyn Trait>`,
+/// where `Trait` has `erased_serde::Deserializer<'de>` as a supertrait.
+///
+/// The impls cover the trait object together with its `Send`, `Sync` and
+/// `Send + Sync` variants.
+///
+/// ```
+/// use erased_serde::{deserializer_trait_object, Deserializer, PathDeserializer};
+/// use serde::Deserialize as _;
+///
+/// trait InputFormat<'de>: Deserializer<'de> {
+///     fn content_type(&self) -> &str;
+/// }
+///
+/// deserializer_trait_object!(<'de> InputFormat<'de>);
+///
+/// impl<'de> InputFormat<'de> for PathDeserializer<'_, 'de> {
+///     fn content_type(&self) -> &str {
+///         "application/json"
+///     }
+/// }
+///
+/// let json = &mut serde_json::Deserializer::from_slice(br#"[1, 2, "3"]"#);
+/// let format = &mut <dyn Deserializer>::erase(json);
+/// let input: Box<dyn InputFormat> = Box::new(PathDeserializer::new(format));
+///
+/// assert_eq!(input.content_type(), "application/json");
+/// let error = Vec::<u32>::deserialize(input).unwrap_err();
+/// assert_eq!(
+///     error.to_string(),
+///     "[2]: invalid type: string \"3\", expected u32 at line 1 column 10",
+/// );
+/// ```
+///
+/// Traits with parameters and `where` clauses are written with the generics
+/// first, like in [`serialize_trait_object!`][crate::serialize_trait_object].
+#[macro_export]
+macro_rules! deserializer_trait_object {
+    ($($tokens:tt)+) => {
+        $crate::__internal_parse_trait_object!(__internal_deserializer_trait_object [] begin $($tokens)+);
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __internal_deserializer_trait_object {
+    // Expand into impls for the bare trait object and its auto trait
+    // combinations.
+    (($($generics:tt)*) ($($path:tt)*) ($($bound:tt)*)) => {
+        $crate::__internal_deserializer_trait_object!(object ($($generics)*) ($($path)*) ($($bound)*));
+        $crate::__internal_deserializer_trait_object!(object ($($generics)*) ($($path)* + $crate::__private::Send) ($($bound)*));
+        $crate::__internal_deserializer_trait_object!(object ($($generics)*) ($($path)* + $crate::__private::Sync) ($($bound)*));
+        $crate::__internal_deserializer_trait_object!(object ($($generics)*) ($($path)* + $crate::__private::Send + $crate::__private::Sync) ($($bound)*));
+    };
+
+    (object ($($generics:tt)*) ($($object:tt)*) ($($bound:tt)*)) => {
+        $crate::__internal_deserializer_trait_object!(impl {} ($($generics)*) (&mut (dyn $($object)* + 'erased)) ($($object)*) ($($bound)*));
+        $crate::__internal_deserializer_trait_object!(impl {mut} ($($generics)*) ($crate::__private::Box<dyn $($object)* + 'erased>) ($($object)*) ($($bound)*));
+    };
+
+    // The 'de of the supertrait is whichever lifetime the trait object's
+    // Deserializer impl names, so the impl is generic over it.
+    (impl {$($mut:tt)*} ($($generics:tt)*) ($ty:ty) ($($object:tt)*) ($($bound:tt)*)) => {
+        impl<'__de, 'erased, $($generics)*> $crate::__private::serde::Deserializer<'__de> for $ty
         where
+            dyn $($object)* + 'erased: $crate::Deserializer<'__de>,
             $($bound)*
         {
-            fn serialize<S>(&self, serializer: S) -> $crate::__private::Result<S::Ok, S::Error>
+            type Error = $crate::Error;
+
+            $crate::__internal_deserializer_trait_object! {
+                forward {$($mut)*}
+                deserialize_any();
+                deserialize_bool();
+                deserialize_i8();
+                deserialize_i16();
+                deserialize_i32();
+                deserialize_i64();
+                deserialize_i128();
+                deserialize_u8();
+                deserialize_u16();
+                deserialize_u32();
+                deserialize_u64();
+                deserialize_u128();
+                deserialize_f32();
+                deserialize_f64();
+                deserialize_char();
+                deserialize_str();
+                deserialize_string();
+                deserialize_bytes();
+                deserialize_byte_buf();
+                deserialize_option();
+                deserialize_unit();
+                deserialize_unit_struct(name: &'static str);
+                deserialize_newtype_struct(name: &'static str);
+                deserialize_seq();
+                deserialize_tuple(len: usize);
+                deserialize_tuple_struct(name: &'static str, len: usize);
+                deserialize_map();
+                deserialize_struct(name: &'static str, fields: &'static [&'static str]);
+                deserialize_enum(name: &'static str, variants: &'static [&'static str]);
+                deserialize_identifier();
+                deserialize_ignored_any();
+            }
+
+            fn is_human_readable(&self) -> bool {
+                $crate::Deserializer::erased_is_human_readable(&**self)
+            }
+        }
+    };
+
+    (forward {$($mut:tt)*}) => {};
+
+    (forward {$($mut:tt)*} $method:ident($($arg:ident: $ty:ty),*); $($rest:tt)*) => {
+        fn $method<__V>($($mut)* self, $($arg: $ty,)* visitor: __V) -> $crate::__private::Result<__V::Value, $crate::Error>
+        where
+            __V: $crate::__private::serde::de::Visitor<'__de>,
+        {
+            let deserializer = $crate::Deserializer::erased_as_deserializer(&mut *self);
+            $crate::__private::serde::Deserializer::$method(deserializer, $($arg,)* visitor)
+        }
+
+        $crate::__internal_deserializer_trait_object!(forward {$($mut)*} $($rest)*);
+    };
+}
+
+/// Implement `serde::de::DeserializeSeed` for `&mut dyn Trait`, where `Trait`
+/// has `erased_serde::DeserializeInPlace<'de>` as a supertrait.
+///
+/// The seed deserializes into the value behind the reference, and produces
+/// `()`. The impls cover the trait object together with its `Send`, `Sync` and
+/// `Send + Sync` variants. A `Box<dyn Trait>` is reloaded through
+/// `&mut *boxed`.
+///
+/// ```
+/// use erased_serde::{deserialize_in_place_trait_object, DeserializeInPlace};
+/// use serde::de::DeserializeSeed;
+/// use std::collections::BTreeMap as Map;
+///
+/// trait Setting: for<'de> DeserializeInPlace<'de> {}
+///
+/// impl<T> Setting for T where T: for<'de> serde::Deserialize<'de> {}
+///
+/// deserialize_in_place_trait_object!(Setting);
+///
+/// let mut settings: Map<&str, Box<dyn Setting>> = Map::new();
+/// settings.insert("verbose", Box::new(false));
+/// settings.insert("threads", Box::new(4u32));
+///
+/// let json = &mut serde_json::Deserializer::from_str("16");
+/// let threads = settings.get_mut("threads").unwrap();
+/// (&mut **threads).deserialize(json).unwrap();
+/// ```
+///
+/// Traits with parameters and `where` clauses are written with the generics
+/// first, like in [`deserializer_trait_object!`][crate::deserializer_trait_object].
+#[macro_export]
+macro_rules! deserialize_in_place_trait_object {
+    ($($tokens:tt)+) => {
+        $crate::__internal_parse_trait_object!(__internal_deserialize_in_place_trait_object [] begin $($tokens)+);
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __internal_deserialize_in_place_trait_object {
+    // Expand into impls for the bare trait object and its auto trait
+    // combinations.
+    (($($generics:tt)*) ($($path:tt)*) ($($bound:tt)*)) => {
+        $crate::__internal_deserialize_in_place_trait_object!(impl ($($generics)*) ($($path)*) ($($bound)*));
+        $crate::__internal_deserialize_in_place_trait_object!(impl ($($generics)*) ($($path)* + $crate::__private::Send) ($($bound)*));
+        $crate::__internal_deserialize_in_place_trait_object!(impl ($($generics)*) ($($path)* + $crate::__private::Sync) ($($bound)*));
+        $crate::__internal_deserialize_in_place_trait_object!(impl ($($generics)*) ($($path)* + $crate::__private::Send + $crate::__private::Sync) ($($bound)*));
+    };
+
+    (impl ($($generics:tt)*) ($($object:tt)*) ($($bound:tt)*)) => {
+        impl<'__a, '__de, 'erased, $($generics)*> $crate::__private::serde::de::DeserializeSeed<'__de> for &'__a mut (dyn $($object)* + 'erased)
+        where
+            dyn $($object)* + 'erased: $crate::DeserializeInPlace<'__de>,
+            $($bound)*
+        {
+            type Value = ();
+
+            fn deserialize<__D>(self, deserializer: __D) -> $crate::__private::Result<(), __D::Error>
             where
-                S: $crate::__private::serde::Serializer,
+                __D: $crate::__private::serde::Deserializer<'__de>,
             {
-                $($($body)*)*
-                $crate::serialize(self, serializer)
+                $crate::deserialize_in_place(self, deserializer)
             }
         }
     };
 }
 
+/// Generate an object-safe mirror of a trait whose methods are generic over a
+/// Serde `Serializer` or `Deserializer`.
+///
+/// This automates the technique that erased-serde itself is built on. Given a
+/// trait `Store` with methods like `fn save<S: serde::Serializer>(&self,
+/// serializer: S)`, the macro defines an object-safe trait `ErasedStore` whose
+/// methods take `&mut dyn erased_serde::Serializer` or `&mut dyn
+/// erased_serde::Deserializer<'de>` instead, implements it for every sized type
+/// that implements `Store`, and implements `Store` for `dyn ErasedStore` and
+/// `Box<dyn ErasedStore>` along with their `Send`, `Sync` and `Send + Sync`
+/// variants.
+///
+/// ```
+/// use erased_serde::erase_trait;
+/// use serde::{Deserialize, Deserializer, Serialize, Serializer};
+///
+/// trait Store {
+///     fn save<S: Serializer>(&self, serializer: S, compact: bool) -> Result<S::Ok, S::Error>;
+///     fn load<'de, D: Deserializer<'de>>(&mut self, deserializer: D) -> Result<(), D::Error>;
+/// }
+///
+/// erase_trait! {
+///     /// An object-safe `Store`.
+///     pub trait ErasedStore for Store {
+///         fn erased_save for save<S: Serializer>(&self, serializer: S, compact: bool) -> Result<S::Ok, S::Error>;
+///         fn erased_load for load<'de, D: Deserializer<'de>>(&mut self, deserializer: D) -> Result<(), D::Error>;
+///     }
+/// }
+///
+/// struct Counter(u64);
+///
+/// impl Store for Counter {
+///     fn save<S: Serializer>(&self, serializer: S, compact: bool) -> Result<S::Ok, S::Error> {
+///         if compact {
+///             self.0.serialize(serializer)
+///         } else {
+///             ("count", self.0).serialize(serializer)
+///         }
+///     }
+///
+///     fn load<'de, D: Deserializer<'de>>(&mut self, deserializer: D) -> Result<(), D::Error> {
+///         self.0 = u64::deserialize(deserializer)?;
+///         Ok(())
+///     }
+/// }
+///
+/// let mut store: Box<dyn ErasedStore> = Box::new(Counter(0));
+///
+/// store.load(&mut serde_json::Deserializer::from_str("9")).unwrap();
+/// let json = store.save(serde_json::value::Serializer, false).unwrap();
+/// assert_eq!(json, serde_json::json!(["count", 9]));
+/// ```
+///
+/// Each method is written as `fn erased_name for name` followed by the
+/// signature of the method in the original trait, which must have one of two
+/// shapes:
+///
+/// - a type parameter bounded by `Serializer`, taking `&self` or `&mut self`
+///   then the serializer then any other arguments, and returning
+///   `Result<S::Ok, S::Error>`;
+///
+/// - a lifetime and a type parameter bounded by `Deserializer<'de>`, taking
+///   `&self` or `&mut self` then the deserializer then any other arguments,
+///   and returning `Result<T, D::Error>` for some `T` not involving `D`.
+///
+/// Errors produced by the erased method are passed back through the generic
+/// method's own error type.
+#[macro_export]
+macro_rules! erase_trait {
+    (
+        $(#[$attr:meta])*
+        $vis:vis trait $erased:ident for $($generic:ident)::+ {
+            $($methods:tt)*
+        }
+    ) => {
+        $crate::__internal_erase_trait! {
+            parse
+            [$(#[$attr])* $vis $erased]
+            [$($generic)::+]
+            []
+            $($methods)*
+        }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __internal_erase_trait {
+    // Normalize each method into a single group, distinguishing methods over a
+    // Serializer from methods over a Deserializer.
+    (
+        parse $header:tt $generic:tt [$($done:tt)*]
+        $(#[$mattr:meta])*
+        fn $em:ident for $m:ident<$S:ident: $($bound:ident)::+>(&self, $p:ident: $ty:ident $(, $arg:ident: $argty:ty)* $(,)?)
+            -> $($result:ident)::+<$ok:ident::Ok, $err:ident::Error>;
+        $($rest:tt)*
+    ) => {
+        $crate::__internal_erase_trait! {
+            parse $header $generic
+            [$($done)* {ser [$(#[$mattr])*] [&] $em $m $S [$($bound)::+] $p [$($arg: $argty),*]}]
+            $($rest)*
+        }
+    };
+    (
+        parse $header:tt $generic:tt [$($done:tt)*]
+        $(#[$mattr:meta])*
+        fn $em:ident for $m:ident<$S:ident: $($bound:ident)::+>(&mut self, $p:ident: $ty:ident $(, $arg:ident: $argty:ty)* $(,)?)
+            -> $($result:ident)::+<$ok:ident::Ok, $err:ident::Error>;
+        $($rest:tt)*
+    ) => {
+        $crate::__internal_erase_trait! {
+            parse $header $generic
+            [$($done)* {ser [$(#[$mattr])*] [&mut] $em $m $S [$($bound)::+] $p [$($arg: $argty),*]}]
+            $($rest)*
+        }
+    };
+    (
+        parse $header:tt $generic:tt [$($done:tt)*]
+        $(#[$mattr:meta])*
+        fn $em:ident for $m:ident<$de:lifetime, $D:ident: $($bound:ident)::+<$bound_de:lifetime>>(&self, $p:ident: $ty:ident $(, $arg:ident: $argty:ty)* $(,)?)
+            -> $($result:ident)::+<$value:ty, $err:ident::Error>;
+        $($rest:tt)*
+    ) => {
+        $crate::__internal_erase_trait! {
+            parse $header $generic
+            [$($done)* {de [$(#[$mattr])*] [&] $em $m $de $D [$($bound)::+<$bound_de>] $p [$($arg: $argty),*] [$value]}]
+            $($rest)*
+        }
+    };
+    (
+        parse $header:tt $generic:tt [$($done:tt)*]
+        $(#[$mattr:meta])*
+        fn $em:ident for $m:ident<$de:lifetime, $D:ident: $($bound:ident)::+<$bound_de:lifetime>>(&mut self, $p:ident: $ty:ident $(, $arg:ident: $argty:ty)* $(,)?)
+            -> $($result:ident)::+<$value:ty, $err:ident::Error>;
+        $($rest:tt)*
+    ) => {
+        $crate::__internal_erase_trait! {
+            parse $header $generic
+            [$($done)* {de [$(#[$mattr])*] [&mut] $em $m $de $D [$($bound)::+<$bound_de>] $p [$($arg: $argty),*] [$value]}]
+            $($rest)*
+        }
+    };
+
+    (parse [$(#[$attr:meta])* $vis:vis $erased:ident] [$($generic:tt)*] [$($methods:tt)*]) => {
+        $(#[$attr])*
+        $vis trait $erased {
+            $crate::__internal_erase_trait!(decl $($methods)*);
+        }
+
+        impl<__T> $erased for __T
+        where
+            __T: $($generic)*,
+        {
+            $crate::__internal_erase_trait!(blanket [$($generic)*] $($methods)*);
+        }
+
+        $crate::__internal_erase_trait!(impl [$erased] [$($generic)*] (dyn $erased + 'erased) [*] $($methods)*);
+        $crate::__internal_erase_trait!(impl [$erased] [$($generic)*] (dyn $erased + $crate::__private::Send + 'erased) [*] $($methods)*);
+        $crate::__internal_erase_trait!(impl [$erased] [$($generic)*] (dyn $erased + $crate::__private::Sync + 'erased) [*] $($methods)*);
+        $crate::__internal_erase_trait!(impl [$erased] [$($generic)*] (dyn $erased + $crate::__private::Send + $crate::__private::Sync + 'erased) [*] $($methods)*);
+        $crate::__internal_erase_trait!(impl [$erased] [$($generic)*] ($crate::__private::Box<dyn $erased + 'erased>) [**] $($methods)*);
+        $crate::__internal_erase_trait!(impl [$erased] [$($generic)*] ($crate::__private::Box<dyn $erased + $crate::__private::Send + 'erased>) [**] $($methods)*);
+        $crate::__internal_erase_trait!(impl [$erased] [$($generic)*] ($crate::__private::Box<dyn $erased + $crate::__private::Sync + 'erased>) [**] $($methods)*);
+        $crate::__internal_erase_trait!(impl [$erased] [$($generic)*] ($crate::__private::Box<dyn $erased + $crate::__private::Send + $crate::__private::Sync + 'erased>) [**] $($methods)*);
+    };
+
+    (decl) => {};
+    (decl {ser [$(#[$mattr:meta])*] [$($recv:tt)*] $em:ident $m:ident $S:ident $bound:tt $p:ident [$($arg:ident: $argty:ty),*]} $($rest:tt)*) => {
+        $(#[$mattr])*
+        fn $em($($recv)* self, $p: &mut dyn $crate::Serializer, $($arg: $argty),*) -> $crate::__private::Result<(), $crate::Error>;
+
+        $crate::__internal_erase_trait!(decl $($rest)*);
+    };
+    (decl {de [$(#[$mattr:meta])*] [$($recv:tt)*] $em:ident $m:ident $de:lifetime $D:ident $bound:tt $p:ident [$($arg:ident: $argty:ty),*] [$value:ty]} $($rest:tt)*) => {
+        $(#[$mattr])*
+        fn $em<$de>($($recv)* self, $p: &mut dyn $crate::Deserializer<$de>, $($arg: $argty),*) -> $crate::__private::Result<$value, $crate::Error>;
+
+        $crate::__internal_erase_trait!(decl $($rest)*);
+    };
+
+    (blanket $generic:tt) => {};
+    (blanket [$($generic:tt)*] {ser $mattr:tt [$($recv:tt)*] $em:ident $m:ident $S:ident $bound:tt $p:ident [$($arg:ident: $argty:ty),*]} $($rest:tt)*) => {
+        fn $em($($recv)* self, $p: &mut dyn $crate::Serializer, $($arg: $argty),*) -> $crate::__private::Result<(), $crate::Error> {
+            <Self as $($generic)*>::$m(self, $p, $($arg),*)
+        }
+
+        $crate::__internal_erase_trait!(blanket [$($generic)*] $($rest)*);
+    };
+    (blanket [$($generic:tt)*] {de $mattr:tt [$($recv:tt)*] $em:ident $m:ident $de:lifetime $D:ident $bound:tt $p:ident [$($arg:ident: $argty:ty),*] [$value:ty]} $($rest:tt)*) => {
+        fn $em<$de>($($recv)* self, $p: &mut dyn $crate::Deserializer<$de>, $($arg: $argty),*) -> $crate::__private::Result<$value, $crate::Error> {
+            <Self as $($generic)*>::$m(self, $p, $($arg),*)
+        }
+
+        $crate::__internal_erase_trait!(blanket [$($generic)*] $($rest)*);
+    };
+
+    (impl $erased:tt [$($generic:tt)*] ($($ty:tt)*) $deref:tt $($methods:tt)*) => {
+        impl<'erased> $($generic)* for $($ty)* {
+            $crate::__internal_erase_trait!(forward $erased $deref $($methods)*);
+        }
+    };
+
+    (forward $erased:tt $deref:tt) => {};
+    (forward [$erased:ident] [$($deref:tt)*] {ser $mattr:tt [$($recv:tt)*] $em:ident $m:ident $S:ident [$($bound:tt)*] $p:ident [$($arg:ident: $argty:ty),*]} $($rest:tt)*) => {
+        fn $m<$S>($($recv)* self, $p: $S, $($arg: $argty),*) -> $crate::__private::Result<$S::Ok, $S::Error>
+        where
+            $S: $($bound)*,
+        {
+            $crate::__private::serialize_with($p, |$p| $erased::$em($($recv)* $($deref)* self, $p, $($arg),*))
+        }
+
+        $crate::__internal_erase_trait!(forward [$erased] [$($deref)*] $($rest)*);
+    };
+    (forward [$erased:ident] [$($deref:tt)*] {de $mattr:tt [$($recv:tt)*] $em:ident $m:ident $de:lifetime $D:ident [$($bound:tt)*] $p:ident [$($arg:ident: $argty:ty),*] [$value:ty]} $($rest:tt)*) => {
+        fn $m<$de, $D>($($recv)* self, $p: $D, $($arg: $argty),*) -> $crate::__private::Result<$value, $D::Error>
+        where
+            $D: $($bound)*,
+        {
+            $crate::__private::deserialize_with($p, |$p| $erased::$em($($recv)* $($deref)* self, $p, $($arg),*))
+        }
+
+        $crate::__internal_erase_trait!(forward [$erased] [$($deref)*] $($rest)*);
+    };
+}
+
 macro_rules! return_impl_trait {
     (
         $(#[$attr:meta])*
-        $vis:vis fn $name:ident <$param:ident> $args:tt -> $impl_trait:ty [$concrete:ty] $($body:tt)+
+        $vis:vis fn $name:ident <$($param:ident),+> $args:tt -> $impl_trait:ty [$concrete:ty] $($body:tt)+
     ) => {
         #[cfg(not(docsrs))]
         $(#[$attr])*
-        $vis fn $name <$param> $args -> $concrete $($body)+
+        $vis fn $name <$($param),+> $args -> $concrete $($body)+
 
         #[cfg(docsrs)]
         $(#[$attr])*
-        $vis fn $name <$param> $args -> $impl_trait $($body)+
+        $vis fn $name <$($param),+> $args -> $impl_trait $($body)+
     };
 }
 
@@ -134,10 +1031,20 @@ macro_rules! return_impl_trait {
 
 #[cfg(test)]
 mod tests {
-    use crate::Serialize;
+    use crate::{Registry, Serialize};
+    use alloc::boxed::Box;
+    use alloc::sync::Arc;
+    use serde::de::DeserializeOwned;
+    use std::sync::OnceLock;
 
     fn assert_serialize<T: ?Sized + serde::Serialize>() {}
 
+    fn assert_deserialize<T: DeserializeOwned>() {}
+
+    fn assert_serializer<T: serde::Serializer>() {}
+
+    fn assert_deserializer<'de, T: serde::Deserializer<'de>>() {}
+
     #[test]
     fn test_plain() {
         trait Trait: Serialize {}
@@ -177,4 +1084,299 @@ mod tests {
         assert_serialize::<dyn Trait<u32>>();
         assert_serialize::<dyn Trait<u32> + Send>();
     }
+
+    #[test]
+    fn test_lifetime() {
+        trait Trait<'a>: Serialize {}
+
+        serialize_trait_object!(<'a> Trait<'a>);
+        assert_serialize::<dyn Trait<'static>>();
+        assert_serialize::<dyn Trait<'static> + Send + Sync>();
+    }
+
+    #[test]
+    fn test_associated_type_binding() {
+        struct Json;
+
+        trait Handler<'a>: Serialize {
+            type Output;
+        }
+
+        serialize_trait_object!(<'a> Handler<'a, Output = Json>);
+        assert_serialize::<dyn Handler<Output = Json>>();
+        assert_serialize::<dyn Handler<Output = Json> + Send>();
+    }
+
+    #[test]
+    fn test_auto_traits() {
+        trait Trait<T>: Serialize {
+            type Output;
+        }
+
+        serialize_trait_object!(
+            #[auto_traits(Send, Send + Unpin, core::panic::UnwindSafe + Sync)]
+            <T> Trait<T, Output = ()> where T: Clone
+        );
+        assert_serialize::<dyn Trait<u32, Output = ()>>();
+        assert_serialize::<dyn Trait<u32, Output = ()> + Send>();
+        assert_serialize::<dyn Trait<u32, Output = ()> + Send + Unpin>();
+        assert_serialize::<dyn Trait<u32, Output = ()> + core::panic::UnwindSafe + Sync>();
+    }
+
+    #[test]
+    fn test_auto_traits_with_tag() {
+        trait Trait: Serialize {}
+
+        serialize_trait_object!(
+            #[auto_traits()]
+            #[external]
+            Trait
+        );
+        assert_serialize::<dyn Trait>();
+
+        trait Other: Serialize {}
+
+        serialize_trait_object!(
+            #[external]
+            #[auto_traits(Unpin)]
+            Other
+        );
+        assert_serialize::<dyn Other>();
+        assert_serialize::<dyn Other + Unpin>();
+    }
+
+    #[test]
+    fn test_deserialize_plain() {
+        trait Trait {}
+
+        fn registry() -> &'static Registry<dyn Trait> {
+            static REGISTRY: OnceLock<Registry<dyn Trait>> = OnceLock::new();
+            REGISTRY.get_or_init(Registry::new)
+        }
+
+        deserialize_trait_object!(Trait, registry = registry);
+        assert_deserialize::<Box<dyn Trait>>();
+        assert_deserialize::<Arc<dyn Trait>>();
+    }
+
+    #[test]
+    fn test_deserialize_send_sync() {
+        trait Trait {}
+
+        fn registry() -> &'static Registry<dyn Trait + Send + Sync> {
+            static REGISTRY: OnceLock<Registry<dyn Trait + Send + Sync>> = OnceLock::new();
+            REGISTRY.get_or_init(Registry::new)
+        }
+
+        deserialize_trait_object!(Trait + Send + Sync, registry = registry);
+        assert_deserialize::<Box<dyn Trait + Send + Sync>>();
+        assert_deserialize::<Arc<dyn Trait + Send + Sync>>();
+    }
+
+    #[test]
+    fn test_deserialize_type_arguments() {
+        trait Trait<T, U> {}
+
+        fn registry() -> &'static Registry<dyn Trait<u8, u16>> {
+            static REGISTRY: OnceLock<Registry<dyn Trait<u8, u16>>> = OnceLock::new();
+            REGISTRY.get_or_init(Registry::new)
+        }
+
+        deserialize_trait_object!(Trait<u8, u16>, registry = registry);
+        assert_deserialize::<Box<dyn Trait<u8, u16>>>();
+    }
+
+    #[test]
+    fn test_serializer_plain() {
+        trait Trait: crate::Serializer {}
+
+        serializer_trait_object!(Trait);
+        assert_serializer::<&mut dyn Trait>();
+        assert_serializer::<&mut (dyn Trait + Send + Sync)>();
+    }
+
+    #[test]
+    fn test_serializer_generics() {
+        trait Trait<'a, T: 'a>: crate::Serializer {}
+
+        serializer_trait_object!(<'a, T> Trait<'a, T> where T: Clone);
+        assert_serializer::<&mut dyn Trait<u8>>();
+        assert_serializer::<&mut (dyn Trait<u8> + Send)>();
+    }
+
+    #[test]
+    fn test_serializer_generic_bounds() {
+        trait Trait<'a, T: Clone + 'a, U: Iterator<Item = T>>: crate::Serializer {}
+
+        serializer_trait_object!(<'a, T: Clone + 'a, U: Iterator<Item = T>,> Trait<'a, T, U>);
+        assert_serializer::<&mut dyn Trait<u8, core::iter::Empty<u8>>>();
+        assert_serializer::<&mut (dyn Trait<u8, core::iter::Empty<u8>> + Sync)>();
+    }
+
+    #[test]
+    fn test_serializer_reborrow() {
+        use alloc::string::ToString;
+        use serde::ser::Serialize as _;
+        use std::io;
+
+        trait Trait: crate::Serializer {}
+
+        impl<T: crate::Serializer> Trait for T {}
+
+        serializer_trait_object!(Trait);
+
+        struct Full;
+
+        impl io::Write for Full {
+            fn write(&mut self, _buf: &[u8]) -> io::Result<usize> {
+                Err(io::Error::new(io::ErrorKind::Other, "full"))
+            }
+
+            fn flush(&mut self) -> io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let mut out = alloc::vec::Vec::new();
+        let json = &mut serde_json::Serializer::new(&mut out);
+        let mut boxed: Box<dyn Trait> = Box::new(<dyn crate::Serializer>::erase(json));
+        (1, "x").serialize(&mut *boxed).unwrap();
+        drop(boxed);
+        assert_eq!(out, b"[1,\"x\"]");
+
+        let json = &mut serde_json::Serializer::new(Full);
+        let mut boxed: Box<dyn Trait> = Box::new(<dyn crate::Serializer>::erase(json));
+        let err = true.serialize(&mut *boxed).unwrap_err();
+        assert_eq!(err.to_string(), "full");
+    }
+
+    #[test]
+    fn test_deserializer_plain() {
+        trait Trait<'de>: crate::Deserializer<'de> {}
+
+        deserializer_trait_object!(<'de> Trait<'de>);
+        assert_deserializer::<&mut dyn Trait>();
+        assert_deserializer::<Box<dyn Trait>>();
+        assert_deserializer::<Box<dyn Trait + Send + Sync>>();
+    }
+
+    #[test]
+    fn test_deserializer_generic_bounds() {
+        trait Trait<'de, T: Clone>: crate::Deserializer<'de> {}
+
+        deserializer_trait_object!(<'de, T: Clone> Trait<'de, T> where T: Default);
+        assert_deserializer::<&mut dyn Trait<u8>>();
+        assert_deserializer::<Box<dyn Trait<u8> + Send>>();
+    }
+
+    #[test]
+    fn test_deserialize_in_place_generic_bounds() {
+        fn assert_seed<'de, T: serde::de::DeserializeSeed<'de>>() {}
+
+        trait Trait<T: Clone>: for<'de> crate::DeserializeInPlace<'de> {}
+
+        deserialize_in_place_trait_object!(<T: Clone> Trait<T> where T: Default);
+        assert_seed::<&mut dyn Trait<u8>>();
+        assert_seed::<&mut (dyn Trait<u8> + Send + Sync)>();
+    }
+
+    #[test]
+    fn test_deserializer_owned() {
+        trait Trait: for<'de> crate::Deserializer<'de> {}
+
+        deserializer_trait_object!(Trait);
+        assert_deserializer::<Box<dyn Trait>>();
+        assert_deserializer::<&mut (dyn Trait + Sync)>();
+    }
+
+    mod erase {
+        use alloc::boxed::Box;
+        use alloc::string::ToString;
+        use alloc::vec;
+        use alloc::vec::Vec;
+        use serde::de::Deserialize;
+        use serde::ser::{Error as _, Serialize, Serializer};
+        use serde_json::json;
+
+        pub trait Store {
+            fn save<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error>;
+            fn save_nth<S: serde::Serializer>(
+                &self,
+                serializer: S,
+                n: usize,
+                label: &str,
+            ) -> Result<S::Ok, S::Error>;
+            fn load<'de, D: serde::Deserializer<'de>>(
+                &mut self,
+                deserializer: D,
+            ) -> Result<usize, D::Error>;
+        }
+
+        erase_trait! {
+            pub trait ErasedStore for self::Store {
+                fn erased_save for save<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error>;
+                fn erased_save_nth for save_nth<S: serde::Serializer>(&self, serializer: S, n: usize, label: &str) -> Result<S::Ok, S::Error>;
+                fn erased_load for load<'de, D: serde::Deserializer<'de>>(&mut self, deserializer: D) -> Result<usize, D::Error>;
+            }
+        }
+
+        struct Numbers(Vec<u8>);
+
+        impl Store for Numbers {
+            fn save<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                self.0.serialize(serializer)
+            }
+
+            fn save_nth<S: Serializer>(
+                &self,
+                serializer: S,
+                n: usize,
+                label: &str,
+            ) -> Result<S::Ok, S::Error> {
+                match self.0.get(n) {
+                    Some(number) => (label, number).serialize(serializer),
+                    None => Err(S::Error::custom("out of range")),
+                }
+            }
+
+            fn load<'de, D: serde::Deserializer<'de>>(
+                &mut self,
+                deserializer: D,
+            ) -> Result<usize, D::Error> {
+                self.0 = Deserialize::deserialize(deserializer)?;
+                Ok(self.0.len())
+            }
+        }
+
+        #[test]
+        fn test_erase_trait() {
+            let mut store: Box<dyn ErasedStore + Send + Sync> = Box::new(Numbers(vec![1, 2]));
+
+            let json = &mut serde_json::Deserializer::from_str("[3, 4, 5]");
+            assert_eq!(store.load(json).unwrap(), 3);
+
+            let value = store.save(serde_json::value::Serializer).unwrap();
+            assert_eq!(value, json!([3, 4, 5]));
+
+            let value = (*store).save_nth(serde_json::value::Serializer, 1, "second");
+            assert_eq!(value.unwrap(), json!(["second", 4]));
+        }
+
+        #[test]
+        fn test_erase_trait_errors() {
+            let mut store: Box<dyn ErasedStore> = Box::new(Numbers(vec![1, 2]));
+
+            let err = store
+                .save_nth(serde_json::value::Serializer, 2, "third")
+                .unwrap_err();
+            assert_eq!(err.to_string(), "out of range");
+
+            let json = &mut serde_json::Deserializer::from_str("[-1]");
+            let err = store.load(json).unwrap_err();
+            assert_eq!(
+                err.to_string(),
+                "invalid value: integer `-1`, expected u8 at line 1 column 3",
+            );
+        }
+    }
 }
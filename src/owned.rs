@@ -0,0 +1,302 @@
+use crate::de::{Deserializer, Out, Visitor};
+use crate::error::Error;
+use crate::sealed::deserializer::Sealed;
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::mem;
+
+/// A `Deserializer<'static>` that owns the input it deserializes from.
+///
+/// A `dyn Deserializer<'de>` made from a format's deserializer borrows its
+/// input for `'de`, so it cannot be kept in a long-lived struct or sent to
+/// another thread apart from the bytes. `OwnedDeserializer` takes the bytes or
+/// string by value together with a closure that builds the format's
+/// deserializer over them, and keeps both.
+///
+/// Strings and bytes which the format would lend out of the input are passed
+/// to the `Deserialize` impl as transient ones instead, since the input does
+/// not outlive the `OwnedDeserializer`. Types that borrow from the input, such
+/// as `&str`, therefore cannot be deserialized from it, while `String` and
+/// other owned types can.
+///
+/// Whether data after the value is an error is up to the format deserializer
+/// that `constructor` returns. One made by `<dyn Deserializer>::erase_owned`
+/// leaves it unread, so `"1 garbage"` deserializes as `1`, while one made by
+/// `<dyn Deserializer>::erase_owned_with_end` rejects it.
+///
+/// ```
+/// use erased_serde::{Deserializer, OwnedDeserializer};
+/// use std::collections::BTreeMap as Map;
+/// use std::thread;
+///
+/// let input = String::from(r#"{"A": 65, "B": 66}"#);
+/// let mut format: Box<dyn Deserializer<'static> + Send> =
+///     Box::new(OwnedDeserializer::from_string(input, |input| {
+///         let json = serde_json::Deserializer::from_str(input);
+///         Box::new(<dyn Deserializer>::erase_owned_with_end(json, |json| json.end()))
+///     }));
+///
+/// let data = thread::spawn(move || {
+///     erased_serde::deserialize::<Map<String, usize>>(&mut format).unwrap()
+/// })
+/// .join()
+/// .unwrap();
+///
+/// assert_eq!(data["A"] + data["B"], 131);
+/// ```
+pub struct OwnedDeserializer {
+    // Borrows from `input`. The lifetime is a lie, kept from ever reaching a
+    // visitor by going through detach. Fields are dropped in declaration order,
+    // so the deserializer is dropped before the input.
+    deserializer: Box<dyn Deserializer<'static> + Send>,
+    _input: Input,
+}
+
+// The raw pointer is an owned allocation that is never written to.
+unsafe impl Send for OwnedDeserializer {}
+
+// Frees the input when dropped, including when `constructor` panics.
+struct Input(*mut [u8]);
+
+impl Drop for Input {
+    fn drop(&mut self) {
+        unsafe { drop(Box::from_raw(self.0)) }
+    }
+}
+
+impl OwnedDeserializer {
+    /// Deserialize from the given bytes, using the format deserializer built by
+    /// `constructor`.
+    pub fn from_vec<F>(input: Vec<u8>, constructor: F) -> Self
+    where
+        F: for<'a> FnOnce(&'a [u8]) -> Box<dyn Deserializer<'a> + Send + 'a>,
+    {
+        let input = Input(Box::into_raw(input.into_boxed_slice()));
+        let deserializer = constructor(unsafe { &*input.0 });
+        OwnedDeserializer {
+            deserializer: unsafe { extend_lifetime(deserializer) },
+            _input: input,
+        }
+    }
+
+    /// Deserialize from the given string, using the format deserializer built
+    /// by `constructor`.
+    pub fn from_string<F>(input: String, constructor: F) -> Self
+    where
+        F: for<'a> FnOnce(&'a str) -> Box<dyn Deserializer<'a> + Send + 'a>,
+    {
+        let input = Box::into_raw(input.into_boxed_str());
+        let input = Input(input as *mut [u8]);
+        let deserializer = constructor(unsafe { &*(input.0 as *const str) });
+        OwnedDeserializer {
+            deserializer: unsafe { extend_lifetime(deserializer) },
+            _input: input,
+        }
+    }
+
+    fn detach(&mut self) -> impl Deserializer<'static> + '_ {
+        <dyn Deserializer>::detach(&mut *self.deserializer)
+    }
+}
+
+unsafe fn extend_lifetime<'a>(
+    deserializer: Box<dyn Deserializer<'a> + Send + 'a>,
+) -> Box<dyn Deserializer<'static> + Send> {
+    unsafe { mem::transmute(deserializer) }
+}
+
+macro_rules! erased_deserialize_detached {
+    ($($erased:ident($($arg:ident: $ty:ty),*);)*) => {
+        $(
+            fn $erased(&mut self, $($arg: $ty,)* visitor: &mut dyn Visitor<'static>) -> Result<Out, Error> {
+                self.detach().$erased($($arg,)* visitor)
+            }
+        )*
+    };
+}
+
+impl Deserializer<'static> for OwnedDeserializer {
+    erased_deserialize_detached! {
+        erased_deserialize_any();
+        erased_deserialize_bool();
+        erased_deserialize_i8();
+        erased_deserialize_i16();
+        erased_deserialize_i32();
+        erased_deserialize_i64();
+        erased_deserialize_i128();
+        erased_deserialize_u8();
+        erased_deserialize_u16();
+        erased_deserialize_u32();
+        erased_deserialize_u64();
+        erased_deserialize_u128();
+        erased_deserialize_f32();
+        erased_deserialize_f64();
+        erased_deserialize_char();
+        erased_deserialize_str();
+        erased_deserialize_string();
+        erased_deserialize_bytes();
+        erased_deserialize_byte_buf();
+        erased_deserialize_option();
+        erased_deserialize_unit();
+        erased_deserialize_unit_struct(name: &'static str);
+        erased_deserialize_newtype_struct(name: &'static str);
+        erased_deserialize_seq();
+        erased_deserialize_tuple(len: usize);
+        erased_deserialize_tuple_struct(name: &'static str, len: usize);
+        erased_deserialize_map();
+        erased_deserialize_struct(name: &'static str, fields: &'static [&'static str]);
+        erased_deserialize_identifier();
+        erased_deserialize_enum(name: &'static str, variants: &'static [&'static str]);
+        erased_deserialize_ignored_any();
+    }
+
+    fn erased_is_human_readable(&self) -> bool {
+        self.deserializer.erased_is_human_readable()
+    }
+
+    fn erased_as_deserializer(&mut self) -> &mut dyn Deserializer<'static> {
+        self
+    }
+}
+
+impl Sealed for OwnedDeserializer {}
+
+// TEST ////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use crate::{Deserializer, ErrorKind, OwnedDeserializer};
+    use alloc::borrow::ToOwned;
+    use alloc::boxed::Box;
+    use alloc::string::{String, ToString};
+    use alloc::vec;
+    use alloc::vec::Vec;
+    use core::fmt;
+    use serde::de::{Deserializer as _, Visitor};
+    use serde_derive::Deserialize;
+
+    fn json(input: &str) -> OwnedDeserializer {
+        OwnedDeserializer::from_string(input.to_owned(), |input| {
+            let json = serde_json::Deserializer::from_str(input);
+            Box::new(<dyn Deserializer>::erase_owned_with_end(json, |json| {
+                json.end()
+            }))
+        })
+    }
+
+    #[test]
+    fn test_owned_types() {
+        #[derive(Deserialize, PartialEq, Debug)]
+        enum Message {
+            Text(String),
+            Bytes(Vec<u8>),
+        }
+
+        let mut format = json(r#"[{"Text": "hi"}, {"Bytes": [1, 2]}]"#);
+        let messages: Vec<Message> = crate::deserialize(&mut format).unwrap();
+        let expected = vec![Message::Text("hi".to_owned()), Message::Bytes(vec![1, 2])];
+        assert_eq!(messages, expected);
+    }
+
+    #[test]
+    fn test_borrowed_str() {
+        let mut format = json(r#""plain""#);
+        let string: String = crate::deserialize(&mut format).unwrap();
+        assert_eq!(string, "plain");
+
+        // A type that can only borrow is rejected rather than left dangling.
+        let mut format = json(r#""plain""#);
+        let error = crate::deserialize::<&str>(&mut format).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "invalid type: string \"plain\", expected a borrowed string at line 1 column 7",
+        );
+    }
+
+    #[test]
+    fn test_trailing_data() {
+        let mut format = json("1 garbage");
+        let error = crate::deserialize::<u8>(&mut format).unwrap_err();
+        assert_eq!(error.to_string(), "trailing characters at line 1 column 3");
+
+        // Without an end check, the rest of the input is left for later.
+        let mut format = OwnedDeserializer::from_string("1 2".to_owned(), |input| {
+            let json = serde_json::Deserializer::from_str(input);
+            Box::new(<dyn Deserializer>::erase_owned(json))
+        });
+        let one: u8 = crate::deserialize(&mut format).unwrap();
+        let two: u8 = crate::deserialize(&mut format).unwrap();
+        assert_eq!((one, two), (1, 2));
+    }
+
+    #[test]
+    fn test_constructor_panic() {
+        let result = std::panic::catch_unwind(|| {
+            OwnedDeserializer::from_vec(b"[]".to_vec(), |_input| panic!("no format"))
+        });
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_error_kind() {
+        #[derive(Deserialize, Debug)]
+        #[allow(dead_code)]
+        struct Config {
+            name: String,
+        }
+
+        let mut format = json("{}");
+        let error = crate::deserialize::<Config>(&mut format).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::MissingField);
+    }
+
+    // Records how the format handed over the bytes.
+    struct Bytes;
+
+    impl<'de> Visitor<'de> for Bytes {
+        type Value = (&'static str, Vec<u8>);
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("bytes")
+        }
+
+        fn visit_bytes<E>(self, v: &[u8]) -> Result<Self::Value, E> {
+            Ok(("transient", v.to_vec()))
+        }
+
+        fn visit_borrowed_bytes<E>(self, v: &'de [u8]) -> Result<Self::Value, E> {
+            Ok(("borrowed", v.to_vec()))
+        }
+    }
+
+    #[test]
+    fn test_bytes() {
+        let cbor = [0x44, b'c', b'b', b'o', b'r'];
+
+        let de = &mut serde_cbor::Deserializer::from_slice(&cbor);
+        let bytes = de.deserialize_bytes(Bytes).unwrap();
+        assert_eq!(bytes, ("borrowed", b"cbor".to_vec()));
+
+        let mut format = OwnedDeserializer::from_vec(cbor.to_vec(), |input| {
+            let cbor = serde_cbor::Deserializer::from_slice(input);
+            Box::new(<dyn Deserializer>::erase_owned(cbor))
+        });
+        let bytes = (&mut format as &mut dyn Deserializer)
+            .deserialize_bytes(Bytes)
+            .unwrap();
+        assert_eq!(bytes, ("transient", b"cbor".to_vec()));
+    }
+
+    #[test]
+    fn test_send() {
+        let mut format: Box<dyn Deserializer<'static> + Send> = Box::new(json("[1, 2]"));
+        let sum = std::thread::spawn(move || {
+            let numbers: Vec<u32> = crate::deserialize(&mut format).unwrap();
+            numbers.iter().sum::<u32>()
+        })
+        .join()
+        .unwrap();
+        assert_eq!(sum.to_string(), "3");
+    }
+}
@@ -0,0 +1,1931 @@
+use crate::de::{Deserializer, Out, Visitor};
+use crate::error::{with_cause, with_path, Error};
+use crate::sealed::deserializer::Sealed;
+use crate::ser::{MakeSerializer, Serialize, Serializer};
+use alloc::borrow::ToOwned;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::cell::Cell;
+use core::fmt::{self, Display};
+use serde::ser::Impossible;
+
+/// Location within a value at which serialization or deserialization failed,
+/// such as `events[12].payload.user_id`.
+///
+/// Returned by [`Error::path`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Path {
+    segments: Vec<Segment>,
+}
+
+/// One step of a [`Path`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Segment {
+    /// Element of a sequence or tuple, by position.
+    Seq { index: usize },
+    /// Field of a struct, or entry of a map whose key is a string, number,
+    /// bool or char.
+    Map { key: String },
+    /// Content of an enum variant.
+    Enum { variant: String },
+    /// Entry of a map whose key cannot be rendered as text.
+    Unknown,
+}
+
+impl Path {
+    /// The steps from the outermost value inward.
+    #[must_use]
+    pub fn segments(&self) -> &[Segment] {
+        &self.segments
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.segments.is_empty()
+    }
+}
+
+impl Display for Path {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        if self.segments.is_empty() {
+            return formatter.write_str(".");
+        }
+        for (i, segment) in self.segments.iter().enumerate() {
+            match segment {
+                Segment::Seq { index } => write!(formatter, "[{index}]")?,
+                Segment::Map { key } | Segment::Enum { variant: key } => {
+                    if i > 0 {
+                        formatter.write_str(".")?;
+                    }
+                    formatter.write_str(key)?;
+                }
+                Segment::Unknown => {
+                    if i > 0 {
+                        formatter.write_str(".")?;
+                    }
+                    formatter.write_str("?")?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Serialize the given type-erased value, recording where in it an error
+/// occurred.
+///
+/// Any error is returned with its [`Path`] attached, which is also included in
+/// the error's `Display` output.
+///
+/// ```rust
+/// use erased_serde::{Serialize, Serializer};
+/// use serde::ser::{Error as _, Serializer as _};
+/// use serde_derive::Serialize;
+///
+/// struct UserId(u64);
+///
+/// impl serde::Serialize for UserId {
+///     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+///     where
+///         S: serde::Serializer,
+///     {
+///         if self.0 == 0 {
+///             return Err(S::Error::custom("user id must not be zero"));
+///         }
+///         serializer.serialize_u64(self.0)
+///     }
+/// }
+///
+/// #[derive(Serialize)]
+/// struct Payload {
+///     user_id: UserId,
+/// }
+///
+/// #[derive(Serialize)]
+/// struct Event {
+///     payload: Payload,
+/// }
+///
+/// let events: Vec<Box<dyn Serialize>> = vec![
+///     Box::new(Event { payload: Payload { user_id: UserId(1) } }),
+///     Box::new(Event { payload: Payload { user_id: UserId(0) } }),
+/// ];
+///
+/// let json = &mut serde_json::Serializer::new(Vec::new());
+/// let format = &mut <dyn Serializer>::erase(json);
+///
+/// let error = erased_serde::serialize_with_path(&events, format).unwrap_err();
+/// assert_eq!(error.path().unwrap().to_string(), "[1].payload.user_id");
+/// assert_eq!(error.to_string(), "[1].payload.user_id: user id must not be zero");
+/// ```
+pub fn serialize_with_path<T>(value: &T, serializer: &mut dyn Serializer) -> Result<(), Error>
+where
+    T: ?Sized + Serialize,
+{
+    let track = Track::new();
+    let wrapped = TrackedSerializer {
+        delegate: MakeSerializer(&mut *serializer),
+        chain: &Chain::Root,
+        track: &track,
+    };
+    crate::ser::serialize(value, wrapped).map_err(|err| {
+        let error = crate::ser::take_error(serializer, err);
+        with_path(error, track.into_path())
+    })
+}
+
+// TRACKING ////////////////////////////////////////////////////////////////////
+
+// Path to the current value, built up on the stack as the value is traversed
+// and only turned into a Path once something fails.
+pub(crate) enum Chain<'a> {
+    Root,
+    Seq {
+        parent: &'a Chain<'a>,
+        index: usize,
+    },
+    Map {
+        parent: &'a Chain<'a>,
+        key: &'a str,
+    },
+    Enum {
+        parent: &'a Chain<'a>,
+        variant: &'a str,
+    },
+    Unknown {
+        parent: &'a Chain<'a>,
+    },
+}
+
+impl Chain<'_> {
+    fn to_path(&self) -> Path {
+        let mut segments = Vec::new();
+        let mut chain = self;
+        loop {
+            let (segment, parent) = match chain {
+                Chain::Root => break,
+                Chain::Seq { parent, index } => (Segment::Seq { index: *index }, parent),
+                Chain::Map { parent, key } => (
+                    Segment::Map {
+                        key: (*key).to_owned(),
+                    },
+                    parent,
+                ),
+                Chain::Enum { parent, variant } => (
+                    Segment::Enum {
+                        variant: (*variant).to_owned(),
+                    },
+                    parent,
+                ),
+                Chain::Unknown { parent } => (Segment::Unknown, parent),
+            };
+            segments.push(segment);
+            chain = parent;
+        }
+        segments.reverse();
+        Path { segments }
+    }
+}
+
+// Holds the path of the innermost failure. Every level that sees the error go
+// by reports its own location, and only the first of those is kept.
+pub(crate) struct Track {
+    path: Cell<Option<Path>>,
+}
+
+impl Track {
+    pub(crate) fn new() -> Self {
+        Track {
+            path: Cell::new(None),
+        }
+    }
+
+    pub(crate) fn trigger<E>(&self, chain: &Chain, err: E) -> E {
+        let path = match self.path.take() {
+            Some(path) => path,
+            None => chain.to_path(),
+        };
+        self.path.set(Some(path));
+        err
+    }
+
+    pub(crate) fn into_path(self) -> Path {
+        self.path.into_inner().unwrap_or_default()
+    }
+}
+
+// SERIALIZER //////////////////////////////////////////////////////////////////
+
+struct TrackedValue<'a, 'b, T: ?Sized> {
+    value: &'a T,
+    chain: &'a Chain<'a>,
+    track: &'b Track,
+}
+
+impl<T> serde::Serialize for TrackedValue<'_, '_, T>
+where
+    T: ?Sized + serde::Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        self.value.serialize(TrackedSerializer {
+            delegate: serializer,
+            chain: self.chain,
+            track: self.track,
+        })
+    }
+}
+
+struct TrackedSerializer<'a, 'b, S> {
+    delegate: S,
+    chain: &'a Chain<'a>,
+    track: &'b Track,
+}
+
+// Used for sequences, tuples and tuple structs.
+struct TrackedSeq<'a, 'b, S> {
+    delegate: S,
+    chain: &'a Chain<'a>,
+    index: usize,
+    track: &'b Track,
+}
+
+struct TrackedMap<'a, 'b, S> {
+    delegate: S,
+    chain: &'a Chain<'a>,
+    key: Option<String>,
+    track: &'b Track,
+}
+
+struct TrackedStruct<'a, 'b, S> {
+    delegate: S,
+    chain: &'a Chain<'a>,
+    track: &'b Track,
+}
+
+// Used for tuple variants and struct variants.
+struct TrackedVariant<'a, 'b, S> {
+    delegate: S,
+    chain: &'a Chain<'a>,
+    variant: &'static str,
+    index: usize,
+    track: &'b Track,
+}
+
+macro_rules! forward {
+    ($($method:ident($($arg:ident: $ty:ty),*);)*) => {
+        $(
+            fn $method(self $(, $arg: $ty)*) -> Result<S::Ok, S::Error> {
+                let chain = self.chain;
+                let track = self.track;
+                self.delegate
+                    .$method($($arg),*)
+                    .map_err(|err| track.trigger(chain, err))
+            }
+        )*
+    };
+}
+
+impl<'a, 'b, S> serde::Serializer for TrackedSerializer<'a, 'b, S>
+where
+    S: serde::Serializer,
+{
+    type Ok = S::Ok;
+    type Error = S::Error;
+    type SerializeSeq = TrackedSeq<'a, 'b, S::SerializeSeq>;
+    type SerializeTuple = TrackedSeq<'a, 'b, S::SerializeTuple>;
+    type SerializeTupleStruct = TrackedSeq<'a, 'b, S::SerializeTupleStruct>;
+    type SerializeTupleVariant = TrackedVariant<'a, 'b, S::SerializeTupleVariant>;
+    type SerializeMap = TrackedMap<'a, 'b, S::SerializeMap>;
+    type SerializeStruct = TrackedStruct<'a, 'b, S::SerializeStruct>;
+    type SerializeStructVariant = TrackedVariant<'a, 'b, S::SerializeStructVariant>;
+
+    forward! {
+        serialize_bool(v: bool);
+        serialize_i8(v: i8);
+        serialize_i16(v: i16);
+        serialize_i32(v: i32);
+        serialize_i64(v: i64);
+        serialize_i128(v: i128);
+        serialize_u8(v: u8);
+        serialize_u16(v: u16);
+        serialize_u32(v: u32);
+        serialize_u64(v: u64);
+        serialize_u128(v: u128);
+        serialize_f32(v: f32);
+        serialize_f64(v: f64);
+        serialize_char(v: char);
+        serialize_str(v: &str);
+        serialize_bytes(v: &[u8]);
+        serialize_none();
+        serialize_unit();
+        serialize_unit_struct(name: &'static str);
+        serialize_unit_variant(name: &'static str, variant_index: u32, variant: &'static str);
+    }
+
+    fn serialize_some<T>(self, value: &T) -> Result<S::Ok, S::Error>
+    where
+        T: ?Sized + serde::Serialize,
+    {
+        let chain = self.chain;
+        let track = self.track;
+        let tracked = TrackedValue {
+            value,
+            chain,
+            track,
+        };
+        self.delegate
+            .serialize_some(&tracked)
+            .map_err(|err| track.trigger(chain, err))
+    }
+
+    fn serialize_newtype_struct<T>(self, name: &'static str, value: &T) -> Result<S::Ok, S::Error>
+    where
+        T: ?Sized + serde::Serialize,
+    {
+        let chain = self.chain;
+        let track = self.track;
+        let tracked = TrackedValue {
+            value,
+            chain,
+            track,
+        };
+        self.delegate
+            .serialize_newtype_struct(name, &tracked)
+            .map_err(|err| track.trigger(chain, err))
+    }
+
+    fn serialize_newtype_variant<T>(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<S::Ok, S::Error>
+    where
+        T: ?Sized + serde::Serialize,
+    {
+        let chain = Chain::Enum {
+            parent: self.chain,
+            variant,
+        };
+        let track = self.track;
+        let tracked = TrackedValue {
+            value,
+            chain: &chain,
+            track,
+        };
+        self.delegate
+            .serialize_newtype_variant(name, variant_index, variant, &tracked)
+            .map_err(|err| track.trigger(&chain, err))
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq, S::Error> {
+        let chain = self.chain;
+        let track = self.track;
+        match self.delegate.serialize_seq(len) {
+            Ok(delegate) => Ok(TrackedSeq {
+                delegate,
+                chain,
+                index: 0,
+                track,
+            }),
+            Err(err) => Err(track.trigger(chain, err)),
+        }
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple, S::Error> {
+        let chain = self.chain;
+        let track = self.track;
+        match self.delegate.serialize_tuple(len) {
+            Ok(delegate) => Ok(TrackedSeq {
+                delegate,
+                chain,
+                index: 0,
+                track,
+            }),
+            Err(err) => Err(track.trigger(chain, err)),
+        }
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleStruct, S::Error> {
+        let chain = self.chain;
+        let track = self.track;
+        match self.delegate.serialize_tuple_struct(name, len) {
+            Ok(delegate) => Ok(TrackedSeq {
+                delegate,
+                chain,
+                index: 0,
+                track,
+            }),
+            Err(err) => Err(track.trigger(chain, err)),
+        }
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleVariant, S::Error> {
+        let chain = self.chain;
+        let track = self.track;
+        match self
+            .delegate
+            .serialize_tuple_variant(name, variant_index, variant, len)
+        {
+            Ok(delegate) => Ok(TrackedVariant {
+                delegate,
+                chain,
+                variant,
+                index: 0,
+                track,
+            }),
+            Err(err) => Err(track.trigger(chain, err)),
+        }
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<Self::SerializeMap, S::Error> {
+        let chain = self.chain;
+        let track = self.track;
+        match self.delegate.serialize_map(len) {
+            Ok(delegate) => Ok(TrackedMap {
+                delegate,
+                chain,
+                key: None,
+                track,
+            }),
+            Err(err) => Err(track.trigger(chain, err)),
+        }
+    }
+
+    fn serialize_struct(
+        self,
+        name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStruct, S::Error> {
+        let chain = self.chain;
+        let track = self.track;
+        match self.delegate.serialize_struct(name, len) {
+            Ok(delegate) => Ok(TrackedStruct {
+                delegate,
+                chain,
+                track,
+            }),
+            Err(err) => Err(track.trigger(chain, err)),
+        }
+    }
+
+    fn serialize_struct_variant(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStructVariant, S::Error> {
+        let chain = self.chain;
+        let track = self.track;
+        match self
+            .delegate
+            .serialize_struct_variant(name, variant_index, variant, len)
+        {
+            Ok(delegate) => Ok(TrackedVariant {
+                delegate,
+                chain,
+                variant,
+                index: 0,
+                track,
+            }),
+            Err(err) => Err(track.trigger(chain, err)),
+        }
+    }
+
+    fn collect_str<T>(self, value: &T) -> Result<S::Ok, S::Error>
+    where
+        T: ?Sized + Display,
+    {
+        let chain = self.chain;
+        let track = self.track;
+        self.delegate
+            .collect_str(value)
+            .map_err(|err| track.trigger(chain, err))
+    }
+
+    fn is_human_readable(&self) -> bool {
+        self.delegate.is_human_readable()
+    }
+}
+
+impl<S> TrackedSeq<'_, '_, S> {
+    fn element<T, E>(
+        &mut self,
+        value: &T,
+        serialize: impl FnOnce(&mut S, &TrackedValue<T>) -> Result<(), E>,
+    ) -> Result<(), E>
+    where
+        T: ?Sized,
+    {
+        let chain = Chain::Seq {
+            parent: self.chain,
+            index: self.index,
+        };
+        self.index += 1;
+        let tracked = TrackedValue {
+            value,
+            chain: &chain,
+            track: self.track,
+        };
+        serialize(&mut self.delegate, &tracked).map_err(|err| self.track.trigger(&chain, err))
+    }
+}
+
+impl<S> serde::ser::SerializeSeq for TrackedSeq<'_, '_, S>
+where
+    S: serde::ser::SerializeSeq,
+{
+    type Ok = S::Ok;
+    type Error = S::Error;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<(), S::Error>
+    where
+        T: ?Sized + serde::Serialize,
+    {
+        self.element(value, |delegate, value| delegate.serialize_element(value))
+    }
+
+    fn end(self) -> Result<S::Ok, S::Error> {
+        let chain = self.chain;
+        let track = self.track;
+        self.delegate.end().map_err(|err| track.trigger(chain, err))
+    }
+}
+
+impl<S> serde::ser::SerializeTuple for TrackedSeq<'_, '_, S>
+where
+    S: serde::ser::SerializeTuple,
+{
+    type Ok = S::Ok;
+    type Error = S::Error;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<(), S::Error>
+    where
+        T: ?Sized + serde::Serialize,
+    {
+        self.element(value, |delegate, value| delegate.serialize_element(value))
+    }
+
+    fn end(self) -> Result<S::Ok, S::Error> {
+        let chain = self.chain;
+        let track = self.track;
+        self.delegate.end().map_err(|err| track.trigger(chain, err))
+    }
+}
+
+impl<S> serde::ser::SerializeTupleStruct for TrackedSeq<'_, '_, S>
+where
+    S: serde::ser::SerializeTupleStruct,
+{
+    type Ok = S::Ok;
+    type Error = S::Error;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<(), S::Error>
+    where
+        T: ?Sized + serde::Serialize,
+    {
+        self.element(value, |delegate, value| delegate.serialize_field(value))
+    }
+
+    fn end(self) -> Result<S::Ok, S::Error> {
+        let chain = self.chain;
+        let track = self.track;
+        self.delegate.end().map_err(|err| track.trigger(chain, err))
+    }
+}
+
+impl<S> serde::ser::SerializeMap for TrackedMap<'_, '_, S>
+where
+    S: serde::ser::SerializeMap,
+{
+    type Ok = S::Ok;
+    type Error = S::Error;
+
+    fn serialize_key<T>(&mut self, key: &T) -> Result<(), S::Error>
+    where
+        T: ?Sized + serde::Serialize,
+    {
+        self.key = key.serialize(MapKeyCapture).ok();
+        let chain = match &self.key {
+            Some(key) => Chain::Map {
+                parent: self.chain,
+                key,
+            },
+            None => Chain::Unknown { parent: self.chain },
+        };
+        let tracked = TrackedValue {
+            value: key,
+            chain: &chain,
+            track: self.track,
+        };
+        self.delegate
+            .serialize_key(&tracked)
+            .map_err(|err| self.track.trigger(&chain, err))
+    }
+
+    fn serialize_value<T>(&mut self, value: &T) -> Result<(), S::Error>
+    where
+        T: ?Sized + serde::Serialize,
+    {
+        let key = self.key.take();
+        let chain = match &key {
+            Some(key) => Chain::Map {
+                parent: self.chain,
+                key,
+            },
+            None => Chain::Unknown { parent: self.chain },
+        };
+        let tracked = TrackedValue {
+            value,
+            chain: &chain,
+            track: self.track,
+        };
+        self.delegate
+            .serialize_value(&tracked)
+            .map_err(|err| self.track.trigger(&chain, err))
+    }
+
+    fn serialize_entry<K, V>(&mut self, key: &K, value: &V) -> Result<(), S::Error>
+    where
+        K: ?Sized + serde::Serialize,
+        V: ?Sized + serde::Serialize,
+    {
+        let captured = key.serialize(MapKeyCapture).ok();
+        let chain = match &captured {
+            Some(key) => Chain::Map {
+                parent: self.chain,
+                key,
+            },
+            None => Chain::Unknown { parent: self.chain },
+        };
+        let key = TrackedValue {
+            value: key,
+            chain: &chain,
+            track: self.track,
+        };
+        let value = TrackedValue {
+            value,
+            chain: &chain,
+            track: self.track,
+        };
+        self.delegate
+            .serialize_entry(&key, &value)
+            .map_err(|err| self.track.trigger(&chain, err))
+    }
+
+    fn end(self) -> Result<S::Ok, S::Error> {
+        let chain = self.chain;
+        let track = self.track;
+        self.delegate.end().map_err(|err| track.trigger(chain, err))
+    }
+}
+
+impl<S> serde::ser::SerializeStruct for TrackedStruct<'_, '_, S>
+where
+    S: serde::ser::SerializeStruct,
+{
+    type Ok = S::Ok;
+    type Error = S::Error;
+
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), S::Error>
+    where
+        T: ?Sized + serde::Serialize,
+    {
+        let chain = Chain::Map {
+            parent: self.chain,
+            key,
+        };
+        let tracked = TrackedValue {
+            value,
+            chain: &chain,
+            track: self.track,
+        };
+        self.delegate
+            .serialize_field(key, &tracked)
+            .map_err(|err| self.track.trigger(&chain, err))
+    }
+
+    fn skip_field(&mut self, key: &'static str) -> Result<(), S::Error> {
+        let chain = self.chain;
+        let track = self.track;
+        self.delegate
+            .skip_field(key)
+            .map_err(|err| track.trigger(chain, err))
+    }
+
+    fn end(self) -> Result<S::Ok, S::Error> {
+        let chain = self.chain;
+        let track = self.track;
+        self.delegate.end().map_err(|err| track.trigger(chain, err))
+    }
+}
+
+impl<S> serde::ser::SerializeTupleVariant for TrackedVariant<'_, '_, S>
+where
+    S: serde::ser::SerializeTupleVariant,
+{
+    type Ok = S::Ok;
+    type Error = S::Error;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<(), S::Error>
+    where
+        T: ?Sized + serde::Serialize,
+    {
+        let variant = Chain::Enum {
+            parent: self.chain,
+            variant: self.variant,
+        };
+        let chain = Chain::Seq {
+            parent: &variant,
+            index: self.index,
+        };
+        self.index += 1;
+        let tracked = TrackedValue {
+            value,
+            chain: &chain,
+            track: self.track,
+        };
+        self.delegate
+            .serialize_field(&tracked)
+            .map_err(|err| self.track.trigger(&chain, err))
+    }
+
+    fn end(self) -> Result<S::Ok, S::Error> {
+        let chain = self.chain;
+        let track = self.track;
+        self.delegate.end().map_err(|err| track.trigger(chain, err))
+    }
+}
+
+impl<S> serde::ser::SerializeStructVariant for TrackedVariant<'_, '_, S>
+where
+    S: serde::ser::SerializeStructVariant,
+{
+    type Ok = S::Ok;
+    type Error = S::Error;
+
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), S::Error>
+    where
+        T: ?Sized + serde::Serialize,
+    {
+        let variant = Chain::Enum {
+            parent: self.chain,
+            variant: self.variant,
+        };
+        let chain = Chain::Map {
+            parent: &variant,
+            key,
+        };
+        let tracked = TrackedValue {
+            value,
+            chain: &chain,
+            track: self.track,
+        };
+        self.delegate
+            .serialize_field(key, &tracked)
+            .map_err(|err| self.track.trigger(&chain, err))
+    }
+
+    fn skip_field(&mut self, key: &'static str) -> Result<(), S::Error> {
+        let chain = self.chain;
+        let track = self.track;
+        self.delegate
+            .skip_field(key)
+            .map_err(|err| track.trigger(chain, err))
+    }
+
+    fn end(self) -> Result<S::Ok, S::Error> {
+        let chain = self.chain;
+        let track = self.track;
+        self.delegate.end().map_err(|err| track.trigger(chain, err))
+    }
+}
+
+// DESERIALIZER ////////////////////////////////////////////////////////////////
+
+/// Wrapper around a `Deserializer` trait object which records where in the
+/// input an error occurred.
+///
+/// Errors from deserializing through this wrapper come with a [`Path`]
+/// attached, available as structured segments from [`Error::path`] and
+/// included in the error's `Display` output.
+///
+/// ```rust
+/// use erased_serde::{Deserializer, PathDeserializer, Segment};
+/// use serde_derive::Deserialize;
+///
+/// #[derive(Deserialize, Debug)]
+/// struct Config {
+///     servers: Vec<Server>,
+/// }
+///
+/// #[derive(Deserialize, Debug)]
+/// struct Server {
+///     tls: Option<Tls>,
+/// }
+///
+/// #[derive(Deserialize, Debug)]
+/// struct Tls {
+///     cert_path: String,
+/// }
+///
+/// let input = br#"{"servers": [{"tls": null}, {"tls": {"cert_path": 443}}]}"#;
+/// let json = &mut serde_json::Deserializer::from_slice(input);
+/// let format = &mut <dyn Deserializer>::erase(json);
+/// let tracked = &mut PathDeserializer::new(format);
+///
+/// let error = erased_serde::deserialize::<Config>(tracked).unwrap_err();
+/// assert_eq!(
+///     error.to_string(),
+///     "servers[1].tls.cert_path: invalid type: integer `443`, expected a string at line 1 column 53",
+/// );
+/// assert_eq!(error.path().unwrap().segments()[1], Segment::Seq { index: 1 });
+/// ```
+pub struct PathDeserializer<'a, 'de> {
+    delegate: &'a mut dyn Deserializer<'de>,
+}
+
+impl<'a, 'de> PathDeserializer<'a, 'de> {
+    /// Wrap the given deserializer so that its errors report their location.
+    pub fn new(deserializer: &'a mut dyn Deserializer<'de>) -> Self {
+        PathDeserializer {
+            delegate: deserializer,
+        }
+    }
+
+    fn track(
+        &mut self,
+        visitor: &mut dyn Visitor<'de>,
+        deserialize: impl FnOnce(
+            TrackedDeserializer<&mut (dyn Deserializer<'de> + 'a)>,
+            &mut dyn Visitor<'de>,
+        ) -> Result<Out, Error>,
+    ) -> Result<Out, Error> {
+        let track = Track::new();
+        let tracked = TrackedDeserializer {
+            delegate: &mut *self.delegate,
+            chain: &Chain::Root,
+            track: &track,
+        };
+        deserialize(tracked, &mut *visitor).map_err(|err| {
+            let error = with_cause(err, visitor.erased_take_error());
+            with_path(error, track.into_path())
+        })
+    }
+}
+
+macro_rules! erased_deserialize {
+    ($($erased:ident => $method:ident($($arg:ident: $ty:ty),*);)*) => {
+        $(
+            fn $erased(&mut self, $($arg: $ty,)* visitor: &mut dyn Visitor<'de>) -> Result<Out, Error> {
+                self.track(visitor, |deserializer, visitor| {
+                    serde::Deserializer::$method(deserializer, $($arg,)* visitor)
+                })
+            }
+        )*
+    };
+}
+
+impl<'de> Deserializer<'de> for PathDeserializer<'_, 'de> {
+    erased_deserialize! {
+        erased_deserialize_any => deserialize_any();
+        erased_deserialize_bool => deserialize_bool();
+        erased_deserialize_i8 => deserialize_i8();
+        erased_deserialize_i16 => deserialize_i16();
+        erased_deserialize_i32 => deserialize_i32();
+        erased_deserialize_i64 => deserialize_i64();
+        erased_deserialize_i128 => deserialize_i128();
+        erased_deserialize_u8 => deserialize_u8();
+        erased_deserialize_u16 => deserialize_u16();
+        erased_deserialize_u32 => deserialize_u32();
+        erased_deserialize_u64 => deserialize_u64();
+        erased_deserialize_u128 => deserialize_u128();
+        erased_deserialize_f32 => deserialize_f32();
+        erased_deserialize_f64 => deserialize_f64();
+        erased_deserialize_char => deserialize_char();
+        erased_deserialize_str => deserialize_str();
+        erased_deserialize_string => deserialize_string();
+        erased_deserialize_bytes => deserialize_bytes();
+        erased_deserialize_byte_buf => deserialize_byte_buf();
+        erased_deserialize_option => deserialize_option();
+        erased_deserialize_unit => deserialize_unit();
+        erased_deserialize_unit_struct => deserialize_unit_struct(name: &'static str);
+        erased_deserialize_newtype_struct => deserialize_newtype_struct(name: &'static str);
+        erased_deserialize_seq => deserialize_seq();
+        erased_deserialize_tuple => deserialize_tuple(len: usize);
+        erased_deserialize_tuple_struct => deserialize_tuple_struct(name: &'static str, len: usize);
+        erased_deserialize_map => deserialize_map();
+        erased_deserialize_struct => deserialize_struct(name: &'static str, fields: &'static [&'static str]);
+        erased_deserialize_identifier => deserialize_identifier();
+        erased_deserialize_enum => deserialize_enum(name: &'static str, variants: &'static [&'static str]);
+        erased_deserialize_ignored_any => deserialize_ignored_any();
+    }
+
+    fn erased_is_human_readable(&self) -> bool {
+        self.delegate.erased_is_human_readable()
+    }
+
+    fn erased_as_deserializer(&mut self) -> &mut dyn Deserializer<'de> {
+        self
+    }
+}
+
+impl Sealed for PathDeserializer<'_, '_> {}
+
+struct TrackedDeserializer<'a, 'b, D> {
+    delegate: D,
+    chain: &'a Chain<'a>,
+    track: &'b Track,
+}
+
+macro_rules! tracked_deserialize {
+    ($($method:ident($($arg:ident: $ty:ty),*);)*) => {
+        $(
+            fn $method<V>(self, $($arg: $ty,)* visitor: V) -> Result<V::Value, D::Error>
+            where
+                V: serde::de::Visitor<'de>,
+            {
+                let chain = self.chain;
+                let track = self.track;
+                let visitor = TrackedVisitor {
+                    delegate: visitor,
+                    chain,
+                    track,
+                };
+                self.delegate
+                    .$method($($arg,)* visitor)
+                    .map_err(|err| track.trigger(chain, err))
+            }
+        )*
+    };
+}
+
+impl<'de, D> serde::Deserializer<'de> for TrackedDeserializer<'_, '_, D>
+where
+    D: serde::Deserializer<'de>,
+{
+    type Error = D::Error;
+
+    tracked_deserialize! {
+        deserialize_any();
+        deserialize_bool();
+        deserialize_i8();
+        deserialize_i16();
+        deserialize_i32();
+        deserialize_i64();
+        deserialize_i128();
+        deserialize_u8();
+        deserialize_u16();
+        deserialize_u32();
+        deserialize_u64();
+        deserialize_u128();
+        deserialize_f32();
+        deserialize_f64();
+        deserialize_char();
+        deserialize_str();
+        deserialize_string();
+        deserialize_bytes();
+        deserialize_byte_buf();
+        deserialize_option();
+        deserialize_unit();
+        deserialize_unit_struct(name: &'static str);
+        deserialize_newtype_struct(name: &'static str);
+        deserialize_seq();
+        deserialize_tuple(len: usize);
+        deserialize_tuple_struct(name: &'static str, len: usize);
+        deserialize_map();
+        deserialize_struct(name: &'static str, fields: &'static [&'static str]);
+        deserialize_identifier();
+        deserialize_enum(name: &'static str, variants: &'static [&'static str]);
+        deserialize_ignored_any();
+    }
+
+    fn is_human_readable(&self) -> bool {
+        self.delegate.is_human_readable()
+    }
+}
+
+struct TrackedSeed<'a, 'b, X> {
+    delegate: X,
+    chain: &'a Chain<'a>,
+    track: &'b Track,
+}
+
+impl<'de, X> serde::de::DeserializeSeed<'de> for TrackedSeed<'_, '_, X>
+where
+    X: serde::de::DeserializeSeed<'de>,
+{
+    type Value = X::Value;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<X::Value, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        self.delegate.deserialize(TrackedDeserializer {
+            delegate: deserializer,
+            chain: self.chain,
+            track: self.track,
+        })
+    }
+}
+
+struct TrackedVisitor<'a, 'b, X> {
+    delegate: X,
+    chain: &'a Chain<'a>,
+    track: &'b Track,
+}
+
+macro_rules! tracked_visit {
+    ($($method:ident($ty:ty);)*) => {
+        $(
+            fn $method<E>(self, v: $ty) -> Result<X::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                let chain = self.chain;
+                let track = self.track;
+                self.delegate
+                    .$method(v)
+                    .map_err(|err| track.trigger(chain, err))
+            }
+        )*
+    };
+}
+
+impl<'de, X> serde::de::Visitor<'de> for TrackedVisitor<'_, '_, X>
+where
+    X: serde::de::Visitor<'de>,
+{
+    type Value = X::Value;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        self.delegate.expecting(formatter)
+    }
+
+    tracked_visit! {
+        visit_bool(bool);
+        visit_i8(i8);
+        visit_i16(i16);
+        visit_i32(i32);
+        visit_i64(i64);
+        visit_i128(i128);
+        visit_u8(u8);
+        visit_u16(u16);
+        visit_u32(u32);
+        visit_u64(u64);
+        visit_u128(u128);
+        visit_f32(f32);
+        visit_f64(f64);
+        visit_char(char);
+        visit_str(&str);
+        visit_borrowed_str(&'de str);
+        visit_string(String);
+        visit_bytes(&[u8]);
+        visit_borrowed_bytes(&'de [u8]);
+        visit_byte_buf(Vec<u8>);
+    }
+
+    fn visit_none<E>(self) -> Result<X::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        let chain = self.chain;
+        let track = self.track;
+        self.delegate
+            .visit_none()
+            .map_err(|err| track.trigger(chain, err))
+    }
+
+    fn visit_some<D>(self, deserializer: D) -> Result<X::Value, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let chain = self.chain;
+        let track = self.track;
+        let deserializer = TrackedDeserializer {
+            delegate: deserializer,
+            chain,
+            track,
+        };
+        self.delegate
+            .visit_some(deserializer)
+            .map_err(|err| track.trigger(chain, err))
+    }
+
+    fn visit_unit<E>(self) -> Result<X::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        let chain = self.chain;
+        let track = self.track;
+        self.delegate
+            .visit_unit()
+            .map_err(|err| track.trigger(chain, err))
+    }
+
+    fn visit_newtype_struct<D>(self, deserializer: D) -> Result<X::Value, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let chain = self.chain;
+        let track = self.track;
+        let deserializer = TrackedDeserializer {
+            delegate: deserializer,
+            chain,
+            track,
+        };
+        self.delegate
+            .visit_newtype_struct(deserializer)
+            .map_err(|err| track.trigger(chain, err))
+    }
+
+    fn visit_seq<A>(self, seq: A) -> Result<X::Value, A::Error>
+    where
+        A: serde::de::SeqAccess<'de>,
+    {
+        let chain = self.chain;
+        let track = self.track;
+        let seq = TrackedSeqAccess {
+            delegate: seq,
+            chain,
+            index: 0,
+            track,
+        };
+        self.delegate
+            .visit_seq(seq)
+            .map_err(|err| track.trigger(chain, err))
+    }
+
+    fn visit_map<A>(self, map: A) -> Result<X::Value, A::Error>
+    where
+        A: serde::de::MapAccess<'de>,
+    {
+        let chain = self.chain;
+        let track = self.track;
+        let map = TrackedMapAccess {
+            delegate: map,
+            chain,
+            key: None,
+            track,
+        };
+        self.delegate
+            .visit_map(map)
+            .map_err(|err| track.trigger(chain, err))
+    }
+
+    fn visit_enum<A>(self, data: A) -> Result<X::Value, A::Error>
+    where
+        A: serde::de::EnumAccess<'de>,
+    {
+        let chain = self.chain;
+        let track = self.track;
+        let data = TrackedEnumAccess {
+            delegate: data,
+            chain,
+            track,
+        };
+        self.delegate
+            .visit_enum(data)
+            .map_err(|err| track.trigger(chain, err))
+    }
+}
+
+struct TrackedSeqAccess<'a, 'b, A> {
+    delegate: A,
+    chain: &'a Chain<'a>,
+    index: usize,
+    track: &'b Track,
+}
+
+impl<'de, A> serde::de::SeqAccess<'de> for TrackedSeqAccess<'_, '_, A>
+where
+    A: serde::de::SeqAccess<'de>,
+{
+    type Error = A::Error;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, A::Error>
+    where
+        T: serde::de::DeserializeSeed<'de>,
+    {
+        let chain = Chain::Seq {
+            parent: self.chain,
+            index: self.index,
+        };
+        self.index += 1;
+        let seed = TrackedSeed {
+            delegate: seed,
+            chain: &chain,
+            track: self.track,
+        };
+        self.delegate
+            .next_element_seed(seed)
+            .map_err(|err| self.track.trigger(&chain, err))
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        self.delegate.size_hint()
+    }
+}
+
+struct TrackedMapAccess<'a, 'b, A> {
+    delegate: A,
+    chain: &'a Chain<'a>,
+    key: Option<String>,
+    track: &'b Track,
+}
+
+impl<'de, A> serde::de::MapAccess<'de> for TrackedMapAccess<'_, '_, A>
+where
+    A: serde::de::MapAccess<'de>,
+{
+    type Error = A::Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, A::Error>
+    where
+        K: serde::de::DeserializeSeed<'de>,
+    {
+        let seed = CaptureKey {
+            delegate: seed,
+            key: &mut self.key,
+        };
+        self.delegate
+            .next_key_seed(seed)
+            .map_err(|err| self.track.trigger(self.chain, err))
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, A::Error>
+    where
+        V: serde::de::DeserializeSeed<'de>,
+    {
+        let key = self.key.take();
+        let chain = match &key {
+            Some(key) => Chain::Map {
+                parent: self.chain,
+                key,
+            },
+            None => Chain::Unknown { parent: self.chain },
+        };
+        let seed = TrackedSeed {
+            delegate: seed,
+            chain: &chain,
+            track: self.track,
+        };
+        self.delegate
+            .next_value_seed(seed)
+            .map_err(|err| self.track.trigger(&chain, err))
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        self.delegate.size_hint()
+    }
+}
+
+struct TrackedEnumAccess<'a, 'b, A> {
+    delegate: A,
+    chain: &'a Chain<'a>,
+    track: &'b Track,
+}
+
+impl<'a, 'b, 'de, A> serde::de::EnumAccess<'de> for TrackedEnumAccess<'a, 'b, A>
+where
+    A: serde::de::EnumAccess<'de>,
+{
+    type Error = A::Error;
+    type Variant = TrackedVariantAccess<'a, 'b, A::Variant>;
+
+    fn variant_seed<V>(self, seed: V) -> Result<(V::Value, Self::Variant), A::Error>
+    where
+        V: serde::de::DeserializeSeed<'de>,
+    {
+        let chain = self.chain;
+        let track = self.track;
+        let mut variant = None;
+        let seed = CaptureKey {
+            delegate: seed,
+            key: &mut variant,
+        };
+        match self.delegate.variant_seed(seed) {
+            Ok((value, delegate)) => Ok((
+                value,
+                TrackedVariantAccess {
+                    delegate,
+                    chain,
+                    variant,
+                    track,
+                },
+            )),
+            Err(err) => Err(track.trigger(chain, err)),
+        }
+    }
+}
+
+struct TrackedVariantAccess<'a, 'b, A> {
+    delegate: A,
+    chain: &'a Chain<'a>,
+    variant: Option<String>,
+    track: &'b Track,
+}
+
+impl<'de, A> serde::de::VariantAccess<'de> for TrackedVariantAccess<'_, '_, A>
+where
+    A: serde::de::VariantAccess<'de>,
+{
+    type Error = A::Error;
+
+    fn unit_variant(self) -> Result<(), A::Error> {
+        let chain = self.chain;
+        let track = self.track;
+        self.delegate
+            .unit_variant()
+            .map_err(|err| track.trigger(chain, err))
+    }
+
+    fn newtype_variant_seed<T>(self, seed: T) -> Result<T::Value, A::Error>
+    where
+        T: serde::de::DeserializeSeed<'de>,
+    {
+        let chain = variant_chain(self.chain, self.variant.as_ref());
+        let seed = TrackedSeed {
+            delegate: seed,
+            chain: &chain,
+            track: self.track,
+        };
+        self.delegate
+            .newtype_variant_seed(seed)
+            .map_err(|err| self.track.trigger(&chain, err))
+    }
+
+    fn tuple_variant<V>(self, len: usize, visitor: V) -> Result<V::Value, A::Error>
+    where
+        V: serde::de::Visitor<'de>,
+    {
+        let chain = variant_chain(self.chain, self.variant.as_ref());
+        let visitor = TrackedVisitor {
+            delegate: visitor,
+            chain: &chain,
+            track: self.track,
+        };
+        self.delegate
+            .tuple_variant(len, visitor)
+            .map_err(|err| self.track.trigger(&chain, err))
+    }
+
+    fn struct_variant<V>(
+        self,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, A::Error>
+    where
+        V: serde::de::Visitor<'de>,
+    {
+        let chain = variant_chain(self.chain, self.variant.as_ref());
+        let visitor = TrackedVisitor {
+            delegate: visitor,
+            chain: &chain,
+            track: self.track,
+        };
+        self.delegate
+            .struct_variant(fields, visitor)
+            .map_err(|err| self.track.trigger(&chain, err))
+    }
+}
+
+fn variant_chain<'a>(parent: &'a Chain<'a>, variant: Option<&'a String>) -> Chain<'a> {
+    match variant {
+        Some(variant) => Chain::Enum { parent, variant },
+        None => Chain::Unknown { parent },
+    }
+}
+
+// Records the string form of a map key or variant name on its way to the
+// delegate's visitor.
+struct CaptureKey<'a, X> {
+    delegate: X,
+    key: &'a mut Option<String>,
+}
+
+impl<'de, X> serde::de::DeserializeSeed<'de> for CaptureKey<'_, X>
+where
+    X: serde::de::DeserializeSeed<'de>,
+{
+    type Value = X::Value;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<X::Value, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        self.delegate.deserialize(CaptureKey {
+            delegate: deserializer,
+            key: self.key,
+        })
+    }
+}
+
+macro_rules! capture_deserialize {
+    ($($method:ident($($arg:ident: $ty:ty),*);)*) => {
+        $(
+            fn $method<V>(self, $($arg: $ty,)* visitor: V) -> Result<V::Value, D::Error>
+            where
+                V: serde::de::Visitor<'de>,
+            {
+                let visitor = CaptureKey {
+                    delegate: visitor,
+                    key: self.key,
+                };
+                self.delegate.$method($($arg,)* visitor)
+            }
+        )*
+    };
+}
+
+impl<'de, D> serde::Deserializer<'de> for CaptureKey<'_, D>
+where
+    D: serde::Deserializer<'de>,
+{
+    type Error = D::Error;
+
+    capture_deserialize! {
+        deserialize_any();
+        deserialize_bool();
+        deserialize_i8();
+        deserialize_i16();
+        deserialize_i32();
+        deserialize_i64();
+        deserialize_i128();
+        deserialize_u8();
+        deserialize_u16();
+        deserialize_u32();
+        deserialize_u64();
+        deserialize_u128();
+        deserialize_f32();
+        deserialize_f64();
+        deserialize_char();
+        deserialize_str();
+        deserialize_string();
+        deserialize_bytes();
+        deserialize_byte_buf();
+        deserialize_option();
+        deserialize_unit();
+        deserialize_unit_struct(name: &'static str);
+        deserialize_newtype_struct(name: &'static str);
+        deserialize_seq();
+        deserialize_tuple(len: usize);
+        deserialize_tuple_struct(name: &'static str, len: usize);
+        deserialize_map();
+        deserialize_struct(name: &'static str, fields: &'static [&'static str]);
+        deserialize_identifier();
+        deserialize_enum(name: &'static str, variants: &'static [&'static str]);
+        deserialize_ignored_any();
+    }
+
+    fn is_human_readable(&self) -> bool {
+        self.delegate.is_human_readable()
+    }
+}
+
+macro_rules! capture_visit {
+    ($($method:ident($ty:ty);)*) => {
+        $(
+            fn $method<E>(self, v: $ty) -> Result<X::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                *self.key = Some(v.to_string());
+                self.delegate.$method(v)
+            }
+        )*
+    };
+}
+
+macro_rules! forward_visit {
+    ($($method:ident($ty:ty);)*) => {
+        $(
+            fn $method<E>(self, v: $ty) -> Result<X::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                self.delegate.$method(v)
+            }
+        )*
+    };
+}
+
+impl<'de, X> serde::de::Visitor<'de> for CaptureKey<'_, X>
+where
+    X: serde::de::Visitor<'de>,
+{
+    type Value = X::Value;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        self.delegate.expecting(formatter)
+    }
+
+    capture_visit! {
+        visit_bool(bool);
+        visit_i8(i8);
+        visit_i16(i16);
+        visit_i32(i32);
+        visit_i64(i64);
+        visit_i128(i128);
+        visit_u8(u8);
+        visit_u16(u16);
+        visit_u32(u32);
+        visit_u64(u64);
+        visit_u128(u128);
+        visit_f32(f32);
+        visit_f64(f64);
+        visit_char(char);
+        visit_str(&str);
+        visit_borrowed_str(&'de str);
+    }
+
+    forward_visit! {
+        visit_bytes(&[u8]);
+        visit_borrowed_bytes(&'de [u8]);
+        visit_byte_buf(Vec<u8>);
+    }
+
+    fn visit_string<E>(self, v: String) -> Result<X::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        *self.key = Some(v.clone());
+        self.delegate.visit_string(v)
+    }
+
+    fn visit_none<E>(self) -> Result<X::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        self.delegate.visit_none()
+    }
+
+    fn visit_some<D>(self, deserializer: D) -> Result<X::Value, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        self.delegate.visit_some(deserializer)
+    }
+
+    fn visit_unit<E>(self) -> Result<X::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        self.delegate.visit_unit()
+    }
+
+    fn visit_newtype_struct<D>(self, deserializer: D) -> Result<X::Value, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        self.delegate.visit_newtype_struct(deserializer)
+    }
+
+    fn visit_seq<A>(self, seq: A) -> Result<X::Value, A::Error>
+    where
+        A: serde::de::SeqAccess<'de>,
+    {
+        self.delegate.visit_seq(seq)
+    }
+
+    fn visit_map<A>(self, map: A) -> Result<X::Value, A::Error>
+    where
+        A: serde::de::MapAccess<'de>,
+    {
+        self.delegate.visit_map(map)
+    }
+
+    fn visit_enum<A>(self, data: A) -> Result<X::Value, A::Error>
+    where
+        A: serde::de::EnumAccess<'de>,
+    {
+        self.delegate.visit_enum(data)
+    }
+}
+
+// MAP KEYS ////////////////////////////////////////////////////////////////////
+
+// Renders a map key for use in a Path, for keys that have a natural textual
+// form.
+struct MapKeyCapture;
+
+#[derive(Debug)]
+struct NotText;
+
+impl Display for NotText {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("map key cannot be rendered as text")
+    }
+}
+
+impl serde::ser::StdError for NotText {}
+
+impl serde::ser::Error for NotText {
+    fn custom<T: Display>(_msg: T) -> Self {
+        NotText
+    }
+}
+
+macro_rules! capture_to_string {
+    ($($method:ident($ty:ty))*) => {
+        $(
+            fn $method(self, v: $ty) -> Result<String, NotText> {
+                Ok(v.to_string())
+            }
+        )*
+    };
+}
+
+impl serde::Serializer for MapKeyCapture {
+    type Ok = String;
+    type Error = NotText;
+    type SerializeSeq = Impossible<String, NotText>;
+    type SerializeTuple = Impossible<String, NotText>;
+    type SerializeTupleStruct = Impossible<String, NotText>;
+    type SerializeTupleVariant = Impossible<String, NotText>;
+    type SerializeMap = Impossible<String, NotText>;
+    type SerializeStruct = Impossible<String, NotText>;
+    type SerializeStructVariant = Impossible<String, NotText>;
+
+    capture_to_string! {
+        serialize_bool(bool)
+        serialize_i8(i8)
+        serialize_i16(i16)
+        serialize_i32(i32)
+        serialize_i64(i64)
+        serialize_i128(i128)
+        serialize_u8(u8)
+        serialize_u16(u16)
+        serialize_u32(u32)
+        serialize_u64(u64)
+        serialize_u128(u128)
+        serialize_f32(f32)
+        serialize_f64(f64)
+        serialize_char(char)
+        serialize_str(&str)
+    }
+
+    fn serialize_bytes(self, _v: &[u8]) -> Result<String, NotText> {
+        Err(NotText)
+    }
+
+    fn serialize_none(self) -> Result<String, NotText> {
+        Err(NotText)
+    }
+
+    fn serialize_some<T>(self, _value: &T) -> Result<String, NotText>
+    where
+        T: ?Sized + serde::Serialize,
+    {
+        Err(NotText)
+    }
+
+    fn serialize_unit(self) -> Result<String, NotText> {
+        Err(NotText)
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<String, NotText> {
+        Err(NotText)
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<String, NotText> {
+        Ok(variant.to_owned())
+    }
+
+    fn serialize_newtype_struct<T>(self, _name: &'static str, value: &T) -> Result<String, NotText>
+    where
+        T: ?Sized + serde::Serialize,
+    {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _value: &T,
+    ) -> Result<String, NotText>
+    where
+        T: ?Sized + serde::Serialize,
+    {
+        Err(NotText)
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq, NotText> {
+        Err(NotText)
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple, NotText> {
+        Err(NotText)
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleStruct, NotText> {
+        Err(NotText)
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant, NotText> {
+        Err(NotText)
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, NotText> {
+        Err(NotText)
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStruct, NotText> {
+        Err(NotText)
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant, NotText> {
+        Err(NotText)
+    }
+}
+
+// TEST ////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::collections::BTreeMap;
+    use alloc::vec;
+    use serde::ser::Error as _;
+    use serde_derive::{Deserialize, Serialize};
+
+    struct Fail;
+
+    impl serde::Serialize for Fail {
+        fn serialize<S>(&self, _serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: serde::Serializer,
+        {
+            Err(S::Error::custom("fail"))
+        }
+    }
+
+    fn path_of(value: &dyn Serialize) -> Error {
+        let mut json = serde_json::Serializer::new(Vec::new());
+        let mut erased = <dyn Serializer>::erase(&mut json);
+        serialize_with_path(value, &mut erased).unwrap_err()
+    }
+
+    #[test]
+    fn test_map_and_variant() {
+        #[derive(Serialize)]
+        enum Event {
+            Tuple(u8, Fail),
+            Struct { inner: Fail },
+        }
+
+        let mut map = BTreeMap::new();
+        map.insert(7, vec![Event::Tuple(0, Fail)]);
+        let err = path_of(&map);
+        assert_eq!(err.path().unwrap().to_string(), "7[0].Tuple[1]");
+        assert_eq!(
+            err.path().unwrap().segments(),
+            [
+                Segment::Map {
+                    key: "7".to_owned(),
+                },
+                Segment::Seq { index: 0 },
+                Segment::Enum {
+                    variant: "Tuple".to_owned(),
+                },
+                Segment::Seq { index: 1 },
+            ],
+        );
+
+        let err = path_of(&(1, Event::Struct { inner: Fail }));
+        assert_eq!(err.to_string(), "[1].Struct.inner: fail");
+    }
+
+    #[test]
+    fn test_format_error() {
+        let mut map = BTreeMap::new();
+        map.insert("a", BTreeMap::from([(vec![1], 1)]));
+        let err = path_of(&map);
+        assert_eq!(err.to_string(), "a.?: key must be a string");
+    }
+
+    #[test]
+    fn test_root() {
+        let err = path_of(&Fail);
+        assert!(err.path().unwrap().segments().is_empty());
+        assert_eq!(err.to_string(), "fail");
+    }
+
+    fn de_path_of<T>(json: &str) -> Error
+    where
+        T: serde::de::DeserializeOwned + core::fmt::Debug,
+    {
+        let mut json = serde_json::Deserializer::from_str(json);
+        let mut erased = <dyn Deserializer>::erase(&mut json);
+        let mut tracked = PathDeserializer::new(&mut erased);
+        crate::deserialize::<T>(&mut tracked).unwrap_err()
+    }
+
+    #[test]
+    fn test_de_nested() {
+        #[derive(Deserialize, Debug)]
+        #[allow(dead_code)]
+        struct Config {
+            servers: Vec<Server>,
+        }
+
+        #[derive(Deserialize, Debug)]
+        #[allow(dead_code)]
+        struct Server {
+            tls: Tls,
+        }
+
+        #[derive(Deserialize, Debug)]
+        #[allow(dead_code)]
+        struct Tls {
+            cert_path: String,
+        }
+
+        let json = r#"{"servers": [{"tls": {"cert_path": ""}}, {"tls": {"cert_path": false}}]}"#;
+        let err = de_path_of::<Config>(json);
+        assert_eq!(
+            err.path().unwrap().segments(),
+            [
+                Segment::Map {
+                    key: "servers".to_string(),
+                },
+                Segment::Seq { index: 1 },
+                Segment::Map {
+                    key: "tls".to_string(),
+                },
+                Segment::Map {
+                    key: "cert_path".to_string(),
+                },
+            ],
+        );
+        assert!(err
+            .to_string()
+            .starts_with("servers[1].tls.cert_path: invalid type: boolean `false`"));
+    }
+
+    #[test]
+    fn test_de_map_and_variant() {
+        #[derive(Deserialize, Debug)]
+        #[allow(dead_code)]
+        enum Event {
+            Tuple(u8, u8),
+            Struct { inner: u8 },
+        }
+
+        let err = de_path_of::<BTreeMap<u8, Event>>(r#"{"7": {"Tuple": [0, -1]}}"#);
+        assert_eq!(err.path().unwrap().to_string(), "7.Tuple[1]");
+
+        let err = de_path_of::<Vec<Event>>(r#"[{"Struct": {}}]"#);
+        assert_eq!(err.path().unwrap().to_string(), "[0].Struct");
+        assert_eq!(err.kind(), crate::ErrorKind::MissingField);
+    }
+
+    #[test]
+    fn test_de_root() {
+        let err = de_path_of::<u8>("true");
+        assert!(err.path().unwrap().segments().is_empty());
+        assert!(err.to_string().starts_with("invalid type: boolean `true`"));
+    }
+}
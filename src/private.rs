@@ -6,8 +6,23 @@ pub mod serde {
     pub use serde::*;
 }
 
+#[doc(hidden)]
+pub use alloc::boxed::Box;
 #[doc(hidden)]
 pub use core::marker::{Send, Sized, Sync};
+#[doc(hidden)]
+pub use core::option::Option;
+#[doc(hidden)]
+pub use core::stringify;
+
+#[doc(hidden)]
+pub use crate::forward::{deserialize_with, serialize_with};
+
+#[doc(hidden)]
+pub use crate::registry::deserialize as deserialize_registered;
+
+#[doc(hidden)]
+pub use crate::tag::{serialize_tagged, Tagging};
 
 #[doc(hidden)]
 pub type Result<T, E> = core::result::Result<T, E>;
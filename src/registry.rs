@@ -0,0 +1,408 @@
+use crate::de::Deserializer;
+use crate::error::{unerase_de_owned, Error};
+use alloc::boxed::Box;
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+use core::fmt;
+use core::str;
+use serde::de::{
+    DeserializeOwned, DeserializeSeed, EnumAccess, Unexpected, VariantAccess, Visitor,
+};
+
+/// Table from tag names to the concrete types that can be deserialized behind
+/// a trait object like `Box<dyn Event>`.
+///
+/// Every concrete type is registered explicitly, so the registry works the
+/// same on every target with no reliance on linker sections or life-before-main.
+/// The trait object type gets its `Deserialize` impls from
+/// [`deserialize_trait_object!`], which looks up the registry through a
+/// function returning `&'static Registry<dyn Event>`.
+///
+/// Values are represented in the format the same way as an externally tagged
+/// enum, `{"login": {"user": "alice"}}` in JSON, where the variant name is the
+/// tag the concrete type was registered under. Formats that identify enum
+/// variants by index, like bincode and postcard, see the position of the tag in
+/// registration order instead.
+///
+/// ```
+/// use erased_serde::{deserialize_trait_object, Registry};
+/// use serde_derive::Deserialize;
+/// use std::sync::OnceLock;
+///
+/// trait Event {
+///     fn describe(&self) -> String;
+/// }
+///
+/// #[derive(Deserialize)]
+/// struct Login {
+///     user: String,
+/// }
+///
+/// impl Event for Login {
+///     fn describe(&self) -> String {
+///         format!("{} logged in", self.user)
+///     }
+/// }
+///
+/// #[derive(Deserialize)]
+/// struct Logout;
+///
+/// impl Event for Logout {
+///     fn describe(&self) -> String {
+///         "logged out".to_owned()
+///     }
+/// }
+///
+/// fn registry() -> &'static Registry<dyn Event> {
+///     static REGISTRY: OnceLock<Registry<dyn Event>> = OnceLock::new();
+///     REGISTRY.get_or_init(|| {
+///         Registry::new()
+///             .register::<Login>("login")
+///             .register::<Logout>("logout")
+///     })
+/// }
+///
+/// deserialize_trait_object!(Event, registry = registry);
+///
+/// fn main() {
+///     let j = r#" [{"login": {"user": "alice"}}, {"logout": null}] "#;
+///     let events: Vec<Box<dyn Event>> = serde_json::from_str(j).unwrap();
+///     assert_eq!(events[0].describe(), "alice logged in");
+///     assert_eq!(events[1].describe(), "logged out");
+///
+///     let j = r#" {"logn": {"user": "bob"}} "#;
+///     let err = serde_json::from_str::<Box<dyn Event>>(j).err().unwrap();
+///     assert_eq!(
+///         err.to_string(),
+///         "unknown variant `logn`, expected `login` or `logout` at line 1 column 8",
+///     );
+/// }
+/// ```
+pub struct Registry<T: ?Sized> {
+    tags: Vec<&'static str>,
+    constructors: BTreeMap<&'static str, Constructor<T>>,
+}
+
+type Constructor<T> = for<'de> fn(&mut dyn Deserializer<'de>) -> Result<Box<T>, Error>;
+
+/// Conversion from a concrete type into the boxed trait object that a
+/// [`Registry`] produces.
+///
+/// This is implemented by [`deserialize_trait_object!`] for every type that
+/// implements the trait, and does not usually need to be implemented by hand.
+pub trait Upcast<L> {
+    fn upcast(value: L) -> Box<Self>;
+}
+
+impl<T: ?Sized> Registry<T> {
+    /// Create a registry with no tags registered.
+    #[must_use]
+    pub fn new() -> Self {
+        Registry {
+            tags: Vec::new(),
+            constructors: BTreeMap::new(),
+        }
+    }
+
+    /// Associate `tag` with the concrete type `L`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `tag` was already registered.
+    #[must_use]
+    pub fn register<L>(mut self, tag: &'static str) -> Self
+    where
+        T: Upcast<L>,
+        L: DeserializeOwned,
+    {
+        let constructor: Constructor<T> = construct::<T, L>;
+        let previous = self.constructors.insert(tag, constructor);
+        assert!(
+            previous.is_none(),
+            "duplicate tag `{tag}` in erased_serde::Registry",
+        );
+        self.tags.push(tag);
+        self
+    }
+
+    /// The registered tags, in the order they were registered.
+    #[must_use]
+    pub fn tags(&self) -> &[&'static str] {
+        &self.tags
+    }
+}
+
+impl<T: ?Sized> Default for Registry<T> {
+    fn default() -> Self {
+        Registry::new()
+    }
+}
+
+impl<T: ?Sized> fmt::Debug for Registry<T> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter
+            .debug_struct("Registry")
+            .field("tags", &self.tags)
+            .finish_non_exhaustive()
+    }
+}
+
+fn construct<T, L>(deserializer: &mut dyn Deserializer) -> Result<Box<T>, Error>
+where
+    T: ?Sized + Upcast<L>,
+    L: DeserializeOwned,
+{
+    crate::deserialize::<L>(deserializer).map(T::upcast)
+}
+
+// Not public API. Used by deserialize_trait_object!.
+#[doc(hidden)]
+pub fn deserialize<'de, T, D>(
+    registry: &'static Registry<T>,
+    deserializer: D,
+) -> Result<Box<T>, D::Error>
+where
+    T: ?Sized,
+    D: serde::Deserializer<'de>,
+{
+    let name = core::any::type_name::<T>();
+    deserializer.deserialize_enum(name, &registry.tags, TaggedVisitor(registry))
+}
+
+struct TaggedVisitor<T: ?Sized + 'static>(&'static Registry<T>);
+
+impl<'de, T: ?Sized> Visitor<'de> for TaggedVisitor<T> {
+    type Value = Box<T>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a value tagged with one of the registered names")
+    }
+
+    fn visit_enum<A>(self, data: A) -> Result<Self::Value, A::Error>
+    where
+        A: EnumAccess<'de>,
+    {
+        let (constructor, variant) = data.variant_seed(TagSeed(self.0))?;
+        variant.newtype_variant_seed(ValueSeed(constructor))
+    }
+}
+
+struct TagSeed<T: ?Sized + 'static>(&'static Registry<T>);
+
+impl<'de, T: ?Sized> DeserializeSeed<'de> for TagSeed<T> {
+    type Value = Constructor<T>;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        deserializer.deserialize_identifier(self)
+    }
+}
+
+impl<T: ?Sized> Visitor<'_> for TagSeed<T> {
+    type Value = Constructor<T>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a registered tag")
+    }
+
+    fn visit_u64<E>(self, index: u64) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        let tag = usize::try_from(index)
+            .ok()
+            .and_then(|index| self.0.tags.get(index));
+        match tag {
+            Some(tag) => Ok(self.0.constructors[tag]),
+            None => Err(E::invalid_value(Unexpected::Unsigned(index), &self)),
+        }
+    }
+
+    fn visit_str<E>(self, tag: &str) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        match self.0.constructors.get(tag) {
+            Some(constructor) => Ok(*constructor),
+            None => Err(E::unknown_variant(tag, &self.0.tags)),
+        }
+    }
+
+    fn visit_bytes<E>(self, tag: &[u8]) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        match str::from_utf8(tag) {
+            Ok(tag) => self.visit_str(tag),
+            Err(_) => Err(E::invalid_value(Unexpected::Bytes(tag), &self)),
+        }
+    }
+}
+
+struct ValueSeed<T: ?Sized>(Constructor<T>);
+
+impl<'de, T: ?Sized> DeserializeSeed<'de> for ValueSeed<T> {
+    type Value = Box<T>;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let mut erased = <dyn Deserializer>::erase(deserializer);
+        (self.0)(&mut erased).map_err(unerase_de_owned)
+    }
+}
+
+// TEST ////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use crate::{Deserializer, ErrorKind, Registry};
+    use alloc::borrow::ToOwned;
+    use alloc::boxed::Box;
+    use alloc::collections::BTreeMap;
+    use alloc::string::{String, ToString};
+    use alloc::sync::Arc;
+    use alloc::vec::Vec;
+    use serde_cbor::Value;
+    use serde_derive::Deserialize;
+    use std::sync::OnceLock;
+
+    trait Event {
+        fn describe(&self) -> String;
+    }
+
+    #[derive(Deserialize)]
+    struct Login {
+        user: String,
+    }
+
+    impl Event for Login {
+        fn describe(&self) -> String {
+            self.user.clone() + " logged in"
+        }
+    }
+
+    #[derive(Deserialize)]
+    struct Logout;
+
+    impl Event for Logout {
+        fn describe(&self) -> String {
+            "logged out".to_owned()
+        }
+    }
+
+    fn registry() -> &'static Registry<dyn Event + Send + Sync> {
+        static REGISTRY: OnceLock<Registry<dyn Event + Send + Sync>> = OnceLock::new();
+        REGISTRY.get_or_init(|| {
+            Registry::new()
+                .register::<Login>("login")
+                .register::<Logout>("logout")
+        })
+    }
+
+    deserialize_trait_object!(Event + Send + Sync, registry = registry);
+
+    #[test]
+    fn test_json() {
+        let j = r#"[{"login":{"user":"alice"}},{"logout":null}]"#;
+        let events: Vec<Box<dyn Event>> = serde_json::from_str(j).unwrap();
+        assert_eq!(events[0].describe(), "alice logged in");
+        assert_eq!(events[1].describe(), "logged out");
+
+        let j = r#"{"login":{"user":"bob"}}"#;
+        let event: Arc<dyn Event> = serde_json::from_str(j).unwrap();
+        assert_eq!(event.describe(), "bob logged in");
+
+        let event: Box<dyn Event + Send> = serde_json::from_str(j).unwrap();
+        assert_eq!(event.describe(), "bob logged in");
+
+        let event: Arc<dyn Event + Send + Sync> = serde_json::from_str(j).unwrap();
+        assert_eq!(event.describe(), "bob logged in");
+    }
+
+    #[test]
+    fn test_cbor() {
+        let mut bytes = Vec::new();
+        let value = serde_json::json!({"login": {"user": "carol"}});
+        serde_cbor::to_writer(&mut bytes, &value).unwrap();
+        let event: Box<dyn Event> = serde_cbor::from_slice(&bytes).unwrap();
+        assert_eq!(event.describe(), "carol logged in");
+    }
+
+    #[test]
+    fn test_tag_index() {
+        let mut bytes = Vec::new();
+        let value = Value::Map(BTreeMap::from([(Value::Integer(1), Value::Null)]));
+        serde_cbor::to_writer(&mut bytes, &value).unwrap();
+        let event: Box<dyn Event> = serde_cbor::from_slice(&bytes).unwrap();
+        assert_eq!(event.describe(), "logged out");
+
+        bytes.clear();
+        let value = Value::Map(BTreeMap::from([(Value::Integer(2), Value::Null)]));
+        serde_cbor::to_writer(&mut bytes, &value).unwrap();
+        let err = serde_cbor::from_slice::<Box<dyn Event>>(&bytes)
+            .err()
+            .unwrap();
+        assert_eq!(
+            err.to_string(),
+            "invalid value: integer `2`, expected a registered tag",
+        );
+    }
+
+    #[test]
+    fn test_tag_bytes() {
+        let mut bytes = Vec::new();
+        let user = Value::Map(BTreeMap::from([(
+            Value::Text("user".to_owned()),
+            Value::Text("dave".to_owned()),
+        )]));
+        let tag = Value::Bytes(b"login".to_vec());
+        let value = Value::Map(BTreeMap::from([(tag, user)]));
+        serde_cbor::to_writer(&mut bytes, &value).unwrap();
+        let event: Box<dyn Event> = serde_cbor::from_slice(&bytes).unwrap();
+        assert_eq!(event.describe(), "dave logged in");
+    }
+
+    #[test]
+    fn test_unknown_tag() {
+        let j = r#"{"logn":{"user":"bob"}}"#;
+        let json = &mut serde_json::Deserializer::from_str(j);
+        let err = crate::deserialize::<Box<dyn Event>>(&mut <dyn Deserializer>::erase(json))
+            .err()
+            .unwrap();
+        assert_eq!(err.kind(), ErrorKind::UnknownVariant);
+        assert_eq!(err.variant(), Some("logn"));
+        assert_eq!(err.expected_one_of(), Some(&["login", "logout"][..]));
+        assert_eq!(err.suggestion(), Some("login"));
+
+        // Every auto trait combination shares the same registry.
+        let json = &mut serde_json::Deserializer::from_str(j);
+        let err = crate::deserialize::<Box<dyn Event + Sync>>(&mut <dyn Deserializer>::erase(json))
+            .err()
+            .unwrap();
+        assert_eq!(err.kind(), ErrorKind::UnknownVariant);
+        assert_eq!(err.expected_one_of(), Some(&["login", "logout"][..]));
+    }
+
+    #[test]
+    fn test_invalid_content() {
+        let j = r#"{"login":{"usr":"bob"}}"#;
+        let json = &mut serde_json::Deserializer::from_str(j);
+        let err = crate::deserialize::<Box<dyn Event>>(&mut <dyn Deserializer>::erase(json))
+            .err()
+            .unwrap();
+        assert_eq!(err.kind(), ErrorKind::MissingField);
+        assert_eq!(err.field(), Some("user"));
+    }
+
+    #[test]
+    #[should_panic = "duplicate tag `login`"]
+    fn test_duplicate_tag() {
+        let _ = Registry::<dyn Event + Send + Sync>::new()
+            .register::<Login>("login")
+            .register::<Logout>("login");
+    }
+}
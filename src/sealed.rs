@@ -6,6 +6,38 @@ pub mod serializer {
     pub trait Sealed {}
 }
 
+pub mod compound {
+    use crate::ser::Serializer;
+
+    pub trait Sealed {
+        fn erased_serializer(&mut self) -> &mut dyn Serializer;
+    }
+}
+
 pub mod deserializer {
     pub trait Sealed {}
 }
+
+pub mod visitor {
+    pub trait Sealed {}
+}
+
+pub mod deserialize_in_place {
+    pub trait Sealed<'de> {}
+}
+
+pub mod deserialize_seed {
+    pub trait Sealed {}
+}
+
+pub mod seq_access {
+    pub trait Sealed {}
+}
+
+pub mod map_access {
+    pub trait Sealed {}
+}
+
+pub mod enum_access {
+    pub trait Sealed {}
+}
@@ -0,0 +1,403 @@
+use crate::de::Deserializer;
+use crate::error::Error;
+use alloc::boxed::Box;
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+use core::any::{self, Any, TypeId};
+use core::fmt;
+use core::marker::PhantomData;
+use serde::de::{DeserializeOwned, DeserializeSeed, Error as _};
+
+/// Table from runtime names to seeds that deserialize a value of some type
+/// into a `Box<dyn Any + Send>`.
+///
+/// This is for routing, where the type of a payload is only known at runtime
+/// by name. Each entry is either a type registered with
+/// [`insert`][SeedTable::insert], a `DeserializeSeed` registered with
+/// [`insert_seed`][SeedTable::insert_seed] that is cloned for every value it
+/// deserializes, or an implementation of [`TableSeed`] registered with
+/// [`insert_boxed`][SeedTable::insert_boxed].
+///
+/// ```
+/// use erased_serde::{Deserializer, SeedTable};
+/// use serde_derive::Deserialize;
+/// use std::any::Any;
+///
+/// #[derive(Deserialize, Debug)]
+/// struct Chat {
+///     text: String,
+/// }
+///
+/// let mut table = SeedTable::new();
+/// table.insert::<Chat>("chat");
+/// table.insert::<u64>("ping");
+///
+/// fn route(table: &SeedTable, name: &str, payload: &str) -> Box<dyn Any + Send> {
+///     let json = &mut serde_json::Deserializer::from_str(payload);
+///     let payload = &mut <dyn Deserializer>::erase(json);
+///     table.deserialize(name, payload).unwrap()
+/// }
+///
+/// let message = route(&table, "chat", r#"{"text": "hi"}"#);
+/// assert_eq!(message.downcast_ref::<Chat>().unwrap().text, "hi");
+///
+/// let message = route(&table, "ping", "7");
+/// assert_eq!(*message.downcast_ref::<u64>().unwrap(), 7);
+///
+/// // With deserialize_as, a type mismatch is an error rather than a panic.
+/// let json = &mut serde_json::Deserializer::from_str("7");
+/// let payload = &mut <dyn Deserializer>::erase(json);
+/// let error = table.deserialize_as::<String>("ping", payload).unwrap_err();
+/// assert!(error.to_string().starts_with("`ping` deserializes to u64, not "));
+/// ```
+pub struct SeedTable {
+    seeds: BTreeMap<String, Box<dyn TableSeed>>,
+}
+
+/// An object-safe seed that a [`SeedTable`] can use any number of times.
+///
+/// The table implements this internally for the entries added by
+/// [`insert`][SeedTable::insert] and [`insert_seed`][SeedTable::insert_seed].
+/// Implementing it directly covers seeds that neither of those fit, for
+/// example one that borrows shared state instead of being cloned.
+///
+/// ```
+/// use erased_serde::{Deserializer, Error, SeedTable, TableSeed};
+/// use std::any::{self, Any, TypeId};
+/// use std::sync::atomic::{AtomicUsize, Ordering};
+/// use std::sync::Arc;
+///
+/// // Deserializes strings and counts how many it has seen.
+/// struct Counted(Arc<AtomicUsize>);
+///
+/// impl TableSeed for Counted {
+///     fn deserialize_boxed(
+///         &self,
+///         deserializer: &mut dyn Deserializer,
+///     ) -> Result<Box<dyn Any + Send>, Error> {
+///         let value: String = erased_serde::deserialize(deserializer)?;
+///         self.0.fetch_add(1, Ordering::Relaxed);
+///         Ok(Box::new(value))
+///     }
+///
+///     fn value_type_id(&self) -> TypeId {
+///         TypeId::of::<String>()
+///     }
+///
+///     fn value_type_name(&self) -> &'static str {
+///         any::type_name::<String>()
+///     }
+/// }
+///
+/// let count = Arc::new(AtomicUsize::new(0));
+/// let mut table = SeedTable::new();
+/// table.insert_boxed("name", Box::new(Counted(Arc::clone(&count))));
+///
+/// let json = &mut serde_json::Deserializer::from_str(r#""dtolnay""#);
+/// let payload = &mut <dyn Deserializer>::erase(json);
+/// let name = table.deserialize_as::<String>("name", payload).unwrap();
+/// assert_eq!(name, "dtolnay");
+/// assert_eq!(count.load(Ordering::Relaxed), 1);
+/// ```
+pub trait TableSeed: Send + Sync {
+    /// Deserialize one value.
+    fn deserialize_boxed(
+        &self,
+        deserializer: &mut dyn Deserializer,
+    ) -> Result<Box<dyn Any + Send>, Error>;
+
+    /// The `TypeId` of the values that `deserialize_boxed` produces, which
+    /// [`deserialize_as`][SeedTable::deserialize_as] checks before
+    /// deserializing anything.
+    fn value_type_id(&self) -> TypeId;
+
+    /// The name of that type, for error messages.
+    fn value_type_name(&self) -> &'static str;
+}
+
+struct TypeSeed<T>(PhantomData<fn() -> T>);
+
+impl<T> TableSeed for TypeSeed<T>
+where
+    T: DeserializeOwned + Send + 'static,
+{
+    fn deserialize_boxed(
+        &self,
+        deserializer: &mut dyn Deserializer,
+    ) -> Result<Box<dyn Any + Send>, Error> {
+        let value = T::deserialize(deserializer)?;
+        Ok(Box::new(value))
+    }
+
+    fn value_type_id(&self) -> TypeId {
+        TypeId::of::<T>()
+    }
+
+    fn value_type_name(&self) -> &'static str {
+        any::type_name::<T>()
+    }
+}
+
+struct ClonedSeed<S>(S);
+
+impl<S, T> TableSeed for ClonedSeed<S>
+where
+    S: for<'de> DeserializeSeed<'de, Value = T> + Clone + Send + Sync + 'static,
+    T: Send + 'static,
+{
+    fn deserialize_boxed(
+        &self,
+        deserializer: &mut dyn Deserializer,
+    ) -> Result<Box<dyn Any + Send>, Error> {
+        let value = self.0.clone().deserialize(deserializer)?;
+        Ok(Box::new(value))
+    }
+
+    fn value_type_id(&self) -> TypeId {
+        TypeId::of::<T>()
+    }
+
+    fn value_type_name(&self) -> &'static str {
+        any::type_name::<T>()
+    }
+}
+
+impl SeedTable {
+    /// Create an empty table.
+    #[must_use]
+    pub fn new() -> Self {
+        SeedTable {
+            seeds: BTreeMap::new(),
+        }
+    }
+
+    /// Deserialize values of type `T` for the given name, replacing any
+    /// previous entry under that name.
+    pub fn insert<T>(&mut self, name: impl Into<String>)
+    where
+        T: DeserializeOwned + Send + 'static,
+    {
+        let seed = TypeSeed::<T>(PhantomData);
+        self.seeds.insert(name.into(), Box::new(seed));
+    }
+
+    /// Deserialize values for the given name using a clone of `seed` for each
+    /// one, replacing any previous entry under that name.
+    pub fn insert_seed<S, T>(&mut self, name: impl Into<String>, seed: S)
+    where
+        S: for<'de> DeserializeSeed<'de, Value = T> + Clone + Send + Sync + 'static,
+        T: Send + 'static,
+    {
+        self.seeds.insert(name.into(), Box::new(ClonedSeed(seed)));
+    }
+
+    /// Deserialize values for the given name using a seed of your own,
+    /// replacing any previous entry under that name.
+    pub fn insert_boxed(&mut self, name: impl Into<String>, seed: Box<dyn TableSeed>) {
+        self.seeds.insert(name.into(), seed);
+    }
+
+    /// Whether there is an entry for the given name.
+    #[must_use]
+    pub fn contains(&self, name: &str) -> bool {
+        self.seeds.contains_key(name)
+    }
+
+    /// The names that have an entry, in sorted order.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.seeds.keys().map(String::as_str)
+    }
+
+    /// Deserialize a value of whichever type is registered under the given
+    /// name.
+    ///
+    /// Fails with an error if there is no entry for the name.
+    pub fn deserialize(
+        &self,
+        name: &str,
+        deserializer: &mut dyn Deserializer,
+    ) -> Result<Box<dyn Any + Send>, Error> {
+        match self.seeds.get(name) {
+            Some(seed) => seed.deserialize_boxed(deserializer),
+            None => Err(Error::custom(format_args!("unknown type name `{name}`"))),
+        }
+    }
+
+    /// Deserialize a value under the given name and downcast it to `T`.
+    ///
+    /// Fails with an error, before deserializing anything, if there is no
+    /// entry for the name or the entry does not produce a `T`.
+    pub fn deserialize_as<T>(
+        &self,
+        name: &str,
+        deserializer: &mut dyn Deserializer,
+    ) -> Result<T, Error>
+    where
+        T: 'static,
+    {
+        let Some(seed) = self.seeds.get(name) else {
+            return Err(Error::custom(format_args!("unknown type name `{name}`")));
+        };
+        let mismatch = || {
+            Error::custom(format_args!(
+                "`{}` deserializes to {}, not {}",
+                name,
+                seed.value_type_name(),
+                any::type_name::<T>(),
+            ))
+        };
+        if seed.value_type_id() != TypeId::of::<T>() {
+            return Err(mismatch());
+        }
+        let value = seed.deserialize_boxed(deserializer)?;
+        match value.downcast::<T>() {
+            Ok(value) => Ok(*value),
+            Err(_) => Err(mismatch()),
+        }
+    }
+}
+
+impl Default for SeedTable {
+    fn default() -> Self {
+        SeedTable::new()
+    }
+}
+
+impl fmt::Debug for SeedTable {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter
+            .debug_struct("SeedTable")
+            .field("names", &self.seeds.keys())
+            .finish_non_exhaustive()
+    }
+}
+
+// TEST ////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use crate::{Deserializer, SeedTable};
+    use alloc::borrow::ToOwned;
+    use alloc::format;
+    use alloc::string::{String, ToString};
+    use alloc::vec;
+    use alloc::vec::Vec;
+    use serde::de::{DeserializeSeed, SeqAccess, Visitor};
+    use serde_derive::Deserialize;
+
+    #[derive(Deserialize, PartialEq, Debug)]
+    struct Chat {
+        text: String,
+    }
+
+    // Sums a sequence of numbers, scaled by a factor known only at runtime.
+    #[derive(Clone)]
+    struct Scaled(u64);
+
+    impl<'de> DeserializeSeed<'de> for Scaled {
+        type Value = u64;
+
+        fn deserialize<D>(self, deserializer: D) -> Result<u64, D::Error>
+        where
+            D: serde::Deserializer<'de>,
+        {
+            deserializer.deserialize_seq(self)
+        }
+    }
+
+    impl<'de> Visitor<'de> for Scaled {
+        type Value = u64;
+
+        fn expecting(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+            formatter.write_str("a sequence of numbers")
+        }
+
+        fn visit_seq<A>(self, mut seq: A) -> Result<u64, A::Error>
+        where
+            A: SeqAccess<'de>,
+        {
+            let mut sum = 0;
+            while let Some(n) = seq.next_element::<u64>()? {
+                sum += n * self.0;
+            }
+            Ok(sum)
+        }
+    }
+
+    fn table() -> SeedTable {
+        let mut table = SeedTable::new();
+        table.insert::<Chat>("chat");
+        table.insert::<Vec<u8>>("bytes");
+        table.insert_seed("scaled", Scaled(10));
+        table
+    }
+
+    #[test]
+    fn test_deserialize() {
+        let table = table();
+
+        let json = &mut serde_json::Deserializer::from_str(r#"{"text": "hi"}"#);
+        let value = table
+            .deserialize("chat", &mut <dyn Deserializer>::erase(json))
+            .unwrap();
+        let expected = Chat {
+            text: "hi".to_owned(),
+        };
+        assert_eq!(*value.downcast::<Chat>().unwrap(), expected);
+
+        let cbor = serde_cbor::to_vec(&[1, 2, 3]).unwrap();
+        let cbor = &mut serde_cbor::Deserializer::from_slice(&cbor);
+        let value = table
+            .deserialize_as::<u64>("scaled", &mut <dyn Deserializer>::erase(cbor))
+            .unwrap();
+        assert_eq!(value, 60);
+
+        let names: Vec<&str> = table.names().collect();
+        assert_eq!(names, ["bytes", "chat", "scaled"]);
+        assert!(table.contains("bytes"));
+        assert!(!table.contains("text"));
+    }
+
+    #[test]
+    fn test_unknown_name() {
+        let table = table();
+
+        let json = &mut serde_json::Deserializer::from_str("null");
+        let error = table
+            .deserialize("ping", &mut <dyn Deserializer>::erase(json))
+            .err()
+            .unwrap();
+        assert_eq!(error.to_string(), "unknown type name `ping`");
+    }
+
+    #[test]
+    fn test_downcast_failure() {
+        let table = table();
+
+        // The payload is not consumed when the types do not match.
+        let json = &mut serde_json::Deserializer::from_str("[1, 2]");
+        let format = &mut <dyn Deserializer>::erase(json);
+        let error = table.deserialize_as::<String>("bytes", format).unwrap_err();
+        let expected = format!(
+            "`bytes` deserializes to {}, not {}",
+            core::any::type_name::<Vec<u8>>(),
+            core::any::type_name::<String>(),
+        );
+        assert_eq!(error.to_string(), expected);
+
+        let value = table.deserialize_as::<Vec<u8>>("bytes", format).unwrap();
+        assert_eq!(value, vec![1, 2]);
+    }
+
+    #[test]
+    fn test_replace() {
+        let mut table = table();
+        table.insert_seed("chat", Scaled(2));
+
+        let json = &mut serde_json::Deserializer::from_str("[4]");
+        let value = table
+            .deserialize_as::<u64>("chat", &mut <dyn Deserializer>::erase(json))
+            .unwrap();
+        assert_eq!(value, 8);
+    }
+}
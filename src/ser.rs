@@ -1,3 +1,37 @@
+//! Object-safe equivalents of Serde's serialization traits.
+//!
+//! Besides [`Serialize`] and [`Serializer`], this module names the traits for
+//! the compound values which an erased serializer opens. These are sealed.
+//!
+//! A `&mut dyn Serializer` is itself a `serde::Serializer` whose error type is
+//! [`erased_serde::Error`][crate::Error], so code which has no `Serialize` type
+//! at hand can drive one directly with Serde's API.
+//!
+//! ```rust
+//! use erased_serde::{Error, Serialize, Serializer};
+//! use serde::ser::{SerializeStruct as _, Serializer as _};
+//!
+//! // Writes a struct whose fields are only known at runtime.
+//! fn write_record(
+//!     serializer: &mut dyn Serializer,
+//!     fields: &[(&'static str, &dyn Serialize)],
+//! ) -> Result<(), Error> {
+//!     let mut record = serializer.serialize_struct("Record", fields.len())?;
+//!     for (key, value) in fields {
+//!         record.serialize_field(key, value)?;
+//!     }
+//!     record.end()
+//! }
+//!
+//! let mut json = <dyn Serializer>::erase(serde_json::value::Serializer);
+//! write_record(&mut json, &[("id", &7), ("tags", &["a", "b"])]).unwrap();
+//! let value = json.into_result().unwrap();
+//! assert_eq!(value, serde_json::json!({"id": 7, "tags": ["a", "b"]}));
+//! ```
+//!
+//! Every call returns the error of the underlying serializer as soon as it
+//! fails, so the usual `?` stops at the first failure.
+
 use self::ErrorImpl::ShortCircuit;
 use crate::error::Error;
 use crate::sealed;
@@ -65,6 +99,9 @@ pub trait Serialize: sealed::serialize::Sealed {
 
     #[doc(hidden)]
     fn do_erased_serialize(&self, serializer: &mut dyn Serializer) -> Result<(), ErrorImpl>;
+
+    #[doc(hidden)]
+    fn erased_type_name(&self) -> &'static str;
 }
 
 /// An object-safe equivalent of Serde's `Serializer` trait.
@@ -108,7 +145,8 @@ pub trait Serialize: sealed::serialize::Sealed {
 /// ```
 ///
 /// This trait is sealed and can only be implemented via a `serde::Serializer`
-/// impl.
+/// impl, or by implementing [`custom::Serializer`][crate::custom::Serializer]
+/// and wrapping it in [`custom::Erased`][crate::custom::Erased].
 pub trait Serializer: sealed::serializer::Sealed {
     fn erased_serialize_bool(&mut self, v: bool);
     fn erased_serialize_i8(&mut self, v: i8);
@@ -180,51 +218,121 @@ pub trait Serializer: sealed::serializer::Sealed {
     fn erased_is_human_readable(&self) -> bool;
     #[doc(hidden)]
     fn erased_display_error(&self) -> &dyn Display;
+    #[doc(hidden)]
+    fn erased_preserved_error(&mut self) -> Option<Error>;
+    #[doc(hidden)]
+    fn erased_failed(&self) -> bool;
+    #[doc(hidden)]
+    fn erased_open_seq(&mut self, len: Option<usize>) -> Result<&mut dyn SerializeSeq, Error>;
+    #[doc(hidden)]
+    fn erased_open_tuple(&mut self, len: usize) -> Result<&mut dyn SerializeTuple, Error>;
+    #[doc(hidden)]
+    fn erased_open_tuple_struct(
+        &mut self,
+        name: &'static str,
+        len: usize,
+    ) -> Result<&mut dyn SerializeTupleStruct, Error>;
+    #[doc(hidden)]
+    fn erased_open_tuple_variant(
+        &mut self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<&mut dyn SerializeTupleVariant, Error>;
+    #[doc(hidden)]
+    fn erased_open_map(&mut self, len: Option<usize>) -> Result<&mut dyn SerializeMap, Error>;
+    #[doc(hidden)]
+    fn erased_open_struct(
+        &mut self,
+        name: &'static str,
+        len: usize,
+    ) -> Result<&mut dyn SerializeStruct, Error>;
+    #[doc(hidden)]
+    fn erased_open_struct_variant(
+        &mut self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<&mut dyn SerializeStructVariant, Error>;
+    #[doc(hidden)]
+    fn erased_as_serializer(&mut self) -> &mut dyn Serializer;
 }
 
 impl dyn Serializer {
-    return_impl_trait! {
-        /// Convert any Serde `Serializer` to a trait object.
-        ///
-        /// ```rust
-        /// use erased_serde::{Serialize, Serializer};
-        /// use std::collections::BTreeMap as Map;
-        /// use std::io;
-        ///
-        /// fn main() {
-        ///     // Construct some serializers.
-        ///     let json = &mut serde_json::Serializer::new(io::stdout());
-        ///     let cbor = &mut serde_cbor::Serializer::new(serde_cbor::ser::IoWrite::new(io::stdout()));
-        ///
-        ///     // The values in this map are boxed trait objects. Ordinarily this would not
-        ///     // be possible with serde::Serializer because of object safety, but type
-        ///     // erasure makes it possible with erased_serde::Serializer.
-        ///     let mut formats: Map<&str, Box<dyn Serializer>> = Map::new();
-        ///     formats.insert("json", Box::new(<dyn Serializer>::erase(json)));
-        ///     formats.insert("cbor", Box::new(<dyn Serializer>::erase(cbor)));
-        ///
-        ///     // These are boxed trait objects as well. Same thing here - type erasure
-        ///     // makes this possible.
-        ///     let mut values: Map<&str, Box<dyn Serialize>> = Map::new();
-        ///     values.insert("vec", Box::new(vec!["a", "b"]));
-        ///     values.insert("int", Box::new(65536));
-        ///
-        ///     // Pick a Serializer out of the formats map.
-        ///     let format = formats.get_mut("json").unwrap();
-        ///
-        ///     // Pick a Serialize out of the values map.
-        ///     let value = values.get("vec").unwrap();
-        ///
-        ///     // This line prints `["a","b"]` to stdout.
-        ///     value.erased_serialize(format).unwrap();
-        /// }
-        /// ```
-        pub fn erase<S>(serializer: S) -> impl Serializer [erase::Serializer<S>]
-        where
-            S: serde::Serializer,
-        {
-            erase::Serializer::new(serializer)
-        }
+    /// Convert any Serde `Serializer` to a trait object.
+    ///
+    /// ```rust
+    /// use erased_serde::{Serialize, Serializer};
+    /// use std::collections::BTreeMap as Map;
+    /// use std::io;
+    ///
+    /// fn main() {
+    ///     // Construct some serializers.
+    ///     let json = &mut serde_json::Serializer::new(io::stdout());
+    ///     let cbor = &mut serde_cbor::Serializer::new(serde_cbor::ser::IoWrite::new(io::stdout()));
+    ///
+    ///     // The values in this map are boxed trait objects. Ordinarily this would not
+    ///     // be possible with serde::Serializer because of object safety, but type
+    ///     // erasure makes it possible with erased_serde::Serializer.
+    ///     let mut formats: Map<&str, Box<dyn Serializer>> = Map::new();
+    ///     formats.insert("json", Box::new(<dyn Serializer>::erase(json)));
+    ///     formats.insert("cbor", Box::new(<dyn Serializer>::erase(cbor)));
+    ///
+    ///     // These are boxed trait objects as well. Same thing here - type erasure
+    ///     // makes this possible.
+    ///     let mut values: Map<&str, Box<dyn Serialize>> = Map::new();
+    ///     values.insert("vec", Box::new(vec!["a", "b"]));
+    ///     values.insert("int", Box::new(65536));
+    ///
+    ///     // Pick a Serializer out of the formats map.
+    ///     let format = formats.get_mut("json").unwrap();
+    ///
+    ///     // Pick a Serialize out of the values map.
+    ///     let value = values.get("vec").unwrap();
+    ///
+    ///     // This line prints `["a","b"]` to stdout.
+    ///     value.erased_serialize(format).unwrap();
+    /// }
+    /// ```
+    pub fn erase<S>(serializer: S) -> erase::Serializer<S>
+    where
+        S: serde::Serializer,
+    {
+        erase::Serializer::new(serializer)
+    }
+
+    /// Convert any Serde `Serializer` to a trait object, keeping its errors
+    /// intact.
+    ///
+    /// Unlike `erase`, which reduces a failure to its message, errors from
+    /// this serializer are stored inside the resulting `erased_serde::Error`
+    /// and can be recovered with [`Error::downcast_ref`] or
+    /// [`Error::downcast`].
+    ///
+    /// ```rust
+    /// use erased_serde::{Serialize, Serializer};
+    /// use std::collections::BTreeMap as Map;
+    ///
+    /// let json = &mut serde_json::Serializer::new(Vec::new());
+    /// let format = &mut <dyn Serializer>::erase_preserving_errors(json);
+    ///
+    /// // JSON object keys must be strings.
+    /// let value: Box<dyn Serialize> = Box::new(Map::from([(vec![1], 1)]));
+    /// let error = value.erased_serialize(format).unwrap_err();
+    ///
+    /// let json_error = error.downcast::<serde_json::Error>().unwrap();
+    /// assert_eq!(json_error.to_string(), "key must be a string");
+    /// ```
+    pub fn erase_preserving_errors<S>(serializer: S) -> erase::Serializer<S>
+    where
+        S: serde::Serializer,
+        S::Error: Send + Sync + 'static,
+    {
+        let mut erased = erase::Serializer::new(serializer);
+        erased.preserve = Some(crate::error::preserve);
+        erased
     }
 }
 
@@ -235,24 +343,67 @@ where
     T: ?Sized + serde::Serialize,
 {
     fn erased_serialize(&self, serializer: &mut dyn Serializer) -> Result<(), Error> {
-        match self.do_erased_serialize(serializer) {
-            Ok(()) => Ok(()),
-            Err(ShortCircuit) => Err(serde::ser::Error::custom(serializer.erased_display_error())),
-            Err(ErrorImpl::Custom(msg)) => Err(serde::ser::Error::custom(msg)),
-        }
+        self.do_erased_serialize(serializer)
+            .map_err(|err| take_error(serializer, err))
     }
 
     fn do_erased_serialize(&self, serializer: &mut dyn Serializer) -> Result<(), ErrorImpl> {
         self.serialize(MakeSerializer(serializer))
     }
+
+    fn erased_type_name(&self) -> &'static str {
+        core::any::type_name::<T>()
+    }
 }
 
 impl<T> sealed::serialize::Sealed for T where T: ?Sized + serde::Serialize {}
 
-mod erase {
+// Resolves the error that came out of serializing into `serializer`, which in
+// the case of ShortCircuit is the one held by the serializer itself.
+pub(crate) fn take_error(serializer: &mut dyn Serializer, error: ErrorImpl) -> Error {
+    match error {
+        ShortCircuit => match serializer.erased_preserved_error() {
+            Some(error) => error,
+            None => serde::ser::Error::custom(serializer.erased_display_error()),
+        },
+        ErrorImpl::Custom(msg) => serde::ser::Error::custom(msg),
+    }
+}
+
+pub(crate) mod erase {
+    use crate::error::Error;
     use core::mem;
 
-    pub enum Serializer<S>
+    /// A Serde `Serializer` converted into an `erased_serde::Serializer`.
+    ///
+    /// This is the type returned by [`<dyn Serializer>::erase`][erase]. Once a
+    /// value has been serialized into it, the serializer's `Ok` output can be
+    /// retrieved with [`into_result`][Serializer::into_result] or
+    /// [`take_ok`][Serializer::take_ok], which makes value-producing
+    /// serializers usable behind a trait object.
+    ///
+    /// [erase]: trait.Serializer.html#method.erase
+    ///
+    /// ```rust
+    /// use erased_serde::{Serialize, Serializer};
+    ///
+    /// let mut erased = <dyn Serializer>::erase(serde_json::value::Serializer);
+    ///
+    /// let value: Box<dyn Serialize> = Box::new(vec![1, 2]);
+    /// value.erased_serialize(&mut erased).unwrap();
+    ///
+    /// let json = erased.into_result().unwrap();
+    /// assert_eq!(json, serde_json::json!([1, 2]));
+    /// ```
+    pub struct Serializer<S>
+    where
+        S: serde::Serializer,
+    {
+        pub(crate) state: State<S>,
+        pub(crate) preserve: Option<fn(S::Error) -> Error>,
+    }
+
+    pub(crate) enum State<S>
     where
         S: serde::Serializer,
     {
@@ -274,19 +425,55 @@ mod erase {
         S: serde::Serializer,
     {
         pub(crate) fn new(serializer: S) -> Self {
-            Serializer::Ready(serializer)
+            Serializer {
+                state: State::Ready(serializer),
+                preserve: None,
+            }
         }
 
-        pub(crate) fn take(&mut self) -> Self {
-            mem::replace(self, Serializer::Unusable)
+        pub(crate) fn take(&mut self) -> State<S> {
+            mem::replace(&mut self.state, State::Unusable)
         }
 
         pub(crate) fn take_serializer(&mut self) -> S {
             match self.take() {
-                Serializer::Ready(serializer) => serializer,
+                State::Ready(serializer) => serializer,
                 _ => unreachable!(),
             }
         }
+
+        /// Consume the erased serializer and return the result of the
+        /// serialization performed through it.
+        ///
+        /// Returns an error made with `S::Error::custom` if no value has been
+        /// serialized, if serialization was left partway through a compound
+        /// value, or if the serializer was created with
+        /// `erase_preserving_errors` and its error has already been moved into
+        /// an `erased_serde::Error`.
+        pub fn into_result(self) -> Result<S::Ok, S::Error> {
+            match self.state {
+                State::Complete(ok) => Ok(ok),
+                State::Error(err) => Err(err),
+                _ => Err(serde::ser::Error::custom(
+                    "erased serializer has not completed serialization",
+                )),
+            }
+        }
+
+        /// Take the serializer's `Ok` output if serialization has completed
+        /// successfully.
+        ///
+        /// Returns `None`, leaving the serializer untouched, if it has not
+        /// finished or if it failed.
+        pub fn take_ok(&mut self) -> Option<S::Ok> {
+            match self.take() {
+                State::Complete(ok) => Some(ok),
+                other => {
+                    self.state = other;
+                    None
+                }
+            }
+        }
     }
 }
 
@@ -295,142 +482,142 @@ where
     T: serde::Serializer,
 {
     fn erased_serialize_bool(&mut self, v: bool) {
-        *self = match self.take_serializer().serialize_bool(v) {
-            Ok(ok) => erase::Serializer::Complete(ok),
-            Err(err) => erase::Serializer::Error(err),
+        self.state = match self.take_serializer().serialize_bool(v) {
+            Ok(ok) => erase::State::Complete(ok),
+            Err(err) => erase::State::Error(err),
         };
     }
 
     fn erased_serialize_i8(&mut self, v: i8) {
-        *self = match self.take_serializer().serialize_i8(v) {
-            Ok(ok) => erase::Serializer::Complete(ok),
-            Err(err) => erase::Serializer::Error(err),
+        self.state = match self.take_serializer().serialize_i8(v) {
+            Ok(ok) => erase::State::Complete(ok),
+            Err(err) => erase::State::Error(err),
         };
     }
 
     fn erased_serialize_i16(&mut self, v: i16) {
-        *self = match self.take_serializer().serialize_i16(v) {
-            Ok(ok) => erase::Serializer::Complete(ok),
-            Err(err) => erase::Serializer::Error(err),
+        self.state = match self.take_serializer().serialize_i16(v) {
+            Ok(ok) => erase::State::Complete(ok),
+            Err(err) => erase::State::Error(err),
         };
     }
 
     fn erased_serialize_i32(&mut self, v: i32) {
-        *self = match self.take_serializer().serialize_i32(v) {
-            Ok(ok) => erase::Serializer::Complete(ok),
-            Err(err) => erase::Serializer::Error(err),
+        self.state = match self.take_serializer().serialize_i32(v) {
+            Ok(ok) => erase::State::Complete(ok),
+            Err(err) => erase::State::Error(err),
         };
     }
 
     fn erased_serialize_i64(&mut self, v: i64) {
-        *self = match self.take_serializer().serialize_i64(v) {
-            Ok(ok) => erase::Serializer::Complete(ok),
-            Err(err) => erase::Serializer::Error(err),
+        self.state = match self.take_serializer().serialize_i64(v) {
+            Ok(ok) => erase::State::Complete(ok),
+            Err(err) => erase::State::Error(err),
         };
     }
 
     fn erased_serialize_i128(&mut self, v: i128) {
-        *self = match self.take_serializer().serialize_i128(v) {
-            Ok(ok) => erase::Serializer::Complete(ok),
-            Err(err) => erase::Serializer::Error(err),
+        self.state = match self.take_serializer().serialize_i128(v) {
+            Ok(ok) => erase::State::Complete(ok),
+            Err(err) => erase::State::Error(err),
         };
     }
 
     fn erased_serialize_u8(&mut self, v: u8) {
-        *self = match self.take_serializer().serialize_u8(v) {
-            Ok(ok) => erase::Serializer::Complete(ok),
-            Err(err) => erase::Serializer::Error(err),
+        self.state = match self.take_serializer().serialize_u8(v) {
+            Ok(ok) => erase::State::Complete(ok),
+            Err(err) => erase::State::Error(err),
         };
     }
 
     fn erased_serialize_u16(&mut self, v: u16) {
-        *self = match self.take_serializer().serialize_u16(v) {
-            Ok(ok) => erase::Serializer::Complete(ok),
-            Err(err) => erase::Serializer::Error(err),
+        self.state = match self.take_serializer().serialize_u16(v) {
+            Ok(ok) => erase::State::Complete(ok),
+            Err(err) => erase::State::Error(err),
         };
     }
 
     fn erased_serialize_u32(&mut self, v: u32) {
-        *self = match self.take_serializer().serialize_u32(v) {
-            Ok(ok) => erase::Serializer::Complete(ok),
-            Err(err) => erase::Serializer::Error(err),
+        self.state = match self.take_serializer().serialize_u32(v) {
+            Ok(ok) => erase::State::Complete(ok),
+            Err(err) => erase::State::Error(err),
         };
     }
 
     fn erased_serialize_u64(&mut self, v: u64) {
-        *self = match self.take_serializer().serialize_u64(v) {
-            Ok(ok) => erase::Serializer::Complete(ok),
-            Err(err) => erase::Serializer::Error(err),
+        self.state = match self.take_serializer().serialize_u64(v) {
+            Ok(ok) => erase::State::Complete(ok),
+            Err(err) => erase::State::Error(err),
         };
     }
 
     fn erased_serialize_u128(&mut self, v: u128) {
-        *self = match self.take_serializer().serialize_u128(v) {
-            Ok(ok) => erase::Serializer::Complete(ok),
-            Err(err) => erase::Serializer::Error(err),
+        self.state = match self.take_serializer().serialize_u128(v) {
+            Ok(ok) => erase::State::Complete(ok),
+            Err(err) => erase::State::Error(err),
         };
     }
 
     fn erased_serialize_f32(&mut self, v: f32) {
-        *self = match self.take_serializer().serialize_f32(v) {
-            Ok(ok) => erase::Serializer::Complete(ok),
-            Err(err) => erase::Serializer::Error(err),
+        self.state = match self.take_serializer().serialize_f32(v) {
+            Ok(ok) => erase::State::Complete(ok),
+            Err(err) => erase::State::Error(err),
         };
     }
 
     fn erased_serialize_f64(&mut self, v: f64) {
-        *self = match self.take_serializer().serialize_f64(v) {
-            Ok(ok) => erase::Serializer::Complete(ok),
-            Err(err) => erase::Serializer::Error(err),
+        self.state = match self.take_serializer().serialize_f64(v) {
+            Ok(ok) => erase::State::Complete(ok),
+            Err(err) => erase::State::Error(err),
         };
     }
 
     fn erased_serialize_char(&mut self, v: char) {
-        *self = match self.take_serializer().serialize_char(v) {
-            Ok(ok) => erase::Serializer::Complete(ok),
-            Err(err) => erase::Serializer::Error(err),
+        self.state = match self.take_serializer().serialize_char(v) {
+            Ok(ok) => erase::State::Complete(ok),
+            Err(err) => erase::State::Error(err),
         };
     }
 
     fn erased_serialize_str(&mut self, v: &str) {
-        *self = match self.take_serializer().serialize_str(v) {
-            Ok(ok) => erase::Serializer::Complete(ok),
-            Err(err) => erase::Serializer::Error(err),
+        self.state = match self.take_serializer().serialize_str(v) {
+            Ok(ok) => erase::State::Complete(ok),
+            Err(err) => erase::State::Error(err),
         };
     }
 
     fn erased_serialize_bytes(&mut self, v: &[u8]) {
-        *self = match self.take_serializer().serialize_bytes(v) {
-            Ok(ok) => erase::Serializer::Complete(ok),
-            Err(err) => erase::Serializer::Error(err),
+        self.state = match self.take_serializer().serialize_bytes(v) {
+            Ok(ok) => erase::State::Complete(ok),
+            Err(err) => erase::State::Error(err),
         };
     }
 
     fn erased_serialize_none(&mut self) {
-        *self = match self.take_serializer().serialize_none() {
-            Ok(ok) => erase::Serializer::Complete(ok),
-            Err(err) => erase::Serializer::Error(err),
+        self.state = match self.take_serializer().serialize_none() {
+            Ok(ok) => erase::State::Complete(ok),
+            Err(err) => erase::State::Error(err),
         };
     }
 
     fn erased_serialize_some(&mut self, value: &dyn Serialize) {
-        *self = match self.take_serializer().serialize_some(value) {
-            Ok(ok) => erase::Serializer::Complete(ok),
-            Err(err) => erase::Serializer::Error(err),
+        self.state = match self.take_serializer().serialize_some(value) {
+            Ok(ok) => erase::State::Complete(ok),
+            Err(err) => erase::State::Error(err),
         };
     }
 
     fn erased_serialize_unit(&mut self) {
-        *self = match self.take_serializer().serialize_unit() {
-            Ok(ok) => erase::Serializer::Complete(ok),
-            Err(err) => erase::Serializer::Error(err),
+        self.state = match self.take_serializer().serialize_unit() {
+            Ok(ok) => erase::State::Complete(ok),
+            Err(err) => erase::State::Error(err),
         };
     }
 
     fn erased_serialize_unit_struct(&mut self, name: &'static str) {
-        *self = match self.take_serializer().serialize_unit_struct(name) {
-            Ok(ok) => erase::Serializer::Complete(ok),
-            Err(err) => erase::Serializer::Error(err),
+        self.state = match self.take_serializer().serialize_unit_struct(name) {
+            Ok(ok) => erase::State::Complete(ok),
+            Err(err) => erase::State::Error(err),
         };
     }
 
@@ -440,19 +627,20 @@ where
         variant_index: u32,
         variant: &'static str,
     ) {
-        *self = match self
-            .take_serializer()
-            .serialize_unit_variant(name, variant_index, variant)
-        {
-            Ok(ok) => erase::Serializer::Complete(ok),
-            Err(err) => erase::Serializer::Error(err),
-        };
+        self.state =
+            match self
+                .take_serializer()
+                .serialize_unit_variant(name, variant_index, variant)
+            {
+                Ok(ok) => erase::State::Complete(ok),
+                Err(err) => erase::State::Error(err),
+            };
     }
 
     fn erased_serialize_newtype_struct(&mut self, name: &'static str, value: &dyn Serialize) {
-        *self = match self.take_serializer().serialize_newtype_struct(name, value) {
-            Ok(ok) => erase::Serializer::Complete(ok),
-            Err(err) => erase::Serializer::Error(err),
+        self.state = match self.take_serializer().serialize_newtype_struct(name, value) {
+            Ok(ok) => erase::State::Complete(ok),
+            Err(err) => erase::State::Error(err),
         };
     }
 
@@ -463,14 +651,14 @@ where
         variant: &'static str,
         value: &dyn Serialize,
     ) {
-        *self = match self.take_serializer().serialize_newtype_variant(
+        self.state = match self.take_serializer().serialize_newtype_variant(
             name,
             variant_index,
             variant,
             value,
         ) {
-            Ok(ok) => erase::Serializer::Complete(ok),
-            Err(err) => erase::Serializer::Error(err),
+            Ok(ok) => erase::State::Complete(ok),
+            Err(err) => erase::State::Error(err),
         };
     }
 
@@ -480,11 +668,11 @@ where
     ) -> Result<&mut dyn SerializeSeq, ErrorImpl> {
         match self.take_serializer().serialize_seq(len) {
             Ok(ok) => {
-                *self = erase::Serializer::Seq(ok);
+                self.state = erase::State::Seq(ok);
                 Ok(self)
             }
             Err(err) => {
-                *self = erase::Serializer::Error(err);
+                self.state = erase::State::Error(err);
                 Err(ShortCircuit)
             }
         }
@@ -493,11 +681,11 @@ where
     fn erased_serialize_tuple(&mut self, len: usize) -> Result<&mut dyn SerializeTuple, ErrorImpl> {
         match self.take_serializer().serialize_tuple(len) {
             Ok(ok) => {
-                *self = erase::Serializer::Tuple(ok);
+                self.state = erase::State::Tuple(ok);
                 Ok(self)
             }
             Err(err) => {
-                *self = erase::Serializer::Error(err);
+                self.state = erase::State::Error(err);
                 Err(ShortCircuit)
             }
         }
@@ -510,11 +698,11 @@ where
     ) -> Result<&mut dyn SerializeTupleStruct, ErrorImpl> {
         match self.take_serializer().serialize_tuple_struct(name, len) {
             Ok(ok) => {
-                *self = erase::Serializer::TupleStruct(ok);
+                self.state = erase::State::TupleStruct(ok);
                 Ok(self)
             }
             Err(err) => {
-                *self = erase::Serializer::Error(err);
+                self.state = erase::State::Error(err);
                 Err(ShortCircuit)
             }
         }
@@ -532,11 +720,11 @@ where
             .serialize_tuple_variant(name, variant_index, variant, len)
         {
             Ok(ok) => {
-                *self = erase::Serializer::TupleVariant(ok);
+                self.state = erase::State::TupleVariant(ok);
                 Ok(self)
             }
             Err(err) => {
-                *self = erase::Serializer::Error(err);
+                self.state = erase::State::Error(err);
                 Err(ShortCircuit)
             }
         }
@@ -548,11 +736,11 @@ where
     ) -> Result<&mut dyn SerializeMap, ErrorImpl> {
         match self.take_serializer().serialize_map(len) {
             Ok(ok) => {
-                *self = erase::Serializer::Map(ok);
+                self.state = erase::State::Map(ok);
                 Ok(self)
             }
             Err(err) => {
-                *self = erase::Serializer::Error(err);
+                self.state = erase::State::Error(err);
                 Err(ShortCircuit)
             }
         }
@@ -565,11 +753,11 @@ where
     ) -> Result<&mut dyn SerializeStruct, ErrorImpl> {
         match self.take_serializer().serialize_struct(name, len) {
             Ok(ok) => {
-                *self = erase::Serializer::Struct(ok);
+                self.state = erase::State::Struct(ok);
                 Ok(self)
             }
             Err(err) => {
-                *self = erase::Serializer::Error(err);
+                self.state = erase::State::Error(err);
                 Err(ShortCircuit)
             }
         }
@@ -587,33 +775,128 @@ where
             .serialize_struct_variant(name, variant_index, variant, len)
         {
             Ok(ok) => {
-                *self = erase::Serializer::StructVariant(ok);
+                self.state = erase::State::StructVariant(ok);
                 Ok(self)
             }
             Err(err) => {
-                *self = erase::Serializer::Error(err);
+                self.state = erase::State::Error(err);
                 Err(ShortCircuit)
             }
         }
     }
 
     fn erased_is_human_readable(&self) -> bool {
-        match self {
-            erase::Serializer::Ready(serializer) => serializer.is_human_readable(),
+        match &self.state {
+            erase::State::Ready(serializer) => serializer.is_human_readable(),
             _ => unreachable!(),
         }
     }
 
     fn erased_display_error(&self) -> &dyn Display {
-        match self {
-            erase::Serializer::Error(err) => err,
+        match &self.state {
+            erase::State::Error(err) => err,
             _ => unreachable!(),
         }
     }
+
+    fn erased_preserved_error(&mut self) -> Option<Error> {
+        let preserve = self.preserve?;
+        match self.take() {
+            erase::State::Error(err) => Some(preserve(err)),
+            state => {
+                self.state = state;
+                None
+            }
+        }
+    }
+
+    fn erased_failed(&self) -> bool {
+        matches!(self.state, erase::State::Error(_))
+    }
+
+    fn erased_open_seq(&mut self, len: Option<usize>) -> Result<&mut dyn SerializeSeq, Error> {
+        let error = self.erased_serialize_seq(len).err();
+        opened(self, error)?;
+        Ok(self)
+    }
+
+    fn erased_open_tuple(&mut self, len: usize) -> Result<&mut dyn SerializeTuple, Error> {
+        let error = self.erased_serialize_tuple(len).err();
+        opened(self, error)?;
+        Ok(self)
+    }
+
+    fn erased_open_tuple_struct(
+        &mut self,
+        name: &'static str,
+        len: usize,
+    ) -> Result<&mut dyn SerializeTupleStruct, Error> {
+        let error = self.erased_serialize_tuple_struct(name, len).err();
+        opened(self, error)?;
+        Ok(self)
+    }
+
+    fn erased_open_tuple_variant(
+        &mut self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<&mut dyn SerializeTupleVariant, Error> {
+        let error = self
+            .erased_serialize_tuple_variant(name, variant_index, variant, len)
+            .err();
+        opened(self, error)?;
+        Ok(self)
+    }
+
+    fn erased_open_map(&mut self, len: Option<usize>) -> Result<&mut dyn SerializeMap, Error> {
+        let error = self.erased_serialize_map(len).err();
+        opened(self, error)?;
+        Ok(self)
+    }
+
+    fn erased_open_struct(
+        &mut self,
+        name: &'static str,
+        len: usize,
+    ) -> Result<&mut dyn SerializeStruct, Error> {
+        let error = self.erased_serialize_struct(name, len).err();
+        opened(self, error)?;
+        Ok(self)
+    }
+
+    fn erased_open_struct_variant(
+        &mut self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<&mut dyn SerializeStructVariant, Error> {
+        let error = self
+            .erased_serialize_struct_variant(name, variant_index, variant, len)
+            .err();
+        opened(self, error)?;
+        Ok(self)
+    }
+
+    fn erased_as_serializer(&mut self) -> &mut dyn Serializer {
+        self
+    }
 }
 
 impl<T> sealed::serializer::Sealed for erase::Serializer<T> where T: serde::Serializer {}
 
+impl<T> sealed::compound::Sealed for T
+where
+    T: Serializer,
+{
+    fn erased_serializer(&mut self) -> &mut dyn Serializer {
+        self
+    }
+}
+
+#[doc(hidden)]
 pub enum ErrorImpl {
     ShortCircuit,
     Custom(Box<String>),
@@ -681,16 +964,12 @@ where
         Ok(()) | Err(ShortCircuit) => {}
         Err(ErrorImpl::Custom(msg)) => return Err(serde::ser::Error::custom(msg)),
     }
-    match erased {
-        erase::Serializer::Complete(ok) => Ok(ok),
-        erase::Serializer::Error(err) => Err(err),
-        _ => unreachable!(),
-    }
+    erased.into_result()
 }
 
 serialize_trait_object!(Serialize);
 
-struct MakeSerializer<TraitObject>(TraitObject);
+pub(crate) struct MakeSerializer<TraitObject>(pub(crate) TraitObject);
 
 impl<'a> serde::Serializer for MakeSerializer<&'a mut (dyn Serializer + '_)> {
     type Ok = ();
@@ -913,7 +1192,7 @@ impl<'a> serde::Serializer for MakeSerializer<&'a mut (dyn Serializer + '_)> {
     }
 }
 
-pub trait SerializeSeq {
+pub trait SerializeSeq: sealed::compound::Sealed {
     fn erased_serialize_element(&mut self, value: &dyn Serialize) -> Result<(), ErrorImpl>;
     fn erased_end(&mut self);
 }
@@ -923,22 +1202,22 @@ where
     T: serde::Serializer,
 {
     fn erased_serialize_element(&mut self, value: &dyn Serialize) -> Result<(), ErrorImpl> {
-        let erase::Serializer::Seq(serializer) = self else {
+        let erase::State::Seq(serializer) = &mut self.state else {
             unreachable!();
         };
         serializer.serialize_element(value).map_err(|err| {
-            *self = erase::Serializer::Error(err);
+            self.state = erase::State::Error(err);
             ShortCircuit
         })
     }
 
     fn erased_end(&mut self) {
-        let erase::Serializer::Seq(serializer) = self.take() else {
+        let erase::State::Seq(serializer) = self.take() else {
             unreachable!();
         };
-        *self = match serializer.end() {
-            Ok(ok) => erase::Serializer::Complete(ok),
-            Err(err) => erase::Serializer::Error(err),
+        self.state = match serializer.end() {
+            Ok(ok) => erase::State::Complete(ok),
+            Err(err) => erase::State::Error(err),
         };
     }
 }
@@ -960,7 +1239,7 @@ impl serde::ser::SerializeSeq for MakeSerializer<&mut dyn SerializeSeq> {
     }
 }
 
-pub trait SerializeTuple {
+pub trait SerializeTuple: sealed::compound::Sealed {
     fn erased_serialize_element(&mut self, value: &dyn Serialize) -> Result<(), ErrorImpl>;
     fn erased_end(&mut self);
 }
@@ -970,22 +1249,22 @@ where
     T: serde::Serializer,
 {
     fn erased_serialize_element(&mut self, value: &dyn Serialize) -> Result<(), ErrorImpl> {
-        let erase::Serializer::Tuple(serializer) = self else {
+        let erase::State::Tuple(serializer) = &mut self.state else {
             unreachable!();
         };
         serializer.serialize_element(value).map_err(|err| {
-            *self = erase::Serializer::Error(err);
+            self.state = erase::State::Error(err);
             ShortCircuit
         })
     }
 
     fn erased_end(&mut self) {
-        let erase::Serializer::Tuple(serializer) = self.take() else {
+        let erase::State::Tuple(serializer) = self.take() else {
             unreachable!();
         };
-        *self = match serializer.end() {
-            Ok(ok) => erase::Serializer::Complete(ok),
-            Err(err) => erase::Serializer::Error(err),
+        self.state = match serializer.end() {
+            Ok(ok) => erase::State::Complete(ok),
+            Err(err) => erase::State::Error(err),
         };
     }
 }
@@ -1007,7 +1286,7 @@ impl serde::ser::SerializeTuple for MakeSerializer<&mut dyn SerializeTuple> {
     }
 }
 
-pub trait SerializeTupleStruct {
+pub trait SerializeTupleStruct: sealed::compound::Sealed {
     fn erased_serialize_field(&mut self, value: &dyn Serialize) -> Result<(), ErrorImpl>;
     fn erased_end(&mut self);
 }
@@ -1017,22 +1296,22 @@ where
     T: serde::Serializer,
 {
     fn erased_serialize_field(&mut self, value: &dyn Serialize) -> Result<(), ErrorImpl> {
-        let erase::Serializer::TupleStruct(serializer) = self else {
+        let erase::State::TupleStruct(serializer) = &mut self.state else {
             unreachable!();
         };
         serializer.serialize_field(value).map_err(|err| {
-            *self = erase::Serializer::Error(err);
+            self.state = erase::State::Error(err);
             ShortCircuit
         })
     }
 
     fn erased_end(&mut self) {
-        let erase::Serializer::TupleStruct(serializer) = self.take() else {
+        let erase::State::TupleStruct(serializer) = self.take() else {
             unreachable!();
         };
-        *self = match serializer.end() {
-            Ok(ok) => erase::Serializer::Complete(ok),
-            Err(err) => erase::Serializer::Error(err),
+        self.state = match serializer.end() {
+            Ok(ok) => erase::State::Complete(ok),
+            Err(err) => erase::State::Error(err),
         };
     }
 }
@@ -1054,7 +1333,7 @@ impl serde::ser::SerializeTupleStruct for MakeSerializer<&mut dyn SerializeTuple
     }
 }
 
-pub trait SerializeTupleVariant {
+pub trait SerializeTupleVariant: sealed::compound::Sealed {
     fn erased_serialize_field(&mut self, value: &dyn Serialize) -> Result<(), ErrorImpl>;
     fn erased_end(&mut self);
 }
@@ -1064,22 +1343,22 @@ where
     T: serde::Serializer,
 {
     fn erased_serialize_field(&mut self, value: &dyn Serialize) -> Result<(), ErrorImpl> {
-        let erase::Serializer::TupleVariant(serializer) = self else {
+        let erase::State::TupleVariant(serializer) = &mut self.state else {
             unreachable!();
         };
         serializer.serialize_field(value).map_err(|err| {
-            *self = erase::Serializer::Error(err);
+            self.state = erase::State::Error(err);
             ShortCircuit
         })
     }
 
     fn erased_end(&mut self) {
-        let erase::Serializer::TupleVariant(serializer) = self.take() else {
+        let erase::State::TupleVariant(serializer) = self.take() else {
             unreachable!();
         };
-        *self = match serializer.end() {
-            Ok(ok) => erase::Serializer::Complete(ok),
-            Err(err) => erase::Serializer::Error(err),
+        self.state = match serializer.end() {
+            Ok(ok) => erase::State::Complete(ok),
+            Err(err) => erase::State::Error(err),
         };
     }
 }
@@ -1101,7 +1380,7 @@ impl serde::ser::SerializeTupleVariant for MakeSerializer<&mut dyn SerializeTupl
     }
 }
 
-pub trait SerializeMap {
+pub trait SerializeMap: sealed::compound::Sealed {
     fn erased_serialize_key(&mut self, key: &dyn Serialize) -> Result<(), ErrorImpl>;
     fn erased_serialize_value(&mut self, value: &dyn Serialize) -> Result<(), ErrorImpl>;
     fn erased_serialize_entry(
@@ -1117,21 +1396,21 @@ where
     T: serde::Serializer,
 {
     fn erased_serialize_key(&mut self, key: &dyn Serialize) -> Result<(), ErrorImpl> {
-        let erase::Serializer::Map(serializer) = self else {
+        let erase::State::Map(serializer) = &mut self.state else {
             unreachable!();
         };
         serializer.serialize_key(key).map_err(|err| {
-            *self = erase::Serializer::Error(err);
+            self.state = erase::State::Error(err);
             ShortCircuit
         })
     }
 
     fn erased_serialize_value(&mut self, value: &dyn Serialize) -> Result<(), ErrorImpl> {
-        let erase::Serializer::Map(serializer) = self else {
+        let erase::State::Map(serializer) = &mut self.state else {
             unreachable!();
         };
         serializer.serialize_value(value).map_err(|err| {
-            *self = erase::Serializer::Error(err);
+            self.state = erase::State::Error(err);
             ShortCircuit
         })
     }
@@ -1141,22 +1420,22 @@ where
         key: &dyn Serialize,
         value: &dyn Serialize,
     ) -> Result<(), ErrorImpl> {
-        let erase::Serializer::Map(serializer) = self else {
+        let erase::State::Map(serializer) = &mut self.state else {
             unreachable!();
         };
         serializer.serialize_entry(key, value).map_err(|err| {
-            *self = erase::Serializer::Error(err);
+            self.state = erase::State::Error(err);
             ShortCircuit
         })
     }
 
     fn erased_end(&mut self) {
-        let erase::Serializer::Map(serializer) = self.take() else {
+        let erase::State::Map(serializer) = self.take() else {
             unreachable!();
         };
-        *self = match serializer.end() {
-            Ok(ok) => erase::Serializer::Complete(ok),
-            Err(err) => erase::Serializer::Error(err),
+        self.state = match serializer.end() {
+            Ok(ok) => erase::State::Complete(ok),
+            Err(err) => erase::State::Error(err),
         };
     }
 }
@@ -1193,7 +1472,7 @@ impl serde::ser::SerializeMap for MakeSerializer<&mut dyn SerializeMap> {
     }
 }
 
-pub trait SerializeStruct {
+pub trait SerializeStruct: sealed::compound::Sealed {
     fn erased_serialize_field(
         &mut self,
         key: &'static str,
@@ -1212,32 +1491,32 @@ where
         key: &'static str,
         value: &dyn Serialize,
     ) -> Result<(), ErrorImpl> {
-        let erase::Serializer::Struct(serializer) = self else {
+        let erase::State::Struct(serializer) = &mut self.state else {
             unreachable!();
         };
         serializer.serialize_field(key, value).map_err(|err| {
-            *self = erase::Serializer::Error(err);
+            self.state = erase::State::Error(err);
             ShortCircuit
         })
     }
 
     fn erased_skip_field(&mut self, key: &'static str) -> Result<(), ErrorImpl> {
-        let erase::Serializer::Struct(serializer) = self else {
+        let erase::State::Struct(serializer) = &mut self.state else {
             unreachable!();
         };
         serializer.skip_field(key).map_err(|err| {
-            *self = erase::Serializer::Error(err);
+            self.state = erase::State::Error(err);
             ShortCircuit
         })
     }
 
     fn erased_end(&mut self) {
-        let erase::Serializer::Struct(serializer) = self.take() else {
+        let erase::State::Struct(serializer) = self.take() else {
             unreachable!();
         };
-        *self = match serializer.end() {
-            Ok(ok) => erase::Serializer::Complete(ok),
-            Err(err) => erase::Serializer::Error(err),
+        self.state = match serializer.end() {
+            Ok(ok) => erase::State::Complete(ok),
+            Err(err) => erase::State::Error(err),
         };
     }
 }
@@ -1263,7 +1542,7 @@ impl serde::ser::SerializeStruct for MakeSerializer<&mut dyn SerializeStruct> {
     }
 }
 
-pub trait SerializeStructVariant {
+pub trait SerializeStructVariant: sealed::compound::Sealed {
     fn erased_serialize_field(
         &mut self,
         key: &'static str,
@@ -1282,32 +1561,32 @@ where
         key: &'static str,
         value: &dyn Serialize,
     ) -> Result<(), ErrorImpl> {
-        let erase::Serializer::StructVariant(serializer) = self else {
+        let erase::State::StructVariant(serializer) = &mut self.state else {
             unreachable!();
         };
         serializer.serialize_field(key, value).map_err(|err| {
-            *self = erase::Serializer::Error(err);
+            self.state = erase::State::Error(err);
             ShortCircuit
         })
     }
 
     fn erased_skip_field(&mut self, key: &'static str) -> Result<(), ErrorImpl> {
-        let erase::Serializer::Struct(serializer) = self else {
+        let erase::State::StructVariant(serializer) = &mut self.state else {
             unreachable!();
         };
         serializer.skip_field(key).map_err(|err| {
-            *self = erase::Serializer::Error(err);
+            self.state = erase::State::Error(err);
             ShortCircuit
         })
     }
 
     fn erased_end(&mut self) {
-        let erase::Serializer::StructVariant(serializer) = self.take() else {
+        let erase::State::StructVariant(serializer) = self.take() else {
             unreachable!();
         };
-        *self = match serializer.end() {
-            Ok(ok) => erase::Serializer::Complete(ok),
-            Err(err) => erase::Serializer::Error(err),
+        self.state = match serializer.end() {
+            Ok(ok) => erase::State::Complete(ok),
+            Err(err) => erase::State::Error(err),
         };
     }
 }
@@ -1333,6 +1612,417 @@ impl serde::ser::SerializeStructVariant for MakeSerializer<&mut dyn SerializeStr
     }
 }
 
+// DRIVE ERASED SERDE BY HAND //////////////////////////////////////////////////
+
+/// The compound state of a `&mut dyn Serializer` which is being driven through
+/// Serde's `Serializer` trait.
+///
+/// This is the `SerializeSeq`, `SerializeStruct`, etc. type of the
+/// `serde::Serializer` impl for `&mut dyn erased_serde::Serializer`.
+pub struct Compound<'a, C: ?Sized>(&'a mut C);
+
+// Turns the failure of a compound value that was opened on `serializer`, if
+// any, into an error.
+pub(crate) fn opened(
+    serializer: &mut dyn Serializer,
+    error: Option<ErrorImpl>,
+) -> Result<(), Error> {
+    match error {
+        Some(error) => Err(take_error(serializer, error)),
+        None => Ok(()),
+    }
+}
+
+impl<C> Compound<'_, C>
+where
+    C: ?Sized + sealed::compound::Sealed,
+{
+    fn check(&mut self, result: Result<(), ErrorImpl>) -> Result<(), Error> {
+        result.map_err(|error| take_error(self.0.erased_serializer(), error))
+    }
+
+    fn finish(self) -> Result<(), Error> {
+        check_failed(self.0.erased_serializer())
+    }
+}
+
+// A value with no compound state, and the end of a compound value, leave any
+// error in the serializer. Hand it to the caller right away instead of only at
+// `into_result`.
+fn check_failed(serializer: &mut dyn Serializer) -> Result<(), Error> {
+    if serializer.erased_failed() {
+        Err(take_error(serializer, ShortCircuit))
+    } else {
+        Ok(())
+    }
+}
+
+impl<'a> serde::Serializer for &'a mut (dyn Serializer + '_) {
+    type Ok = ();
+    type Error = Error;
+    type SerializeSeq = Compound<'a, dyn SerializeSeq + 'a>;
+    type SerializeTuple = Compound<'a, dyn SerializeTuple + 'a>;
+    type SerializeTupleStruct = Compound<'a, dyn SerializeTupleStruct + 'a>;
+    type SerializeTupleVariant = Compound<'a, dyn SerializeTupleVariant + 'a>;
+    type SerializeMap = Compound<'a, dyn SerializeMap + 'a>;
+    type SerializeStruct = Compound<'a, dyn SerializeStruct + 'a>;
+    type SerializeStructVariant = Compound<'a, dyn SerializeStructVariant + 'a>;
+
+    fn serialize_bool(self, v: bool) -> Result<Self::Ok, Self::Error> {
+        self.erased_serialize_bool(v);
+        check_failed(self)
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<Self::Ok, Self::Error> {
+        self.erased_serialize_i8(v);
+        check_failed(self)
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<Self::Ok, Self::Error> {
+        self.erased_serialize_i16(v);
+        check_failed(self)
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<Self::Ok, Self::Error> {
+        self.erased_serialize_i32(v);
+        check_failed(self)
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<Self::Ok, Self::Error> {
+        self.erased_serialize_i64(v);
+        check_failed(self)
+    }
+
+    fn serialize_i128(self, v: i128) -> Result<Self::Ok, Self::Error> {
+        self.erased_serialize_i128(v);
+        check_failed(self)
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<Self::Ok, Self::Error> {
+        self.erased_serialize_u8(v);
+        check_failed(self)
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<Self::Ok, Self::Error> {
+        self.erased_serialize_u16(v);
+        check_failed(self)
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<Self::Ok, Self::Error> {
+        self.erased_serialize_u32(v);
+        check_failed(self)
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<Self::Ok, Self::Error> {
+        self.erased_serialize_u64(v);
+        check_failed(self)
+    }
+
+    fn serialize_u128(self, v: u128) -> Result<Self::Ok, Self::Error> {
+        self.erased_serialize_u128(v);
+        check_failed(self)
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<Self::Ok, Self::Error> {
+        self.erased_serialize_f32(v);
+        check_failed(self)
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<Self::Ok, Self::Error> {
+        self.erased_serialize_f64(v);
+        check_failed(self)
+    }
+
+    fn serialize_char(self, v: char) -> Result<Self::Ok, Self::Error> {
+        self.erased_serialize_char(v);
+        check_failed(self)
+    }
+
+    fn serialize_str(self, v: &str) -> Result<Self::Ok, Self::Error> {
+        self.erased_serialize_str(v);
+        check_failed(self)
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<Self::Ok, Self::Error> {
+        self.erased_serialize_bytes(v);
+        check_failed(self)
+    }
+
+    fn serialize_none(self) -> Result<Self::Ok, Self::Error> {
+        self.erased_serialize_none();
+        check_failed(self)
+    }
+
+    fn serialize_some<T>(self, value: &T) -> Result<Self::Ok, Self::Error>
+    where
+        T: ?Sized + serde::Serialize,
+    {
+        self.erased_serialize_some(&value);
+        check_failed(self)
+    }
+
+    fn serialize_unit(self) -> Result<Self::Ok, Self::Error> {
+        self.erased_serialize_unit();
+        check_failed(self)
+    }
+
+    fn serialize_unit_struct(self, name: &'static str) -> Result<Self::Ok, Self::Error> {
+        self.erased_serialize_unit_struct(name);
+        check_failed(self)
+    }
+
+    fn serialize_unit_variant(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+    ) -> Result<Self::Ok, Self::Error> {
+        self.erased_serialize_unit_variant(name, variant_index, variant);
+        check_failed(self)
+    }
+
+    fn serialize_newtype_struct<T>(
+        self,
+        name: &'static str,
+        value: &T,
+    ) -> Result<Self::Ok, Self::Error>
+    where
+        T: ?Sized + serde::Serialize,
+    {
+        self.erased_serialize_newtype_struct(name, &value);
+        check_failed(self)
+    }
+
+    fn serialize_newtype_variant<T>(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<Self::Ok, Self::Error>
+    where
+        T: ?Sized + serde::Serialize,
+    {
+        self.erased_serialize_newtype_variant(name, variant_index, variant, &value);
+        check_failed(self)
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
+        self.erased_open_seq(len).map(Compound)
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple, Self::Error> {
+        self.erased_open_tuple(len).map(Compound)
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleStruct, Self::Error> {
+        self.erased_open_tuple_struct(name, len).map(Compound)
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleVariant, Self::Error> {
+        self.erased_open_tuple_variant(name, variant_index, variant, len)
+            .map(Compound)
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<Self::SerializeMap, Self::Error> {
+        self.erased_open_map(len).map(Compound)
+    }
+
+    fn serialize_struct(
+        self,
+        name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStruct, Self::Error> {
+        self.erased_open_struct(name, len).map(Compound)
+    }
+
+    fn serialize_struct_variant(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStructVariant, Self::Error> {
+        self.erased_open_struct_variant(name, variant_index, variant, len)
+            .map(Compound)
+    }
+
+    #[cfg(not(feature = "alloc"))]
+    fn collect_str<T>(self, value: &T) -> Result<Self::Ok, Self::Error>
+    where
+        T: ?Sized + Display,
+    {
+        unreachable!()
+    }
+
+    fn is_human_readable(&self) -> bool {
+        self.erased_is_human_readable()
+    }
+}
+
+impl serde::ser::SerializeSeq for Compound<'_, dyn SerializeSeq + '_> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + serde::Serialize,
+    {
+        let result = self.0.erased_serialize_element(&value);
+        self.check(result)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        self.0.erased_end();
+        self.finish()
+    }
+}
+
+impl serde::ser::SerializeTuple for Compound<'_, dyn SerializeTuple + '_> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + serde::Serialize,
+    {
+        let result = self.0.erased_serialize_element(&value);
+        self.check(result)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        self.0.erased_end();
+        self.finish()
+    }
+}
+
+impl serde::ser::SerializeTupleStruct for Compound<'_, dyn SerializeTupleStruct + '_> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + serde::Serialize,
+    {
+        let result = self.0.erased_serialize_field(&value);
+        self.check(result)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        self.0.erased_end();
+        self.finish()
+    }
+}
+
+impl serde::ser::SerializeTupleVariant for Compound<'_, dyn SerializeTupleVariant + '_> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + serde::Serialize,
+    {
+        let result = self.0.erased_serialize_field(&value);
+        self.check(result)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        self.0.erased_end();
+        self.finish()
+    }
+}
+
+impl serde::ser::SerializeMap for Compound<'_, dyn SerializeMap + '_> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_key<T>(&mut self, key: &T) -> Result<(), Error>
+    where
+        T: ?Sized + serde::Serialize,
+    {
+        let result = self.0.erased_serialize_key(&key);
+        self.check(result)
+    }
+
+    fn serialize_value<T>(&mut self, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + serde::Serialize,
+    {
+        let result = self.0.erased_serialize_value(&value);
+        self.check(result)
+    }
+
+    fn serialize_entry<K, V>(&mut self, key: &K, value: &V) -> Result<(), Error>
+    where
+        K: ?Sized + serde::Serialize,
+        V: ?Sized + serde::Serialize,
+    {
+        let result = self.0.erased_serialize_entry(&key, &value);
+        self.check(result)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        self.0.erased_end();
+        self.finish()
+    }
+}
+
+impl serde::ser::SerializeStruct for Compound<'_, dyn SerializeStruct + '_> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + serde::Serialize,
+    {
+        let result = self.0.erased_serialize_field(key, &value);
+        self.check(result)
+    }
+
+    fn skip_field(&mut self, key: &'static str) -> Result<(), Error> {
+        let result = self.0.erased_skip_field(key);
+        self.check(result)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        self.0.erased_end();
+        self.finish()
+    }
+}
+
+impl serde::ser::SerializeStructVariant for Compound<'_, dyn SerializeStructVariant + '_> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + serde::Serialize,
+    {
+        let result = self.0.erased_serialize_field(key, &value);
+        self.check(result)
+    }
+
+    fn skip_field(&mut self, key: &'static str) -> Result<(), Error> {
+        let result = self.0.erased_skip_field(key);
+        self.check(result)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        self.0.erased_end();
+        self.finish()
+    }
+}
+
 // IMPL ERASED SERDE FOR ERASED SERDE //////////////////////////////////////////
 
 macro_rules! deref_erased_serializer {
@@ -1465,6 +2155,46 @@ macro_rules! deref_erased_serializer {
             fn erased_display_error(&self) -> &dyn Display {
                 (**self).erased_display_error()
             }
+
+            fn erased_preserved_error(&mut self) -> Option<Error> {
+                (**self).erased_preserved_error()
+            }
+
+            fn erased_failed(&self) -> bool {
+                (**self).erased_failed()
+            }
+
+            fn erased_open_seq(&mut self, len: Option<usize>) -> Result<&mut dyn SerializeSeq, Error> {
+                (**self).erased_open_seq(len)
+            }
+
+            fn erased_open_tuple(&mut self, len: usize) -> Result<&mut dyn SerializeTuple, Error> {
+                (**self).erased_open_tuple(len)
+            }
+
+            fn erased_open_tuple_struct(&mut self, name: &'static str, len: usize) -> Result<&mut dyn SerializeTupleStruct, Error> {
+                (**self).erased_open_tuple_struct(name, len)
+            }
+
+            fn erased_open_tuple_variant(&mut self, name: &'static str, variant_index: u32, variant: &'static str, len: usize) -> Result<&mut dyn SerializeTupleVariant, Error> {
+                (**self).erased_open_tuple_variant(name, variant_index, variant, len)
+            }
+
+            fn erased_open_map(&mut self, len: Option<usize>) -> Result<&mut dyn SerializeMap, Error> {
+                (**self).erased_open_map(len)
+            }
+
+            fn erased_open_struct(&mut self, name: &'static str, len: usize) -> Result<&mut dyn SerializeStruct, Error> {
+                (**self).erased_open_struct(name, len)
+            }
+
+            fn erased_open_struct_variant(&mut self, name: &'static str, variant_index: u32, variant: &'static str, len: usize) -> Result<&mut dyn SerializeStructVariant, Error> {
+                (**self).erased_open_struct_variant(name, variant_index, variant, len)
+            }
+
+            fn erased_as_serializer(&mut self) -> &mut dyn Serializer {
+                self
+            }
         }
 
         impl<$T> sealed::serializer::Sealed for $ty $(where $($where)*)? {}
@@ -1552,6 +2282,27 @@ mod tests {
         test_json(E::Struct { t: true, f: false });
     }
 
+    #[test]
+    fn test_struct_variant_skip_field() {
+        #[derive(Serialize)]
+        enum E {
+            Struct {
+                t: bool,
+                #[serde(skip_serializing_if = "Option::is_none")]
+                skipped: Option<bool>,
+            },
+        }
+
+        test_json(E::Struct {
+            t: true,
+            skipped: None,
+        });
+        test_json(E::Struct {
+            t: true,
+            skipped: Some(false),
+        });
+    }
+
     #[test]
     fn test_error_custom() {
         struct Kaboom;
@@ -1573,6 +2324,91 @@ mod tests {
         assert_eq!(err.to_string(), "kaboom");
     }
 
+    #[test]
+    fn test_take_ok() {
+        let mut erased = <dyn Serializer>::erase(serde_json::value::Serializer);
+        assert!(erased.take_ok().is_none());
+
+        let obj: &dyn Serialize = &vec!["a"];
+        obj.erased_serialize(&mut erased).unwrap();
+        assert_eq!(erased.take_ok().unwrap(), serde_json::json!(["a"]));
+    }
+
+    #[test]
+    fn test_into_result_incomplete() {
+        let erased = <dyn Serializer>::erase(serde_json::value::Serializer);
+        let err = erased.into_result().unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "erased serializer has not completed serialization",
+        );
+    }
+
+    #[test]
+    fn test_drive_by_hand() {
+        use alloc::collections::BTreeMap as Map;
+        use serde::ser::{SerializeMap as _, SerializeSeq as _, Serializer as _};
+
+        let mut erased = <dyn Serializer>::erase(serde_json::value::Serializer);
+        let serializer: &mut dyn Serializer = &mut erased;
+        let mut seq = serializer.serialize_seq(None).unwrap();
+        seq.serialize_element(&1).unwrap();
+        seq.serialize_element(&"a").unwrap();
+        seq.end().unwrap();
+        assert_eq!(erased.into_result().unwrap(), serde_json::json!([1, "a"]));
+
+        let json = &mut serde_json::Serializer::new(Vec::new());
+        let mut erased = <dyn Serializer>::erase_preserving_errors(json);
+        let serializer: &mut dyn Serializer = &mut erased;
+        let mut map = serializer.serialize_map(Some(1)).unwrap();
+        let err = map
+            .serialize_entry("k", &Map::from([(vec![1], 1)]))
+            .unwrap_err();
+        assert_eq!(err.to_string(), "key must be a string");
+        assert!(err.downcast_ref::<serde_json::Error>().is_some());
+    }
+
+    #[test]
+    fn test_drive_by_hand_errors() {
+        use serde::ser::{SerializeSeq as _, Serializer as _};
+        use std::io;
+
+        struct Limited(usize);
+
+        impl io::Write for Limited {
+            fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+                if buf.len() > self.0 {
+                    return Err(io::Error::new(io::ErrorKind::Other, "full"));
+                }
+                self.0 -= buf.len();
+                Ok(buf.len())
+            }
+
+            fn flush(&mut self) -> io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let json = &mut serde_json::Serializer::new(Limited(0));
+        let mut erased = <dyn Serializer>::erase(json);
+        let serializer: &mut dyn Serializer = &mut erased;
+        let err = serializer.serialize_bool(true).unwrap_err();
+        assert_eq!(err.to_string(), "full");
+
+        let json = &mut serde_json::Serializer::new(Limited(0));
+        let mut erased = <dyn Serializer>::erase(json);
+        let serializer: &mut dyn Serializer = &mut erased;
+        let err = serializer.serialize_seq(Some(1)).err().unwrap();
+        assert_eq!(err.to_string(), "full");
+
+        let json = &mut serde_json::Serializer::new(Limited(2));
+        let mut erased = <dyn Serializer>::erase(json);
+        let serializer: &mut dyn Serializer = &mut erased;
+        let mut seq = serializer.serialize_seq(Some(1)).unwrap();
+        seq.serialize_element(&1).unwrap();
+        assert_eq!(seq.end().unwrap_err().to_string(), "full");
+    }
+
     #[test]
     fn assert_serialize() {
         fn assert<T: serde::Serialize>() {}
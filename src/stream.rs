@@ -0,0 +1,224 @@
+use crate::de::{erase, Deserializer};
+use crate::error::Error;
+use alloc::boxed::Box;
+
+/// An object-safe source of several values in a row, each read through its
+/// own `dyn Deserializer`.
+///
+/// A `dyn Deserializer` is used up by the one value it deserializes, so a
+/// format holding a sequence of values, like newline-delimited JSON or a CBOR
+/// sequence, cannot be read through it. A `StreamDeserializer` instead hands
+/// out a deserializer for the next value each time it is asked, until the input
+/// runs out.
+///
+/// Formats whose deserializer reads one value per call through a mutable
+/// reference, like `serde_json::Deserializer` and `serde_cbor::Deserializer`,
+/// are converted with [`<dyn StreamDeserializer>::erase`][erase]. Other sources
+/// of values, such as separately framed messages, can implement this trait
+/// directly.
+///
+/// [erase]: trait.StreamDeserializer.html#method.erase
+///
+/// ```
+/// use erased_serde::StreamDeserializer;
+/// use serde_derive::Deserialize;
+///
+/// #[derive(Deserialize, Debug)]
+/// struct Event {
+///     id: u32,
+/// }
+///
+/// let input = "{\"id\": 1}\n{\"id\": 2}\n";
+/// let json = serde_json::Deserializer::from_str(input);
+/// let mut stream: Box<dyn StreamDeserializer> =
+///     Box::new(<dyn StreamDeserializer>::erase(json, |json| json.end().is_ok()));
+///
+/// let mut ids = Vec::new();
+/// while let Some(event) = stream.next::<Event>().unwrap() {
+///     ids.push(event.id);
+/// }
+/// assert_eq!(ids, [1, 2]);
+/// ```
+pub trait StreamDeserializer<'de> {
+    /// A deserializer for the next value, or `None` if there are no more.
+    fn erased_next(&mut self) -> Result<Option<&mut dyn Deserializer<'de>>, Error>;
+}
+
+impl<'de> dyn StreamDeserializer<'de> + '_ {
+    return_impl_trait! {
+        /// Convert a Serde `Deserializer` that is used through a mutable
+        /// reference to a stream of the values it holds one after another.
+        ///
+        /// The `at_end` closure reports whether the input has been used up,
+        /// without consuming any of the next value. For `serde_json` this is
+        /// `|json| json.end().is_ok()`, and for `serde_cbor` reading from a
+        /// slice it is `|cbor| cbor.byte_offset() == input.len()`.
+        pub fn erase<D, F>(deserializer: D, at_end: F) -> impl StreamDeserializer<'de> [Stream<D, F>]
+        where
+            for<'b> &'b mut D: serde::Deserializer<'de>,
+            F: FnMut(&mut D) -> bool,
+        {
+            Stream {
+                deserializer: erase::OwnedDeserializer::new(deserializer),
+                at_end,
+            }
+        }
+    }
+
+    /// Deserialize the next value, or return `None` if there are no more.
+    #[allow(clippy::should_implement_trait)]
+    pub fn next<T>(&mut self) -> Result<Option<T>, Error>
+    where
+        T: serde::Deserialize<'de>,
+    {
+        match self.erased_next()? {
+            Some(deserializer) => T::deserialize(deserializer).map(Some),
+            None => Ok(None),
+        }
+    }
+}
+
+pub struct Stream<D, F> {
+    deserializer: erase::OwnedDeserializer<D>,
+    at_end: F,
+}
+
+impl<'de, D, F> StreamDeserializer<'de> for Stream<D, F>
+where
+    for<'a> &'a mut D: serde::Deserializer<'de>,
+    F: FnMut(&mut D) -> bool,
+{
+    fn erased_next(&mut self) -> Result<Option<&mut dyn Deserializer<'de>>, Error> {
+        if (self.at_end)(&mut self.deserializer.deserializer) {
+            Ok(None)
+        } else {
+            Ok(Some(&mut self.deserializer))
+        }
+    }
+}
+
+impl<'de, T> StreamDeserializer<'de> for &mut T
+where
+    T: ?Sized + StreamDeserializer<'de>,
+{
+    fn erased_next(&mut self) -> Result<Option<&mut dyn Deserializer<'de>>, Error> {
+        (**self).erased_next()
+    }
+}
+
+impl<'de, T> StreamDeserializer<'de> for Box<T>
+where
+    T: ?Sized + StreamDeserializer<'de>,
+{
+    fn erased_next(&mut self) -> Result<Option<&mut dyn Deserializer<'de>>, Error> {
+        (**self).erased_next()
+    }
+}
+
+// TEST ////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use crate::{Deserializer, Error, StreamDeserializer};
+    use alloc::borrow::ToOwned;
+    use alloc::boxed::Box;
+    use alloc::string::{String, ToString};
+    use alloc::vec::Vec;
+    use serde_derive::{Deserialize, Serialize};
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    struct Event {
+        id: u32,
+        name: String,
+    }
+
+    fn event(id: u32, name: &str) -> Event {
+        Event {
+            id,
+            name: name.to_owned(),
+        }
+    }
+
+    fn collect(stream: &mut dyn StreamDeserializer) -> Result<Vec<Event>, Error> {
+        let mut events = Vec::new();
+        while let Some(event) = stream.next()? {
+            events.push(event);
+        }
+        Ok(events)
+    }
+
+    #[test]
+    fn test_ndjson() {
+        let input = "{\"id\": 1, \"name\": \"a\"}\n{\"id\": 2, \"name\": \"b\"}\n\n";
+        let json = serde_json::Deserializer::from_str(input);
+        let mut stream = <dyn StreamDeserializer>::erase(json, |json| json.end().is_ok());
+        let events = collect(&mut stream).unwrap();
+        assert_eq!(events, [event(1, "a"), event(2, "b")]);
+
+        // The stream stays at its end.
+        assert!(stream.erased_next().unwrap().is_none());
+    }
+
+    #[test]
+    fn test_cbor_sequence() {
+        let mut input = Vec::new();
+        for event in [event(1, "a"), event(2, "b"), event(3, "c")] {
+            input.extend(serde_cbor::to_vec(&event).unwrap());
+        }
+        let cbor = serde_cbor::Deserializer::from_slice(&input);
+        let at_end = |cbor: &mut serde_cbor::Deserializer<_>| cbor.byte_offset() == input.len();
+        let mut stream: Box<dyn StreamDeserializer> =
+            Box::new(<dyn StreamDeserializer>::erase(cbor, at_end));
+        let events = collect(&mut stream).unwrap();
+        assert_eq!(events, [event(1, "a"), event(2, "b"), event(3, "c")]);
+    }
+
+    #[test]
+    fn test_empty() {
+        let json = serde_json::Deserializer::from_str("  ");
+        let mut stream = <dyn StreamDeserializer>::erase(json, |json| json.end().is_ok());
+        assert!(collect(&mut stream).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_error() {
+        let input = "{\"id\": 1, \"name\": \"a\"}\n{\"id\": \"2\"}\n";
+        let json = serde_json::Deserializer::from_str(input);
+        let mut stream = <dyn StreamDeserializer>::erase(json, |json| json.end().is_ok());
+        let error = collect(&mut stream).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "invalid type: string \"2\", expected u32 at line 2 column 10",
+        );
+    }
+
+    // Messages that arrive separately framed, each in its own buffer.
+    struct Frames<'de> {
+        frames: core::slice::Iter<'de, &'de str>,
+        current: Option<Box<dyn Deserializer<'de> + 'de>>,
+    }
+
+    impl<'de> StreamDeserializer<'de> for Frames<'de> {
+        fn erased_next(&mut self) -> Result<Option<&mut dyn Deserializer<'de>>, Error> {
+            let Some(frame) = self.frames.next() else {
+                return Ok(None);
+            };
+            let json = serde_json::Deserializer::from_str(frame);
+            let current = self
+                .current
+                .insert(Box::new(<dyn Deserializer>::erase_owned(json)));
+            Ok(Some(current))
+        }
+    }
+
+    #[test]
+    fn test_frames() {
+        let frames = [r#"{"id": 1, "name": "a"}"#, r#"{"id": 2, "name": "b"}"#];
+        let mut stream = Frames {
+            frames: frames.iter(),
+            current: None,
+        };
+        let events = collect(&mut stream).unwrap();
+        assert_eq!(events, [event(1, "a"), event(2, "b")]);
+    }
+}
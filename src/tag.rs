@@ -0,0 +1,458 @@
+use crate::ser::Serialize;
+use serde::ser::{Error as _, Impossible, SerializeMap as _, SerializeStruct as _};
+
+// Not public API. How serialize_trait_object! wraps each value with its tag.
+#[doc(hidden)]
+pub enum Tagging {
+    External,
+    Internal {
+        tag: &'static str,
+    },
+    Adjacent {
+        object: &'static str,
+        tag: &'static str,
+        content: &'static str,
+    },
+}
+
+// Not public API. Used by serialize_trait_object!.
+#[doc(hidden)]
+pub fn serialize_tagged<T, S>(
+    value: &T,
+    name: &str,
+    tagging: Tagging,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    T: ?Sized + Serialize,
+    S: serde::Serializer,
+{
+    match tagging {
+        Tagging::External => {
+            let mut map = serializer.serialize_map(Some(1))?;
+            map.serialize_entry(name, &Erased(value))?;
+            map.end()
+        }
+        Tagging::Internal { tag } => crate::serialize(
+            value,
+            InternallyTagged {
+                tag,
+                name,
+                delegate: serializer,
+            },
+        ),
+        Tagging::Adjacent {
+            object,
+            tag,
+            content,
+        } => {
+            let mut state = serializer.serialize_struct(object, 2)?;
+            state.serialize_field(tag, name)?;
+            state.serialize_field(content, &Erased(value))?;
+            state.end()
+        }
+    }
+}
+
+struct Erased<'a, T: ?Sized>(&'a T);
+
+impl<T> serde::Serialize for Erased<'_, T>
+where
+    T: ?Sized + Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        crate::serialize(self.0, serializer)
+    }
+}
+
+// Adds the tag as the first entry of the map or struct that the value
+// serializes as. Anything else has nowhere to put the tag.
+struct InternallyTagged<'a, S> {
+    tag: &'static str,
+    name: &'a str,
+    delegate: S,
+}
+
+impl<S> InternallyTagged<'_, S>
+where
+    S: serde::Serializer,
+{
+    fn bad_type(self, what: &str) -> S::Error {
+        S::Error::custom(format_args!(
+            "cannot serialize {} with internal tag `{}` because it is {}",
+            self.name, self.tag, what,
+        ))
+    }
+}
+
+impl<S> serde::Serializer for InternallyTagged<'_, S>
+where
+    S: serde::Serializer,
+{
+    type Ok = S::Ok;
+    type Error = S::Error;
+    type SerializeSeq = Impossible<S::Ok, S::Error>;
+    type SerializeTuple = Impossible<S::Ok, S::Error>;
+    type SerializeTupleStruct = Impossible<S::Ok, S::Error>;
+    type SerializeTupleVariant = Impossible<S::Ok, S::Error>;
+    type SerializeMap = S::SerializeMap;
+    type SerializeStruct = S::SerializeStruct;
+    type SerializeStructVariant = Impossible<S::Ok, S::Error>;
+
+    fn serialize_bool(self, _v: bool) -> Result<S::Ok, S::Error> {
+        Err(self.bad_type("a boolean"))
+    }
+
+    fn serialize_i8(self, _v: i8) -> Result<S::Ok, S::Error> {
+        Err(self.bad_type("an integer"))
+    }
+
+    fn serialize_i16(self, _v: i16) -> Result<S::Ok, S::Error> {
+        Err(self.bad_type("an integer"))
+    }
+
+    fn serialize_i32(self, _v: i32) -> Result<S::Ok, S::Error> {
+        Err(self.bad_type("an integer"))
+    }
+
+    fn serialize_i64(self, _v: i64) -> Result<S::Ok, S::Error> {
+        Err(self.bad_type("an integer"))
+    }
+
+    fn serialize_i128(self, _v: i128) -> Result<S::Ok, S::Error> {
+        Err(self.bad_type("an integer"))
+    }
+
+    fn serialize_u8(self, _v: u8) -> Result<S::Ok, S::Error> {
+        Err(self.bad_type("an integer"))
+    }
+
+    fn serialize_u16(self, _v: u16) -> Result<S::Ok, S::Error> {
+        Err(self.bad_type("an integer"))
+    }
+
+    fn serialize_u32(self, _v: u32) -> Result<S::Ok, S::Error> {
+        Err(self.bad_type("an integer"))
+    }
+
+    fn serialize_u64(self, _v: u64) -> Result<S::Ok, S::Error> {
+        Err(self.bad_type("an integer"))
+    }
+
+    fn serialize_u128(self, _v: u128) -> Result<S::Ok, S::Error> {
+        Err(self.bad_type("an integer"))
+    }
+
+    fn serialize_f32(self, _v: f32) -> Result<S::Ok, S::Error> {
+        Err(self.bad_type("a float"))
+    }
+
+    fn serialize_f64(self, _v: f64) -> Result<S::Ok, S::Error> {
+        Err(self.bad_type("a float"))
+    }
+
+    fn serialize_char(self, _v: char) -> Result<S::Ok, S::Error> {
+        Err(self.bad_type("a char"))
+    }
+
+    fn serialize_str(self, _v: &str) -> Result<S::Ok, S::Error> {
+        Err(self.bad_type("a string"))
+    }
+
+    fn serialize_bytes(self, _v: &[u8]) -> Result<S::Ok, S::Error> {
+        Err(self.bad_type("a byte array"))
+    }
+
+    fn serialize_none(self) -> Result<S::Ok, S::Error> {
+        Err(self.bad_type("an optional"))
+    }
+
+    fn serialize_some<T>(self, _value: &T) -> Result<S::Ok, S::Error>
+    where
+        T: ?Sized + serde::Serialize,
+    {
+        Err(self.bad_type("an optional"))
+    }
+
+    fn serialize_unit(self) -> Result<S::Ok, S::Error> {
+        let mut map = self.delegate.serialize_map(Some(1))?;
+        map.serialize_entry(self.tag, self.name)?;
+        map.end()
+    }
+
+    fn serialize_unit_struct(self, name: &'static str) -> Result<S::Ok, S::Error> {
+        let mut state = self.delegate.serialize_struct(name, 1)?;
+        state.serialize_field(self.tag, self.name)?;
+        state.end()
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<S::Ok, S::Error> {
+        let mut map = self.delegate.serialize_map(Some(2))?;
+        map.serialize_entry(self.tag, self.name)?;
+        map.serialize_entry(variant, &())?;
+        map.end()
+    }
+
+    fn serialize_newtype_struct<T>(self, _name: &'static str, value: &T) -> Result<S::Ok, S::Error>
+    where
+        T: ?Sized + serde::Serialize,
+    {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<S::Ok, S::Error>
+    where
+        T: ?Sized + serde::Serialize,
+    {
+        let mut map = self.delegate.serialize_map(Some(2))?;
+        map.serialize_entry(self.tag, self.name)?;
+        map.serialize_entry(variant, value)?;
+        map.end()
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq, S::Error> {
+        Err(self.bad_type("a sequence"))
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple, S::Error> {
+        Err(self.bad_type("a tuple"))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleStruct, S::Error> {
+        Err(self.bad_type("a tuple struct"))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant, S::Error> {
+        Err(self.bad_type("a tuple variant"))
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<S::SerializeMap, S::Error> {
+        let mut map = self.delegate.serialize_map(len.map(|len| len + 1))?;
+        map.serialize_entry(self.tag, self.name)?;
+        Ok(map)
+    }
+
+    fn serialize_struct(
+        self,
+        name: &'static str,
+        len: usize,
+    ) -> Result<S::SerializeStruct, S::Error> {
+        let mut state = self.delegate.serialize_struct(name, len + 1)?;
+        state.serialize_field(self.tag, self.name)?;
+        Ok(state)
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant, S::Error> {
+        Err(self.bad_type("a struct variant"))
+    }
+
+    fn is_human_readable(&self) -> bool {
+        self.delegate.is_human_readable()
+    }
+}
+
+// TEST ////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use crate::{Serialize, Value};
+    use alloc::borrow::ToOwned;
+    use alloc::boxed::Box;
+    use alloc::string::{String, ToString};
+    use alloc::vec;
+    use alloc::vec::Vec;
+    use serde_derive::Serialize;
+    use serde_json::json;
+
+    #[derive(Serialize)]
+    struct Login {
+        user: String,
+    }
+
+    #[derive(Serialize)]
+    struct Logout;
+
+    #[derive(Serialize)]
+    struct Wrapped(Login);
+
+    #[derive(Serialize)]
+    struct Batch(Vec<u8>);
+
+    #[derive(Serialize)]
+    struct Count(u128);
+
+    trait Event: Serialize {
+        fn name(&self) -> String;
+    }
+
+    impl Event for Login {
+        fn name(&self) -> String {
+            "Login".to_owned()
+        }
+    }
+
+    impl Event for Logout {
+        fn name(&self) -> String {
+            "Logout".to_owned()
+        }
+    }
+
+    impl Event for Wrapped {
+        fn name(&self) -> String {
+            "Wrapped".to_owned()
+        }
+    }
+
+    impl Event for Batch {
+        fn name(&self) -> String {
+            "Batch".to_owned()
+        }
+    }
+
+    impl Event for Count {
+        fn name(&self) -> String {
+            "Count".to_owned()
+        }
+    }
+
+    fn login() -> Login {
+        Login {
+            user: "alice".to_owned(),
+        }
+    }
+
+    #[test]
+    fn test_external() {
+        trait External: Serialize {}
+        impl External for Login {}
+
+        serialize_trait_object!(
+            #[external]
+            External
+        );
+
+        let event: Box<dyn External> = Box::new(login());
+        let expected = json!({
+            "erased_serde::tag::tests::Login": {"user": "alice"},
+        });
+        assert_eq!(serde_json::to_value(&event).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_internal() {
+        trait Internal: Event {}
+        impl Internal for Login {}
+        impl Internal for Logout {}
+        impl Internal for Wrapped {}
+        impl Internal for Batch {}
+        impl Internal for Count {}
+
+        serialize_trait_object!(
+            #[internal(tag = "type", name = name)]
+            Internal
+        );
+
+        let events: Vec<Box<dyn Internal>> = vec![
+            Box::new(login()),
+            Box::new(Logout),
+            Box::new(Wrapped(login())),
+        ];
+        let expected = json!([
+            {"type": "Login", "user": "alice"},
+            {"type": "Logout"},
+            {"type": "Wrapped", "user": "alice"},
+        ]);
+        assert_eq!(serde_json::to_value(&events).unwrap(), expected);
+
+        let event: Box<dyn Internal> = Box::new(Batch(vec![1, 2]));
+        let err = serde_json::to_value(&event).unwrap_err();
+        let expected = "cannot serialize Batch with internal tag `type` because it is a sequence";
+        assert_eq!(err.to_string(), expected);
+
+        let event: Box<dyn Internal> = Box::new(Count(1));
+        let err = serde_json::to_value(&event).unwrap_err();
+        let expected = "cannot serialize Count with internal tag `type` because it is an integer";
+        assert_eq!(err.to_string(), expected);
+    }
+
+    #[test]
+    fn test_adjacent() {
+        trait Adjacent: Event {}
+        impl Adjacent for Login {}
+        impl Adjacent for Batch {}
+
+        serialize_trait_object!(
+            #[adjacent(tag = "t", content = "c", name = name)]
+            Adjacent
+        );
+
+        let events: Vec<Box<dyn Adjacent + Send>> =
+            vec![Box::new(login()), Box::new(Batch(vec![1, 2]))];
+        let expected = json!([
+            {"t": "Login", "c": {"user": "alice"}},
+            {"t": "Batch", "c": [1, 2]},
+        ]);
+        assert_eq!(serde_json::to_value(&events).unwrap(), expected);
+
+        let value = crate::to_value(&events[0]).unwrap();
+        let Value::Struct { name, .. } = value else {
+            panic!("expected a struct, got {:?}", value);
+        };
+        assert_eq!(name, "Adjacent");
+    }
+
+    #[test]
+    fn test_adjacent_generic_name() {
+        mod handlers {
+            pub trait Handler<'a>: super::Event {
+                type Output;
+            }
+        }
+
+        impl handlers::Handler<'_> for Login {
+            type Output = ();
+        }
+
+        serialize_trait_object!(
+            #[adjacent(tag = "t", content = "c", name = name)]
+            <'a> handlers::Handler<'a, Output = ()>
+        );
+
+        let event: Box<dyn handlers::Handler<Output = ()>> = Box::new(login());
+        let value = crate::to_value(&event).unwrap();
+        let Value::Struct { name, .. } = value else {
+            panic!("expected a struct, got {:?}", value);
+        };
+        assert_eq!(name, "Handler");
+    }
+}
@@ -0,0 +1,497 @@
+use crate::de::Deserializer;
+use crate::error::Error;
+use crate::ser::{Serialize, Serializer};
+#[cfg(feature = "alloc")]
+use alloc::string::String;
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+use core::cell::Cell;
+use core::fmt::{self, Display};
+use serde::ser::{SerializeMap as _, SerializeSeq as _};
+
+/// Stream a value from a deserializer trait object straight into a serializer
+/// trait object.
+///
+/// Nothing is buffered beyond what the two formats themselves hold: every
+/// primitive, sequence element and map entry is forwarded to the serializer as
+/// soon as the deserializer produces it. Strings and bytes which the input
+/// lends out through `visit_borrowed_str` and `visit_borrowed_bytes` are passed
+/// along without being copied.
+///
+/// The input must be self-describing, since everything is read through
+/// `deserialize_any`. Enums which the input reports from `deserialize_any`, as
+/// formats with tagged values like YAML and RON do, cannot be transcoded and
+/// fail with an error: the serializer needs the variant name as a
+/// `&'static str`, while the input only lends it out for the duration of the
+/// call.
+///
+/// ```rust
+/// use erased_serde::{Deserializer, Serializer};
+///
+/// let json = br#"{"a": [1, true, null]}"#;
+/// let mut cbor = Vec::new();
+///
+/// let de = &mut serde_json::Deserializer::from_slice(json);
+/// let ser = &mut serde_cbor::Serializer::new(&mut cbor);
+/// erased_serde::transcode(
+///     &mut <dyn Deserializer>::erase(de),
+///     &mut <dyn Serializer>::erase(ser),
+/// )
+/// .unwrap();
+///
+/// let value: serde_json::Value = serde_cbor::from_slice(&cbor).unwrap();
+/// assert_eq!(value, serde_json::json!({"a": [1, true, null]}));
+/// ```
+pub fn transcode(
+    deserializer: &mut dyn Deserializer,
+    serializer: &mut dyn Serializer,
+) -> Result<(), Error> {
+    Transcoder::new(deserializer).erased_serialize(serializer)
+}
+
+// Deserializes its input at the moment it is asked to serialize itself.
+struct Transcoder<D>(Cell<Option<D>>);
+
+impl<D> Transcoder<D> {
+    fn new(deserializer: D) -> Self {
+        Transcoder(Cell::new(Some(deserializer)))
+    }
+}
+
+impl<'de, D> serde::Serialize for Transcoder<D>
+where
+    D: serde::Deserializer<'de>,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let Some(deserializer) = self.0.take() else {
+            return Err(serde::ser::Error::custom(
+                "transcoded value was serialized more than once",
+            ));
+        };
+        let mut error = None;
+        let visitor = Visitor {
+            serializer,
+            error: &mut error,
+        };
+        deserializer
+            .deserialize_any(visitor)
+            .map_err(|err| match error {
+                // The serializer failed. The deserializer only saw a placeholder.
+                Some(error) => error,
+                None => serde::ser::Error::custom(err),
+            })
+    }
+}
+
+// Serializer errors cannot travel through the deserializer's error type without
+// being rendered into a message, and some serializer errors have nothing to
+// render until the outermost layer asks for them. They are parked here instead
+// and the deserializer is handed this placeholder.
+struct SerializerFailed;
+
+impl Display for SerializerFailed {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("serializer failed")
+    }
+}
+
+fn park<S, E>(result: Result<S, E>, slot: &mut Option<E>) -> Result<S, ()> {
+    result.map_err(|err| *slot = Some(err))
+}
+
+struct Visitor<'a, S>
+where
+    S: serde::Serializer,
+{
+    serializer: S,
+    error: &'a mut Option<S::Error>,
+}
+
+impl<S> Visitor<'_, S>
+where
+    S: serde::Serializer,
+{
+    fn forward<E>(self, op: impl FnOnce(S) -> Result<S::Ok, S::Error>) -> Result<S::Ok, E>
+    where
+        E: serde::de::Error,
+    {
+        park(op(self.serializer), self.error).map_err(|()| E::custom(SerializerFailed))
+    }
+}
+
+impl<'de, S> serde::de::Visitor<'de> for Visitor<'_, S>
+where
+    S: serde::Serializer,
+{
+    type Value = S::Ok;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("any value")
+    }
+
+    fn visit_bool<E>(self, v: bool) -> Result<S::Ok, E>
+    where
+        E: serde::de::Error,
+    {
+        self.forward(|serializer| serializer.serialize_bool(v))
+    }
+
+    fn visit_i8<E>(self, v: i8) -> Result<S::Ok, E>
+    where
+        E: serde::de::Error,
+    {
+        self.forward(|serializer| serializer.serialize_i8(v))
+    }
+
+    fn visit_i16<E>(self, v: i16) -> Result<S::Ok, E>
+    where
+        E: serde::de::Error,
+    {
+        self.forward(|serializer| serializer.serialize_i16(v))
+    }
+
+    fn visit_i32<E>(self, v: i32) -> Result<S::Ok, E>
+    where
+        E: serde::de::Error,
+    {
+        self.forward(|serializer| serializer.serialize_i32(v))
+    }
+
+    fn visit_i64<E>(self, v: i64) -> Result<S::Ok, E>
+    where
+        E: serde::de::Error,
+    {
+        self.forward(|serializer| serializer.serialize_i64(v))
+    }
+
+    fn visit_i128<E>(self, v: i128) -> Result<S::Ok, E>
+    where
+        E: serde::de::Error,
+    {
+        self.forward(|serializer| serializer.serialize_i128(v))
+    }
+
+    fn visit_u8<E>(self, v: u8) -> Result<S::Ok, E>
+    where
+        E: serde::de::Error,
+    {
+        self.forward(|serializer| serializer.serialize_u8(v))
+    }
+
+    fn visit_u16<E>(self, v: u16) -> Result<S::Ok, E>
+    where
+        E: serde::de::Error,
+    {
+        self.forward(|serializer| serializer.serialize_u16(v))
+    }
+
+    fn visit_u32<E>(self, v: u32) -> Result<S::Ok, E>
+    where
+        E: serde::de::Error,
+    {
+        self.forward(|serializer| serializer.serialize_u32(v))
+    }
+
+    fn visit_u64<E>(self, v: u64) -> Result<S::Ok, E>
+    where
+        E: serde::de::Error,
+    {
+        self.forward(|serializer| serializer.serialize_u64(v))
+    }
+
+    fn visit_u128<E>(self, v: u128) -> Result<S::Ok, E>
+    where
+        E: serde::de::Error,
+    {
+        self.forward(|serializer| serializer.serialize_u128(v))
+    }
+
+    fn visit_f32<E>(self, v: f32) -> Result<S::Ok, E>
+    where
+        E: serde::de::Error,
+    {
+        self.forward(|serializer| serializer.serialize_f32(v))
+    }
+
+    fn visit_f64<E>(self, v: f64) -> Result<S::Ok, E>
+    where
+        E: serde::de::Error,
+    {
+        self.forward(|serializer| serializer.serialize_f64(v))
+    }
+
+    fn visit_char<E>(self, v: char) -> Result<S::Ok, E>
+    where
+        E: serde::de::Error,
+    {
+        self.forward(|serializer| serializer.serialize_char(v))
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<S::Ok, E>
+    where
+        E: serde::de::Error,
+    {
+        self.forward(|serializer| serializer.serialize_str(v))
+    }
+
+    fn visit_borrowed_str<E>(self, v: &'de str) -> Result<S::Ok, E>
+    where
+        E: serde::de::Error,
+    {
+        self.forward(|serializer| serializer.serialize_str(v))
+    }
+
+    #[cfg(feature = "alloc")]
+    fn visit_string<E>(self, v: String) -> Result<S::Ok, E>
+    where
+        E: serde::de::Error,
+    {
+        self.forward(|serializer| serializer.serialize_str(&v))
+    }
+
+    fn visit_bytes<E>(self, v: &[u8]) -> Result<S::Ok, E>
+    where
+        E: serde::de::Error,
+    {
+        self.forward(|serializer| serializer.serialize_bytes(v))
+    }
+
+    fn visit_borrowed_bytes<E>(self, v: &'de [u8]) -> Result<S::Ok, E>
+    where
+        E: serde::de::Error,
+    {
+        self.forward(|serializer| serializer.serialize_bytes(v))
+    }
+
+    #[cfg(feature = "alloc")]
+    fn visit_byte_buf<E>(self, v: Vec<u8>) -> Result<S::Ok, E>
+    where
+        E: serde::de::Error,
+    {
+        self.forward(|serializer| serializer.serialize_bytes(&v))
+    }
+
+    fn visit_none<E>(self) -> Result<S::Ok, E>
+    where
+        E: serde::de::Error,
+    {
+        self.forward(serde::Serializer::serialize_none)
+    }
+
+    fn visit_some<D>(self, deserializer: D) -> Result<S::Ok, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        self.forward(|serializer| serializer.serialize_some(&Transcoder::new(deserializer)))
+    }
+
+    fn visit_unit<E>(self) -> Result<S::Ok, E>
+    where
+        E: serde::de::Error,
+    {
+        self.forward(serde::Serializer::serialize_unit)
+    }
+
+    fn visit_newtype_struct<D>(self, deserializer: D) -> Result<S::Ok, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        self.forward(|serializer| {
+            serde::Serialize::serialize(&Transcoder::new(deserializer), serializer)
+        })
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<S::Ok, A::Error>
+    where
+        A: serde::de::SeqAccess<'de>,
+    {
+        let mut state = park(self.serializer.serialize_seq(seq.size_hint()), self.error)
+            .map_err(|()| serde::de::Error::custom(SerializerFailed))?;
+        while let Some(()) = seq.next_element_seed(ElementSeed {
+            state: &mut state,
+            error: &mut *self.error,
+        })? {}
+        park(state.end(), self.error).map_err(|()| serde::de::Error::custom(SerializerFailed))
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<S::Ok, A::Error>
+    where
+        A: serde::de::MapAccess<'de>,
+    {
+        let mut state = park(self.serializer.serialize_map(map.size_hint()), self.error)
+            .map_err(|()| serde::de::Error::custom(SerializerFailed))?;
+        while let Some(()) = map.next_key_seed(KeySeed {
+            state: &mut state,
+            error: &mut *self.error,
+        })? {
+            map.next_value_seed(ValueSeed {
+                state: &mut state,
+                error: &mut *self.error,
+            })?;
+        }
+        park(state.end(), self.error).map_err(|()| serde::de::Error::custom(SerializerFailed))
+    }
+
+    fn visit_enum<A>(self, _data: A) -> Result<S::Ok, A::Error>
+    where
+        A: serde::de::EnumAccess<'de>,
+    {
+        Err(serde::de::Error::custom(
+            "enums cannot be transcoded from deserialize_any",
+        ))
+    }
+}
+
+struct ElementSeed<'a, S>
+where
+    S: serde::ser::SerializeSeq,
+{
+    state: &'a mut S,
+    error: &'a mut Option<S::Error>,
+}
+
+impl<'de, S> serde::de::DeserializeSeed<'de> for ElementSeed<'_, S>
+where
+    S: serde::ser::SerializeSeq,
+{
+    type Value = ();
+
+    fn deserialize<D>(self, deserializer: D) -> Result<(), D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let result = self.state.serialize_element(&Transcoder::new(deserializer));
+        park(result, self.error).map_err(|()| serde::de::Error::custom(SerializerFailed))
+    }
+}
+
+struct KeySeed<'a, S>
+where
+    S: serde::ser::SerializeMap,
+{
+    state: &'a mut S,
+    error: &'a mut Option<S::Error>,
+}
+
+impl<'de, S> serde::de::DeserializeSeed<'de> for KeySeed<'_, S>
+where
+    S: serde::ser::SerializeMap,
+{
+    type Value = ();
+
+    fn deserialize<D>(self, deserializer: D) -> Result<(), D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let result = self.state.serialize_key(&Transcoder::new(deserializer));
+        park(result, self.error).map_err(|()| serde::de::Error::custom(SerializerFailed))
+    }
+}
+
+struct ValueSeed<'a, S>
+where
+    S: serde::ser::SerializeMap,
+{
+    state: &'a mut S,
+    error: &'a mut Option<S::Error>,
+}
+
+impl<'de, S> serde::de::DeserializeSeed<'de> for ValueSeed<'_, S>
+where
+    S: serde::ser::SerializeMap,
+{
+    type Value = ();
+
+    fn deserialize<D>(self, deserializer: D) -> Result<(), D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let result = self.state.serialize_value(&Transcoder::new(deserializer));
+        park(result, self.error).map_err(|()| serde::de::Error::custom(SerializerFailed))
+    }
+}
+
+// TEST ////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+    use alloc::vec::Vec;
+
+    fn json_to_json(input: &str) -> Result<Vec<u8>, Error> {
+        let mut output = Vec::new();
+        let de = &mut serde_json::Deserializer::from_str(input);
+        let ser = &mut serde_json::Serializer::new(&mut output);
+        transcode(
+            &mut <dyn Deserializer>::erase(de),
+            &mut <dyn Serializer>::erase(ser),
+        )?;
+        Ok(output)
+    }
+
+    #[test]
+    fn test_json() {
+        let input = r#"{"a":[1,-1,1.5,"s",null,true],"b":{"c":{}}}"#;
+        assert_eq!(json_to_json(input).unwrap(), input.as_bytes());
+    }
+
+    #[test]
+    fn test_deserializer_error() {
+        let err = json_to_json("[1, 2").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "EOF while parsing a list at line 1 column 5",
+        );
+    }
+
+    #[test]
+    fn test_serializer_error() {
+        // JSON object keys must be strings.
+        let mut output = Vec::new();
+        let de = &mut serde_cbor::Deserializer::from_slice(&[0xa1, 0x81, 0x01, 0x02]);
+        let ser = &mut serde_json::Serializer::new(&mut output);
+        let err = transcode(
+            &mut <dyn Deserializer>::erase(de),
+            &mut <dyn Serializer>::erase(ser),
+        )
+        .unwrap_err();
+        assert_eq!(err.to_string(), "key must be a string");
+    }
+
+    #[test]
+    fn test_enum() {
+        #[derive(serde_derive::Serialize)]
+        enum E {
+            Newtype(u8),
+        }
+
+        let value = crate::to_value(&E::Newtype(1)).unwrap();
+        let mut output = Vec::new();
+        let ser = &mut serde_json::Serializer::new(&mut output);
+        let err = transcode(
+            &mut <dyn Deserializer>::erase(value),
+            &mut <dyn Serializer>::erase(ser),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "enums cannot be transcoded from deserialize_any",
+        );
+    }
+
+    #[test]
+    fn test_serialize_twice() {
+        let de = &mut serde_json::Deserializer::from_str("1");
+        let transcoder = Transcoder::new(de);
+        assert_eq!(serde_json::to_string(&transcoder).unwrap(), "1");
+        let err = serde_json::to_string(&transcoder).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "transcoded value was serialized more than once",
+        );
+    }
+}
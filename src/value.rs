@@ -0,0 +1,1188 @@
+use crate::error::Error;
+use crate::ser::Serialize;
+use alloc::borrow::ToOwned;
+use alloc::boxed::Box;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt::Display;
+use serde::de::value::{BorrowedStrDeserializer, MapDeserializer, SeqDeserializer};
+use serde::de::{IntoDeserializer, Unexpected};
+use serde::ser::{
+    SerializeSeq as _, SerializeTuple as _, SerializeTupleStruct as _, SerializeTupleVariant as _,
+};
+
+/// An owned snapshot of the calls made by a `Serialize` impl.
+///
+/// Capturing a value with [`to_value`] records every method called on the
+/// serializer, including type names, variant names, length hints and skipped
+/// struct fields. Serializing the `Value` later replays exactly the same
+/// sequence of calls into whichever serializer is chosen at that point.
+///
+/// The captured data does not borrow from the original value, so `Value` is
+/// `'static + Send + Sync`.
+///
+/// `Value` is also a `serde::Deserializer`, so a buffered payload can be
+/// deserialized into any concrete type later, or erased with
+/// `<dyn erased_serde::Deserializer>::erase`. Bytes, 128-bit integers and enum
+/// structure survive the trip without loss.
+///
+/// ```rust
+/// use erased_serde::Serialize;
+///
+/// let boxed: Box<dyn Serialize> = Box::new(vec!["a", "b"]);
+///
+/// // Snapshot now...
+/// let value = erased_serde::to_value(&boxed).unwrap();
+/// drop(boxed);
+///
+/// // ...serialize later.
+/// let json = serde_json::to_string(&value).unwrap();
+/// assert_eq!(json, r#"["a","b"]"#);
+///
+/// // ...or deserialize it into something else entirely.
+/// let de = &mut <dyn erased_serde::Deserializer>::erase(value);
+/// let strings: Vec<String> = erased_serde::deserialize(de).unwrap();
+/// assert_eq!(strings, ["a", "b"]);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+    Bool(bool),
+    I8(i8),
+    I16(i16),
+    I32(i32),
+    I64(i64),
+    I128(i128),
+    U8(u8),
+    U16(u16),
+    U32(u32),
+    U64(u64),
+    U128(u128),
+    F32(f32),
+    F64(f64),
+    Char(char),
+    Str(String),
+    Bytes(Vec<u8>),
+    None,
+    Some(Box<Value>),
+    Unit,
+    UnitStruct(&'static str),
+    UnitVariant {
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+    },
+    NewtypeStruct(&'static str, Box<Value>),
+    NewtypeVariant {
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        value: Box<Value>,
+    },
+    Seq {
+        len: Option<usize>,
+        elements: Vec<Value>,
+    },
+    Tuple(Vec<Value>),
+    TupleStruct {
+        name: &'static str,
+        fields: Vec<Value>,
+    },
+    TupleVariant {
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        fields: Vec<Value>,
+    },
+    Map {
+        len: Option<usize>,
+        entries: Vec<MapEntry>,
+    },
+    Struct {
+        name: &'static str,
+        len: usize,
+        fields: Vec<StructField>,
+    },
+    StructVariant {
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        len: usize,
+        fields: Vec<StructField>,
+    },
+}
+
+/// One call made on `SerializeMap` while capturing a [`Value`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum MapEntry {
+    Key(Value),
+    Value(Value),
+    Entry(Value, Value),
+}
+
+/// One call made on `SerializeStruct` or `SerializeStructVariant` while
+/// capturing a [`Value`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum StructField {
+    Field(&'static str, Value),
+    Skip(&'static str),
+}
+
+/// Capture the given type-erased serializable value into an owned [`Value`].
+pub fn to_value<T>(value: &T) -> Result<Value, Error>
+where
+    T: ?Sized + Serialize,
+{
+    crate::ser::serialize(value, ValueSerializer)
+}
+
+// CAPTURE /////////////////////////////////////////////////////////////////////
+
+struct ValueSerializer;
+
+fn capture<T>(value: &T) -> Result<Value, Error>
+where
+    T: ?Sized + serde::Serialize,
+{
+    value.serialize(ValueSerializer)
+}
+
+impl serde::Serializer for ValueSerializer {
+    type Ok = Value;
+    type Error = Error;
+    type SerializeSeq = CaptureSeq;
+    type SerializeTuple = CaptureSeq;
+    type SerializeTupleStruct = CaptureSeq;
+    type SerializeTupleVariant = CaptureSeq;
+    type SerializeMap = CaptureMap;
+    type SerializeStruct = CaptureStruct;
+    type SerializeStructVariant = CaptureStruct;
+
+    fn serialize_bool(self, v: bool) -> Result<Value, Error> {
+        Ok(Value::Bool(v))
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<Value, Error> {
+        Ok(Value::I8(v))
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<Value, Error> {
+        Ok(Value::I16(v))
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<Value, Error> {
+        Ok(Value::I32(v))
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<Value, Error> {
+        Ok(Value::I64(v))
+    }
+
+    fn serialize_i128(self, v: i128) -> Result<Value, Error> {
+        Ok(Value::I128(v))
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<Value, Error> {
+        Ok(Value::U8(v))
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<Value, Error> {
+        Ok(Value::U16(v))
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<Value, Error> {
+        Ok(Value::U32(v))
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<Value, Error> {
+        Ok(Value::U64(v))
+    }
+
+    fn serialize_u128(self, v: u128) -> Result<Value, Error> {
+        Ok(Value::U128(v))
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<Value, Error> {
+        Ok(Value::F32(v))
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<Value, Error> {
+        Ok(Value::F64(v))
+    }
+
+    fn serialize_char(self, v: char) -> Result<Value, Error> {
+        Ok(Value::Char(v))
+    }
+
+    fn serialize_str(self, v: &str) -> Result<Value, Error> {
+        Ok(Value::Str(v.to_owned()))
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<Value, Error> {
+        Ok(Value::Bytes(v.to_owned()))
+    }
+
+    fn serialize_none(self) -> Result<Value, Error> {
+        Ok(Value::None)
+    }
+
+    fn serialize_some<T>(self, value: &T) -> Result<Value, Error>
+    where
+        T: ?Sized + serde::Serialize,
+    {
+        capture(value).map(|value| Value::Some(Box::new(value)))
+    }
+
+    fn serialize_unit(self) -> Result<Value, Error> {
+        Ok(Value::Unit)
+    }
+
+    fn serialize_unit_struct(self, name: &'static str) -> Result<Value, Error> {
+        Ok(Value::UnitStruct(name))
+    }
+
+    fn serialize_unit_variant(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+    ) -> Result<Value, Error> {
+        Ok(Value::UnitVariant {
+            name,
+            variant_index,
+            variant,
+        })
+    }
+
+    fn serialize_newtype_struct<T>(self, name: &'static str, value: &T) -> Result<Value, Error>
+    where
+        T: ?Sized + serde::Serialize,
+    {
+        capture(value).map(|value| Value::NewtypeStruct(name, Box::new(value)))
+    }
+
+    fn serialize_newtype_variant<T>(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<Value, Error>
+    where
+        T: ?Sized + serde::Serialize,
+    {
+        capture(value).map(|value| Value::NewtypeVariant {
+            name,
+            variant_index,
+            variant,
+            value: Box::new(value),
+        })
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<CaptureSeq, Error> {
+        Ok(CaptureSeq::new(SeqKind::Seq(len)))
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<CaptureSeq, Error> {
+        Ok(CaptureSeq::new(SeqKind::Tuple))
+    }
+
+    fn serialize_tuple_struct(self, name: &'static str, _len: usize) -> Result<CaptureSeq, Error> {
+        Ok(CaptureSeq::new(SeqKind::TupleStruct(name)))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<CaptureSeq, Error> {
+        Ok(CaptureSeq::new(SeqKind::TupleVariant(
+            name,
+            variant_index,
+            variant,
+        )))
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<CaptureMap, Error> {
+        Ok(CaptureMap {
+            len,
+            entries: Vec::new(),
+        })
+    }
+
+    fn serialize_struct(self, name: &'static str, len: usize) -> Result<CaptureStruct, Error> {
+        Ok(CaptureStruct {
+            variant: None,
+            name,
+            len,
+            fields: Vec::new(),
+        })
+    }
+
+    fn serialize_struct_variant(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<CaptureStruct, Error> {
+        Ok(CaptureStruct {
+            variant: Some((variant_index, variant)),
+            name,
+            len,
+            fields: Vec::new(),
+        })
+    }
+
+    fn collect_str<T>(self, value: &T) -> Result<Value, Error>
+    where
+        T: ?Sized + Display,
+    {
+        Ok(Value::Str(value.to_string()))
+    }
+}
+
+enum SeqKind {
+    Seq(Option<usize>),
+    Tuple,
+    TupleStruct(&'static str),
+    TupleVariant(&'static str, u32, &'static str),
+}
+
+struct CaptureSeq {
+    kind: SeqKind,
+    elements: Vec<Value>,
+}
+
+impl CaptureSeq {
+    fn new(kind: SeqKind) -> Self {
+        CaptureSeq {
+            kind,
+            elements: Vec::new(),
+        }
+    }
+
+    fn push<T>(&mut self, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + serde::Serialize,
+    {
+        self.elements.push(capture(value)?);
+        Ok(())
+    }
+
+    fn finish(self) -> Value {
+        let elements = self.elements;
+        match self.kind {
+            SeqKind::Seq(len) => Value::Seq { len, elements },
+            SeqKind::Tuple => Value::Tuple(elements),
+            SeqKind::TupleStruct(name) => Value::TupleStruct {
+                name,
+                fields: elements,
+            },
+            SeqKind::TupleVariant(name, variant_index, variant) => Value::TupleVariant {
+                name,
+                variant_index,
+                variant,
+                fields: elements,
+            },
+        }
+    }
+}
+
+impl serde::ser::SerializeSeq for CaptureSeq {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + serde::Serialize,
+    {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Value, Error> {
+        Ok(self.finish())
+    }
+}
+
+impl serde::ser::SerializeTuple for CaptureSeq {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + serde::Serialize,
+    {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Value, Error> {
+        Ok(self.finish())
+    }
+}
+
+impl serde::ser::SerializeTupleStruct for CaptureSeq {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + serde::Serialize,
+    {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Value, Error> {
+        Ok(self.finish())
+    }
+}
+
+impl serde::ser::SerializeTupleVariant for CaptureSeq {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + serde::Serialize,
+    {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Value, Error> {
+        Ok(self.finish())
+    }
+}
+
+struct CaptureMap {
+    len: Option<usize>,
+    entries: Vec<MapEntry>,
+}
+
+impl serde::ser::SerializeMap for CaptureMap {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_key<T>(&mut self, key: &T) -> Result<(), Error>
+    where
+        T: ?Sized + serde::Serialize,
+    {
+        self.entries.push(MapEntry::Key(capture(key)?));
+        Ok(())
+    }
+
+    fn serialize_value<T>(&mut self, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + serde::Serialize,
+    {
+        self.entries.push(MapEntry::Value(capture(value)?));
+        Ok(())
+    }
+
+    fn serialize_entry<K, V>(&mut self, key: &K, value: &V) -> Result<(), Error>
+    where
+        K: ?Sized + serde::Serialize,
+        V: ?Sized + serde::Serialize,
+    {
+        let key = capture(key)?;
+        let value = capture(value)?;
+        self.entries.push(MapEntry::Entry(key, value));
+        Ok(())
+    }
+
+    fn end(self) -> Result<Value, Error> {
+        Ok(Value::Map {
+            len: self.len,
+            entries: self.entries,
+        })
+    }
+}
+
+struct CaptureStruct {
+    variant: Option<(u32, &'static str)>,
+    name: &'static str,
+    len: usize,
+    fields: Vec<StructField>,
+}
+
+impl CaptureStruct {
+    fn push<T>(&mut self, key: &'static str, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + serde::Serialize,
+    {
+        self.fields.push(StructField::Field(key, capture(value)?));
+        Ok(())
+    }
+
+    fn finish(self) -> Value {
+        match self.variant {
+            None => Value::Struct {
+                name: self.name,
+                len: self.len,
+                fields: self.fields,
+            },
+            Some((variant_index, variant)) => Value::StructVariant {
+                name: self.name,
+                variant_index,
+                variant,
+                len: self.len,
+                fields: self.fields,
+            },
+        }
+    }
+}
+
+impl serde::ser::SerializeStruct for CaptureStruct {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + serde::Serialize,
+    {
+        self.push(key, value)
+    }
+
+    fn skip_field(&mut self, key: &'static str) -> Result<(), Error> {
+        self.fields.push(StructField::Skip(key));
+        Ok(())
+    }
+
+    fn end(self) -> Result<Value, Error> {
+        Ok(self.finish())
+    }
+}
+
+impl serde::ser::SerializeStructVariant for CaptureStruct {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + serde::Serialize,
+    {
+        self.push(key, value)
+    }
+
+    fn skip_field(&mut self, key: &'static str) -> Result<(), Error> {
+        self.fields.push(StructField::Skip(key));
+        Ok(())
+    }
+
+    fn end(self) -> Result<Value, Error> {
+        Ok(self.finish())
+    }
+}
+
+// REPLAY //////////////////////////////////////////////////////////////////////
+
+impl serde::Serialize for Value {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        match self {
+            Value::Bool(v) => serializer.serialize_bool(*v),
+            Value::I8(v) => serializer.serialize_i8(*v),
+            Value::I16(v) => serializer.serialize_i16(*v),
+            Value::I32(v) => serializer.serialize_i32(*v),
+            Value::I64(v) => serializer.serialize_i64(*v),
+            Value::I128(v) => serializer.serialize_i128(*v),
+            Value::U8(v) => serializer.serialize_u8(*v),
+            Value::U16(v) => serializer.serialize_u16(*v),
+            Value::U32(v) => serializer.serialize_u32(*v),
+            Value::U64(v) => serializer.serialize_u64(*v),
+            Value::U128(v) => serializer.serialize_u128(*v),
+            Value::F32(v) => serializer.serialize_f32(*v),
+            Value::F64(v) => serializer.serialize_f64(*v),
+            Value::Char(v) => serializer.serialize_char(*v),
+            Value::Str(v) => serializer.serialize_str(v),
+            Value::Bytes(v) => serializer.serialize_bytes(v),
+            Value::None => serializer.serialize_none(),
+            Value::Some(value) => serializer.serialize_some(&**value),
+            Value::Unit => serializer.serialize_unit(),
+            Value::UnitStruct(name) => serializer.serialize_unit_struct(name),
+            Value::UnitVariant {
+                name,
+                variant_index,
+                variant,
+            } => serializer.serialize_unit_variant(name, *variant_index, variant),
+            Value::NewtypeStruct(name, value) => {
+                serializer.serialize_newtype_struct(name, &**value)
+            }
+            Value::NewtypeVariant {
+                name,
+                variant_index,
+                variant,
+                value,
+            } => serializer.serialize_newtype_variant(name, *variant_index, variant, &**value),
+            Value::Seq { len, elements } => {
+                let mut seq = serializer.serialize_seq(*len)?;
+                for element in elements {
+                    seq.serialize_element(element)?;
+                }
+                seq.end()
+            }
+            Value::Tuple(elements) => {
+                let mut tuple = serializer.serialize_tuple(elements.len())?;
+                for element in elements {
+                    tuple.serialize_element(element)?;
+                }
+                tuple.end()
+            }
+            Value::TupleStruct { name, fields } => {
+                let mut tuple_struct = serializer.serialize_tuple_struct(name, fields.len())?;
+                for field in fields {
+                    tuple_struct.serialize_field(field)?;
+                }
+                tuple_struct.end()
+            }
+            Value::TupleVariant {
+                name,
+                variant_index,
+                variant,
+                fields,
+            } => {
+                let mut tuple_variant = serializer.serialize_tuple_variant(
+                    name,
+                    *variant_index,
+                    variant,
+                    fields.len(),
+                )?;
+                for field in fields {
+                    tuple_variant.serialize_field(field)?;
+                }
+                tuple_variant.end()
+            }
+            Value::Map { len, entries } => {
+                serialize_entries(serializer.serialize_map(*len)?, entries)
+            }
+            Value::Struct { name, len, fields } => {
+                let state = serializer.serialize_struct(name, *len)?;
+                serialize_struct_fields(state, fields)
+            }
+            Value::StructVariant {
+                name,
+                variant_index,
+                variant,
+                len,
+                fields,
+            } => {
+                let state =
+                    serializer.serialize_struct_variant(name, *variant_index, variant, *len)?;
+                serialize_struct_variant_fields(state, fields)
+            }
+        }
+    }
+}
+
+fn serialize_entries<M>(mut map: M, entries: &[MapEntry]) -> Result<M::Ok, M::Error>
+where
+    M: serde::ser::SerializeMap,
+{
+    for entry in entries {
+        match entry {
+            MapEntry::Key(key) => map.serialize_key(key)?,
+            MapEntry::Value(value) => map.serialize_value(value)?,
+            MapEntry::Entry(key, value) => map.serialize_entry(key, value)?,
+        }
+    }
+    map.end()
+}
+
+fn serialize_struct_fields<S>(mut state: S, fields: &[StructField]) -> Result<S::Ok, S::Error>
+where
+    S: serde::ser::SerializeStruct,
+{
+    for field in fields {
+        match field {
+            StructField::Field(key, value) => state.serialize_field(key, value)?,
+            StructField::Skip(key) => state.skip_field(key)?,
+        }
+    }
+    state.end()
+}
+
+fn serialize_struct_variant_fields<S>(
+    mut state: S,
+    fields: &[StructField],
+) -> Result<S::Ok, S::Error>
+where
+    S: serde::ser::SerializeStructVariant,
+{
+    for field in fields {
+        match field {
+            StructField::Field(key, value) => state.serialize_field(key, value)?,
+            StructField::Skip(key) => state.skip_field(key)?,
+        }
+    }
+    state.end()
+}
+
+// DESERIALIZER ////////////////////////////////////////////////////////////////
+
+impl<'de> serde::Deserializer<'de> for Value {
+    type Error = Error;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: serde::de::Visitor<'de>,
+    {
+        match self {
+            Value::Bool(v) => visitor.visit_bool(v),
+            Value::I8(v) => visitor.visit_i8(v),
+            Value::I16(v) => visitor.visit_i16(v),
+            Value::I32(v) => visitor.visit_i32(v),
+            Value::I64(v) => visitor.visit_i64(v),
+            Value::I128(v) => visitor.visit_i128(v),
+            Value::U8(v) => visitor.visit_u8(v),
+            Value::U16(v) => visitor.visit_u16(v),
+            Value::U32(v) => visitor.visit_u32(v),
+            Value::U64(v) => visitor.visit_u64(v),
+            Value::U128(v) => visitor.visit_u128(v),
+            Value::F32(v) => visitor.visit_f32(v),
+            Value::F64(v) => visitor.visit_f64(v),
+            Value::Char(v) => visitor.visit_char(v),
+            Value::Str(v) => visitor.visit_string(v),
+            Value::Bytes(v) => visitor.visit_byte_buf(v),
+            Value::None => visitor.visit_none(),
+            Value::Some(value) => visitor.visit_some(*value),
+            Value::Unit | Value::UnitStruct(_) => visitor.visit_unit(),
+            Value::NewtypeStruct(_, value) => visitor.visit_newtype_struct(*value),
+            Value::Seq { elements, .. }
+            | Value::Tuple(elements)
+            | Value::TupleStruct {
+                fields: elements, ..
+            } => visit_seq(elements, visitor),
+            Value::Map { entries, .. } => visit_map(pair_entries(entries)?, visitor),
+            Value::Struct { fields, .. } => visit_struct(fields, visitor),
+            Value::UnitVariant { .. }
+            | Value::NewtypeVariant { .. }
+            | Value::TupleVariant { .. }
+            | Value::StructVariant { .. } => visitor.visit_enum(EnumDeserializer(self)),
+        }
+    }
+
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: serde::de::Visitor<'de>,
+    {
+        match self {
+            Value::None | Value::Unit => visitor.visit_none(),
+            Value::Some(value) => visitor.visit_some(*value),
+            value => visitor.visit_some(value),
+        }
+    }
+
+    fn deserialize_newtype_struct<V>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error>
+    where
+        V: serde::de::Visitor<'de>,
+    {
+        match self {
+            Value::NewtypeStruct(_, value) => visitor.visit_newtype_struct(*value),
+            value => visitor.visit_newtype_struct(value),
+        }
+    }
+
+    fn deserialize_enum<V>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error>
+    where
+        V: serde::de::Visitor<'de>,
+    {
+        match self {
+            Value::Str(variant) => visitor.visit_enum(variant.into_deserializer()),
+            Value::UnitVariant { .. }
+            | Value::NewtypeVariant { .. }
+            | Value::TupleVariant { .. }
+            | Value::StructVariant { .. } => visitor.visit_enum(EnumDeserializer(self)),
+            other => Err(serde::de::Error::invalid_type(
+                other.unexpected(),
+                &"enum variant",
+            )),
+        }
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct seq tuple tuple_struct map struct
+        identifier ignored_any
+    }
+}
+
+impl IntoDeserializer<'_, Error> for Value {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self {
+        self
+    }
+}
+
+impl Value {
+    fn unexpected(&self) -> Unexpected {
+        match self {
+            Value::Bool(v) => Unexpected::Bool(*v),
+            Value::I8(v) => Unexpected::Signed(i64::from(*v)),
+            Value::I16(v) => Unexpected::Signed(i64::from(*v)),
+            Value::I32(v) => Unexpected::Signed(i64::from(*v)),
+            Value::I64(v) => Unexpected::Signed(*v),
+            Value::U8(v) => Unexpected::Unsigned(u64::from(*v)),
+            Value::U16(v) => Unexpected::Unsigned(u64::from(*v)),
+            Value::U32(v) => Unexpected::Unsigned(u64::from(*v)),
+            Value::U64(v) => Unexpected::Unsigned(*v),
+            Value::I128(_) | Value::U128(_) => Unexpected::Other("128-bit integer"),
+            Value::F32(v) => Unexpected::Float(f64::from(*v)),
+            Value::F64(v) => Unexpected::Float(*v),
+            Value::Char(v) => Unexpected::Char(*v),
+            Value::Str(v) => Unexpected::Str(v),
+            Value::Bytes(v) => Unexpected::Bytes(v),
+            Value::None | Value::Some(_) => Unexpected::Option,
+            Value::Unit | Value::UnitStruct(_) => Unexpected::Unit,
+            Value::UnitVariant { .. } => Unexpected::UnitVariant,
+            Value::NewtypeStruct(..) => Unexpected::NewtypeStruct,
+            Value::NewtypeVariant { .. } => Unexpected::NewtypeVariant,
+            Value::Seq { .. } | Value::Tuple(_) | Value::TupleStruct { .. } => Unexpected::Seq,
+            Value::TupleVariant { .. } => Unexpected::TupleVariant,
+            Value::Map { .. } | Value::Struct { .. } => Unexpected::Map,
+            Value::StructVariant { .. } => Unexpected::StructVariant,
+        }
+    }
+}
+
+fn visit_seq<'de, V>(elements: Vec<Value>, visitor: V) -> Result<V::Value, Error>
+where
+    V: serde::de::Visitor<'de>,
+{
+    let mut seq = SeqDeserializer::new(elements.into_iter());
+    let value = visitor.visit_seq(&mut seq)?;
+    seq.end()?;
+    Ok(value)
+}
+
+fn visit_map<'de, V>(entries: Vec<(Value, Value)>, visitor: V) -> Result<V::Value, Error>
+where
+    V: serde::de::Visitor<'de>,
+{
+    let mut map = MapDeserializer::new(entries.into_iter());
+    let value = visitor.visit_map(&mut map)?;
+    map.end()?;
+    Ok(value)
+}
+
+fn visit_struct<'de, V>(fields: Vec<StructField>, visitor: V) -> Result<V::Value, Error>
+where
+    V: serde::de::Visitor<'de>,
+{
+    let present = fields.into_iter().filter_map(|field| match field {
+        StructField::Field(key, value) => Some((key, value)),
+        StructField::Skip(_) => None,
+    });
+    let mut map = MapDeserializer::new(present);
+    let value = visitor.visit_map(&mut map)?;
+    map.end()?;
+    Ok(value)
+}
+
+// Keys and values may have been captured through separate `serialize_key` and
+// `serialize_value` calls. Line them back up into pairs.
+fn pair_entries(entries: Vec<MapEntry>) -> Result<Vec<(Value, Value)>, Error> {
+    let mut pairs = Vec::with_capacity(entries.len());
+    let mut pending_key = None;
+    for entry in entries {
+        match (entry, pending_key.take()) {
+            (MapEntry::Key(key), None) => pending_key = Some(key),
+            (MapEntry::Value(value), Some(key)) | (MapEntry::Entry(key, value), None) => {
+                pairs.push((key, value));
+            }
+            (MapEntry::Key(_) | MapEntry::Entry(..), Some(_)) => {
+                return Err(serde::de::Error::custom("map key without a value"));
+            }
+            (MapEntry::Value(_), None) => {
+                return Err(serde::de::Error::custom("map value without a key"));
+            }
+        }
+    }
+    if pending_key.is_some() {
+        return Err(serde::de::Error::custom("map key without a value"));
+    }
+    Ok(pairs)
+}
+
+struct EnumDeserializer(Value);
+
+impl<'de> serde::de::EnumAccess<'de> for EnumDeserializer {
+    type Error = Error;
+    type Variant = Self;
+
+    fn variant_seed<V>(self, seed: V) -> Result<(V::Value, Self), Error>
+    where
+        V: serde::de::DeserializeSeed<'de>,
+    {
+        let variant = match &self.0 {
+            Value::UnitVariant { variant, .. }
+            | Value::NewtypeVariant { variant, .. }
+            | Value::TupleVariant { variant, .. }
+            | Value::StructVariant { variant, .. } => *variant,
+            _ => unreachable!(),
+        };
+        let variant = seed.deserialize(BorrowedStrDeserializer::new(variant))?;
+        Ok((variant, self))
+    }
+}
+
+impl<'de> serde::de::VariantAccess<'de> for EnumDeserializer {
+    type Error = Error;
+
+    fn unit_variant(self) -> Result<(), Error> {
+        match self.0 {
+            Value::UnitVariant { .. } => Ok(()),
+            other => Err(serde::de::Error::invalid_type(
+                other.unexpected(),
+                &"unit variant",
+            )),
+        }
+    }
+
+    fn newtype_variant_seed<T>(self, seed: T) -> Result<T::Value, Error>
+    where
+        T: serde::de::DeserializeSeed<'de>,
+    {
+        match self.0 {
+            Value::NewtypeVariant { value, .. } => seed.deserialize(*value),
+            other => Err(serde::de::Error::invalid_type(
+                other.unexpected(),
+                &"newtype variant",
+            )),
+        }
+    }
+
+    fn tuple_variant<V>(self, _len: usize, visitor: V) -> Result<V::Value, Error>
+    where
+        V: serde::de::Visitor<'de>,
+    {
+        match self.0 {
+            Value::TupleVariant { fields, .. } => visit_seq(fields, visitor),
+            other => Err(serde::de::Error::invalid_type(
+                other.unexpected(),
+                &"tuple variant",
+            )),
+        }
+    }
+
+    fn struct_variant<V>(
+        self,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error>
+    where
+        V: serde::de::Visitor<'de>,
+    {
+        match self.0 {
+            Value::StructVariant { fields, .. } => visit_struct(fields, visitor),
+            other => Err(serde::de::Error::invalid_type(
+                other.unexpected(),
+                &"struct variant",
+            )),
+        }
+    }
+}
+
+// TEST ////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::borrow::ToOwned;
+    use alloc::collections::BTreeMap;
+    use alloc::vec;
+    use serde::Deserialize as _;
+    use serde_derive::Serialize;
+
+    fn test_json<T>(t: T)
+    where
+        T: serde::Serialize,
+    {
+        let expected = serde_json::to_vec(&t).unwrap();
+        let obj: Box<dyn Serialize> = Box::new(t);
+        let value = to_value(&obj).unwrap();
+        drop(obj);
+        assert_eq!(serde_json::to_vec(&value).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_primitives() {
+        test_json(true);
+        test_json(-1i8);
+        test_json(u128::MAX);
+        test_json(1.5f64);
+        test_json('x');
+        test_json("s");
+        test_json(Some(1));
+        test_json(None::<u8>);
+        test_json(());
+    }
+
+    #[test]
+    fn test_compound() {
+        let mut map = BTreeMap::new();
+        map.insert("k", vec![(1, "a")]);
+        test_json(map);
+    }
+
+    #[test]
+    fn test_struct() {
+        #[derive(Serialize)]
+        struct S {
+            f: usize,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            skipped: Option<u8>,
+        }
+
+        let value = to_value(&S {
+            f: 1,
+            skipped: None,
+        })
+        .unwrap();
+        assert_eq!(
+            value,
+            Value::Struct {
+                name: "S",
+                len: 1,
+                fields: vec![
+                    StructField::Field("f", Value::U64(1)),
+                    StructField::Skip("skipped"),
+                ],
+            },
+        );
+        test_json(S {
+            f: 1,
+            skipped: Some(2),
+        });
+    }
+
+    #[test]
+    fn test_enum() {
+        #[derive(Serialize)]
+        enum E {
+            Unit,
+            Newtype(bool),
+            Tuple(bool, bool),
+            Struct { t: bool, f: bool },
+        }
+
+        test_json(E::Unit);
+        test_json(E::Newtype(true));
+        test_json(E::Tuple(true, false));
+        test_json(E::Struct { t: true, f: false });
+    }
+
+    fn test_roundtrip<T>(t: T)
+    where
+        T: serde::Serialize + serde::de::DeserializeOwned + PartialEq + core::fmt::Debug,
+    {
+        let value = to_value(&t).unwrap();
+        let de: &mut dyn crate::Deserializer = &mut <dyn crate::Deserializer>::erase(value);
+        assert_eq!(t, crate::deserialize::<T>(de).unwrap());
+    }
+
+    #[test]
+    fn test_deserialize() {
+        #[derive(serde_derive::Serialize, serde_derive::Deserialize, PartialEq, Debug)]
+        enum E {
+            Unit,
+            Newtype(i128),
+            Tuple(u8, char),
+            Struct {
+                #[serde(with = "bytes")]
+                b: Vec<u8>,
+                #[serde(skip_serializing_if = "Option::is_none")]
+                o: Option<bool>,
+            },
+        }
+
+        mod bytes {
+            use alloc::vec::Vec;
+
+            pub fn serialize<S: serde::Serializer>(b: &[u8], s: S) -> Result<S::Ok, S::Error> {
+                s.serialize_bytes(b)
+            }
+
+            pub fn deserialize<'de, D: serde::Deserializer<'de>>(
+                d: D,
+            ) -> Result<Vec<u8>, D::Error> {
+                struct BytesVisitor;
+
+                impl serde::de::Visitor<'_> for BytesVisitor {
+                    type Value = Vec<u8>;
+
+                    fn expecting(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+                        f.write_str("byte buf")
+                    }
+
+                    fn visit_byte_buf<E>(self, v: Vec<u8>) -> Result<Vec<u8>, E> {
+                        Ok(v)
+                    }
+                }
+
+                d.deserialize_byte_buf(BytesVisitor)
+            }
+        }
+
+        test_roundtrip(E::Unit);
+        test_roundtrip(E::Newtype(i128::MIN));
+        test_roundtrip(E::Tuple(1, 'x'));
+        test_roundtrip(E::Struct {
+            b: vec![0, 255],
+            o: None,
+        });
+        test_roundtrip(vec![Some(1u64), None]);
+        test_roundtrip(BTreeMap::from([(1u8, "a".to_owned())]));
+    }
+
+    #[test]
+    fn test_deserialize_error() {
+        let value = to_value(&"str").unwrap();
+        let err =
+            crate::deserialize::<u8>(&mut <dyn crate::Deserializer>::erase(value)).unwrap_err();
+        assert_eq!(err.to_string(), "invalid type: string \"str\", expected u8",);
+    }
+
+    #[test]
+    fn test_deserialize_struct() {
+        #[derive(Serialize)]
+        struct Dto {
+            a: u8,
+        }
+
+        #[derive(serde_derive::Deserialize, PartialEq, Debug)]
+        struct Domain {
+            a: u32,
+            b: u32,
+        }
+
+        #[derive(serde_derive::Deserialize, PartialEq, Debug)]
+        struct Partial {
+            a: u32,
+        }
+
+        let value = to_value(&Dto { a: 1 }).unwrap();
+        assert_eq!(Partial::deserialize(value).unwrap(), Partial { a: 1 });
+
+        let value = to_value(&Dto { a: 1 }).unwrap();
+        let err = Domain::deserialize(value).unwrap_err();
+        assert_eq!(err.to_string(), "missing field `b`");
+    }
+
+    #[test]
+    fn test_send_sync() {
+        fn assert<T: Send + Sync + 'static>() {}
+        assert::<Value>();
+    }
+}
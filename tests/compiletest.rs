@@ -5,3 +5,12 @@ fn ui() {
     let t = trybuild::TestCases::new();
     t.compile_fail("tests/ui/*.rs");
 }
+
+#[cfg(feature = "macros")]
+#[rustversion::attr(not(nightly), ignore = "requires nightly")]
+#[cfg_attr(miri, ignore = "incompatible with miri")]
+#[test]
+fn ui_macros() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/ui-macros/*.rs");
+}
@@ -0,0 +1,84 @@
+#![cfg(feature = "macros")]
+
+use serde_derive::Serialize;
+use serde_json::json;
+use std::fmt::Display;
+
+fn assert_serialize<T: ?Sized + serde::Serialize>() {}
+
+#[derive(Serialize)]
+struct Login {
+    user: String,
+}
+
+#[test]
+fn test_plain() {
+    #[erased_serde::serializable]
+    trait Event {}
+
+    impl Event for Login {}
+
+    assert_serialize::<dyn Event>();
+    assert_serialize::<dyn Event + Send>();
+    assert_serialize::<dyn Event + Sync>();
+    assert_serialize::<dyn Event + Send + Sync>();
+
+    let event: Box<dyn Event> = Box::new(Login {
+        user: "alice".to_owned(),
+    });
+    let value = serde_json::to_value(&event).unwrap();
+    assert_eq!(value, json!({"user": "alice"}));
+}
+
+#[test]
+fn test_explicit_supertrait() {
+    #[erased_serde::serializable]
+    trait Event: erased_serde::Serialize + Display {}
+
+    assert_serialize::<dyn Event>();
+}
+
+#[test]
+fn test_imported_supertrait() {
+    mod event {
+        use erased_serde::Serialize;
+
+        #[erased_serde::serializable]
+        #[deny(clippy::trait_duplication_in_bounds)]
+        pub trait Event: Serialize {}
+    }
+
+    assert_serialize::<dyn event::Event>();
+}
+
+#[test]
+fn test_generics() {
+    #[erased_serde::serializable]
+    #[allow(dead_code)]
+    trait Handler<'a, T: Clone + 'a, const N: usize = 1>
+    where
+        T: Default,
+        Self: 'a,
+    {
+        type Output: Display;
+        type Input<'b>
+        where
+            Self: Sized;
+
+        fn handle(&self, input: &'a T) -> [Self::Output; N];
+    }
+
+    assert_serialize::<dyn Handler<'static, u8, Output = String>>();
+    assert_serialize::<dyn Handler<'static, u8, 2, Output = String> + Send>();
+}
+
+#[test]
+fn test_auto_traits() {
+    #[erased_serde::serializable(auto_traits(Send + Sync, Unpin, std::panic::UnwindSafe))]
+    trait Event {}
+
+    assert_serialize::<dyn Event>();
+    assert_serialize::<dyn Event + Send + Sync>();
+    assert_serialize::<dyn Event + Unpin>();
+    assert_serialize::<dyn Event + std::panic::UnwindSafe>();
+}
@@ -7,3 +7,9 @@ serialize_trait_object!(MyTrait);
 pub trait MyGenericTrait<'a, T>: erased_serde::Serialize {}
 
 serialize_trait_object!(<'a, T> MyGenericTrait<'a, T>);
+
+pub trait MyHandler<'a>: erased_serde::Serialize {
+    type Output;
+}
+
+serialize_trait_object!(#[auto_traits(Send + Unpin)] <'a> MyHandler<'a, Output = ()>);
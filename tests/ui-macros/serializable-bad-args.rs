@@ -0,0 +1,7 @@
+#[erased_serde::serializable(auto_trait(Send))]
+trait Event {}
+
+#[erased_serde::serializable(auto_traits(Send) Sync)]
+trait Other {}
+
+fn main() {}
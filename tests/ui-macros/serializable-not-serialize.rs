@@ -0,0 +1,8 @@
+#[erased_serde::serializable]
+trait Event {}
+
+struct Login;
+
+impl Event for Login {}
+
+fn main() {}
@@ -0,0 +1,4 @@
+#[erased_serde::serializable]
+struct Event {}
+
+fn main() {}
@@ -0,0 +1,12 @@
+use erased_serde::serialize_trait_object;
+
+pub trait MyTrait: erased_serde::Serialize {}
+
+serialize_trait_object!(#[auto_traits(Send)] MyTrait);
+
+fn assert_serialize<T: ?Sized + serde::Serialize>() {}
+
+fn main() {
+    assert_serialize::<dyn MyTrait + Send>();
+    assert_serialize::<dyn MyTrait + Sync>();
+}
@@ -0,0 +1,7 @@
+use erased_serde::serialize_trait_object;
+
+pub trait MyTrait: erased_serde::Serialize {}
+
+serialize_trait_object!(#[auto_traits(Send + Clone)] MyTrait);
+
+fn main() {}
@@ -0,0 +1,19 @@
+use erased_serde::custom::{Deserializer, Out, Visit};
+use erased_serde::Error;
+
+struct Replay {
+    previous: Option<Out<'static>>,
+}
+
+impl<'de> Deserializer<'de> for Replay {
+    fn erased_deserialize_any<'a>(&mut self, visitor: Visit<'a, 'de>) -> Result<Out<'a>, Error> {
+        if let Some(out) = self.previous.take() {
+            return Ok(out);
+        }
+        let out = visitor.visit_unit()?;
+        self.previous = Some(out);
+        unimplemented!()
+    }
+}
+
+fn main() {}
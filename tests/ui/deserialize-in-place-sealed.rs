@@ -0,0 +1,14 @@
+use erased_serde::{DeserializeInPlace, Deserializer, Error};
+
+struct Reloadable;
+
+impl<'de> DeserializeInPlace<'de> for Reloadable {
+    fn erased_deserialize_in_place(
+        &mut self,
+        _deserializer: &mut dyn Deserializer<'de>,
+    ) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+fn main() {}